@@ -0,0 +1,260 @@
+/**
+ * SpiteStack Attachments Module
+ *
+ * Content-addressed blob storage for file uploads. Command handlers that take
+ * `Attachment` parameters stream uploaded files here and hand the command a
+ * small reference object instead, so binary data never ends up in an event
+ * payload.
+ *
+ * Blobs are shared by every upload of the same bytes, so nothing is deleted
+ * when a command fails. `collectAttachmentGarbage` removes blobs no event
+ * references instead.
+ */
+
+import { mkdir, readdir, rename, stat, unlink, utimes } from 'node:fs/promises';
+import { readGlobalFrom, type EventLog } from './event-log';
+
+/**
+ * Reference to a stored upload. This is what commands (and events) see.
+ */
+export type Attachment = {
+  hash: string; // sha256 of the content, hex encoded
+  size: number;
+  contentType: string;
+  filename?: string;
+};
+
+/**
+ * How a multipart field maps onto a command parameter.
+ * 'one' - single Attachment, 'many' - Attachment[],
+ * 'text' - kept as a string, 'json' - JSON-decoded (numbers, booleans, objects)
+ */
+export type AttachmentFieldSpec = Record<string, 'one' | 'many' | 'text' | 'json'>;
+
+export class AttachmentTooLargeError extends Error {
+  constructor(public readonly field: string, public readonly maxBytes: number) {
+    super(`Attachment '${field}' exceeds the ${maxBytes} byte limit`);
+    this.name = 'AttachmentTooLargeError';
+  }
+}
+
+const DEFAULT_MAX_BYTES = 25 * 1024 * 1024;
+// Uploads younger than this may belong to a command that has not appended yet
+const DEFAULT_GC_GRACE_MS = 60 * 60 * 1000;
+const HASH_PATTERN = /^[0-9a-f]{64}$/;
+// Tenant ids become a directory name, so they may not contain path separators or dots
+const TENANT_PATTERN = /^[A-Za-z0-9][A-Za-z0-9_-]*$/;
+
+function blobRoot(): string {
+  return process.env.ATTACHMENT_DATA_DIR || './data/blobs';
+}
+
+function blobDir(tenant: string): string {
+  if (!TENANT_PATTERN.test(tenant)) {
+    throw new Error(`Invalid tenant id for attachments: '${tenant}'`);
+  }
+  return `${blobRoot()}/${tenant}`;
+}
+
+function maxBytes(): number {
+  const configured = Number(process.env.ATTACHMENT_MAX_BYTES);
+  return Number.isFinite(configured) && configured > 0 ? configured : DEFAULT_MAX_BYTES;
+}
+
+/**
+ * Stream a file into the tenant's blob directory.
+ *
+ * The content is hashed while it is written to a temp file and then renamed
+ * to its hash, so identical uploads are stored once.
+ */
+export async function storeAttachment(file: Blob, tenant: string, field = 'file'): Promise<Attachment> {
+  const dir = blobDir(tenant);
+  await mkdir(dir, { recursive: true });
+
+  const limit = maxBytes();
+  const tempPath = `${dir}/.upload-${crypto.randomUUID()}`;
+  const hasher = new Bun.CryptoHasher('sha256');
+  const writer = Bun.file(tempPath).writer();
+  let size = 0;
+
+  try {
+    for await (const chunk of file.stream()) {
+      size += chunk.byteLength;
+      if (size > limit) {
+        throw new AttachmentTooLargeError(field, limit);
+      }
+      hasher.update(chunk);
+      writer.write(chunk);
+    }
+    await writer.end();
+  } catch (err) {
+    await writer.end();
+    await unlink(tempPath).catch(() => {});
+    throw err;
+  }
+
+  const hash = hasher.digest('hex');
+  // Replacing an existing blob also refreshes its mtime for the GC grace period
+  await rename(tempPath, `${dir}/${hash}`);
+
+  return {
+    hash,
+    size,
+    contentType: file.type || 'application/octet-stream',
+    filename: file instanceof File && file.name ? file.name : undefined,
+  };
+}
+
+/**
+ * Convert a multipart form into a command body.
+ *
+ * Attachment fields are stored and replaced with their references. Other
+ * fields are decoded according to `spec`; unknown fields are left as-is and
+ * rejected later by the validator if they matter.
+ */
+export async function storeAttachments(
+  form: FormData,
+  spec: AttachmentFieldSpec,
+  tenant: string
+): Promise<Record<string, unknown>> {
+  const body: Record<string, unknown> = {};
+
+  for (const key of new Set(form.keys())) {
+    const values = form.getAll(key);
+    const mode = spec[key];
+
+    if (mode === 'one' || mode === 'many') {
+      const refs: Attachment[] = [];
+      for (const value of values) {
+        if (typeof value !== 'string') {
+          refs.push(await storeAttachment(value, tenant, key));
+        }
+      }
+      body[key] = mode === 'many' ? refs : refs[0];
+      continue;
+    }
+
+    const value = values[values.length - 1];
+    body[key] = typeof value === 'string' && mode === 'json' ? decodeField(value) : value;
+  }
+
+  return body;
+}
+
+/**
+ * Remove blobs that no event references.
+ *
+ * Scans the whole event log for attachment references, then deletes every
+ * blob (and abandoned temp upload) not referenced by any tenant and older
+ * than the grace period (`ATTACHMENT_GC_GRACE_MS`, default one hour), which
+ * covers uploads whose command is still running. Returns the number of files
+ * removed.
+ */
+export async function collectAttachmentGarbage(db: EventLog, graceMs = gcGraceMs()): Promise<number> {
+  const root = blobRoot();
+  const tenants = await readdir(root).catch(() => [] as string[]);
+  if (tenants.length === 0) return 0;
+
+  // Hashes are content addresses, so a hash referenced anywhere is kept everywhere
+  const referenced = new Set<string>();
+  for await (const events of readGlobalFrom(db, 0)) {
+    for (const event of events) {
+      try {
+        collectHashes(JSON.parse(event.data.toString()), referenced);
+      } catch {
+        // Not JSON, so it cannot hold a reference
+      }
+    }
+  }
+
+  const cutoff = Date.now() - graceMs;
+  let removed = 0;
+  for (const tenant of tenants) {
+    if (!TENANT_PATTERN.test(tenant)) continue;
+    const dir = `${root}/${tenant}`;
+    for (const name of await readdir(dir).catch(() => [] as string[])) {
+      const keep = HASH_PATTERN.test(name) ? referenced.has(name) : !name.startsWith('.upload-');
+      if (keep) continue;
+      const path = `${dir}/${name}`;
+      const info = await stat(path).catch(() => null);
+      if (!info || info.mtimeMs > cutoff) continue;
+      await unlink(path).then(
+        () => removed++,
+        () => {}
+      );
+    }
+  }
+
+  return removed;
+}
+
+/**
+ * Check the attachment references of a JSON command body.
+ *
+ * References can only name blobs already uploaded to the same tenant. Returns
+ * the first field whose reference does not resolve, or null. Values that are
+ * not references are left to the validator.
+ */
+export async function findMissingAttachment(
+  body: unknown,
+  spec: AttachmentFieldSpec,
+  tenant: string
+): Promise<string | null> {
+  if (typeof body !== 'object' || body === null) return null;
+
+  for (const [key, mode] of Object.entries(spec)) {
+    if (mode !== 'one' && mode !== 'many') continue;
+    const value = (body as Record<string, unknown>)[key];
+    const refs = mode === 'many' && Array.isArray(value) ? value : [value];
+    for (const ref of refs) {
+      if (typeof ref !== 'object' || ref === null || typeof (ref as Attachment).hash !== 'string') continue;
+      const hash = (ref as Attachment).hash;
+      if (!(await openAttachment(tenant, hash))) return key;
+      // Keep the blob out of the next GC pass until the command has appended
+      const now = new Date();
+      await utimes(`${blobDir(tenant)}/${hash}`, now, now).catch(() => {});
+    }
+  }
+
+  return null;
+}
+
+/**
+ * Look up a stored attachment by hash. Returns null if it does not exist.
+ */
+export async function openAttachment(tenant: string, hash: string) {
+  if (!HASH_PATTERN.test(hash) || !TENANT_PATTERN.test(tenant)) return null;
+  const file = Bun.file(`${blobDir(tenant)}/${hash}`);
+  return (await file.exists()) ? file : null;
+}
+
+/**
+ * Whether a request carries a multipart body.
+ */
+export function isMultipart(req: Request): boolean {
+  return (req.headers.get('Content-Type') || '').startsWith('multipart/form-data');
+}
+
+function gcGraceMs(): number {
+  const configured = Number(process.env.ATTACHMENT_GC_GRACE_MS);
+  return Number.isFinite(configured) && configured >= 0 ? configured : DEFAULT_GC_GRACE_MS;
+}
+
+/** Adds the hash of every attachment reference in an event payload. */
+function collectHashes(value: unknown, hashes: Set<string>): void {
+  if (Array.isArray(value)) {
+    for (const item of value) collectHashes(item, hashes);
+  } else if (typeof value === 'object' && value !== null) {
+    const hash = (value as Partial<Attachment>).hash;
+    if (typeof hash === 'string' && HASH_PATTERN.test(hash)) hashes.add(hash);
+    for (const item of Object.values(value)) collectHashes(item, hashes);
+  }
+}
+
+function decodeField(value: string): unknown {
+  try {
+    return JSON.parse(value);
+  } catch {
+    return value;
+  }
+}
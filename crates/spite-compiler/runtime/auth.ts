@@ -4,6 +4,8 @@
  * Provides JWT-based authentication middleware for Bun handlers.
  */
 
+import type { SessionConfig } from './session';
+
 export type AuthConfig = {
   secret: string;
   issuer?: string;
@@ -11,8 +13,31 @@ export type AuthConfig = {
   expiresIn?: number; // seconds, default 900 (15min)
   refreshExpiresIn?: number; // seconds, default 604800 (7d)
   maxSessionDuration?: number; // seconds, default 2592000 (30d) - Absolute max session life
+  session?: SessionConfig; // When set, the router authenticates with server-side sessions instead of JWTs
+  keySource?: KeySource; // Verifies incoming tokens; defaults to HS256 with `secret`. Issued tokens always use `secret`.
+};
+
+export type JwtHeader = {
+  alg: string;
+  kid?: string;
+  typ?: string;
+};
+
+export type VerifyKey = {
+  key: CryptoKey;
+  algorithm: AlgorithmIdentifier | EcdsaParams;
 };
 
+/**
+ * Resolves the key that verifies a token.
+ *
+ * Returning null rejects the token, so a source only accepts the
+ * algorithms it knows how to check.
+ */
+export interface KeySource {
+  getVerifyKey(header: JwtHeader): Promise<VerifyKey | null>;
+}
+
 export type AuthUser = {
   sub: string;
   sid?: string; // Session ID
@@ -67,6 +92,106 @@ export function resolveTenant(req: Request, user: AuthUser): { ok: true; tenant:
   return { ok: false, error: 'Tenant ID required (X-Tenant-ID header)', status: 400 };
 }
 
+/**
+ * Key source for HS256 tokens signed with a shared secret.
+ */
+export function secretKeySource(secret: string): KeySource {
+  let cachedKey: CryptoKey | null = null;
+
+  return {
+    async getVerifyKey(header) {
+      if (header.alg !== 'HS256') return null;
+      if (!cachedKey) {
+        cachedKey = await crypto.subtle.importKey(
+          'raw',
+          new TextEncoder().encode(secret),
+          { name: 'HMAC', hash: 'SHA-256' },
+          false,
+          ['verify']
+        );
+      }
+      return { key: cachedKey, algorithm: 'HMAC' };
+    },
+  };
+}
+
+const JWK_ALGORITHMS: Record<string, { import: RsaHashedImportParams | EcKeyImportParams; verify: AlgorithmIdentifier | EcdsaParams }> = {
+  RS256: { import: { name: 'RSASSA-PKCS1-v1_5', hash: 'SHA-256' }, verify: 'RSASSA-PKCS1-v1_5' },
+  RS384: { import: { name: 'RSASSA-PKCS1-v1_5', hash: 'SHA-384' }, verify: 'RSASSA-PKCS1-v1_5' },
+  RS512: { import: { name: 'RSASSA-PKCS1-v1_5', hash: 'SHA-512' }, verify: 'RSASSA-PKCS1-v1_5' },
+  ES256: { import: { name: 'ECDSA', namedCurve: 'P-256' }, verify: { name: 'ECDSA', hash: 'SHA-256' } },
+  ES384: { import: { name: 'ECDSA', namedCurve: 'P-384' }, verify: { name: 'ECDSA', hash: 'SHA-384' } },
+};
+
+/**
+ * Key source for tokens from an external identity provider, verified
+ * against its JWKS endpoint (RS256/384/512, ES256/384).
+ *
+ * Keys are cached for `cacheTtlMs`; an unknown `kid` refetches the set, at
+ * most once every `minRefreshMs`, so key rotation is picked up without
+ * letting bad tokens hammer the provider. Failed fetches count towards that
+ * limit too, and the previous keys stay in use until a fetch succeeds.
+ */
+export function jwksKeySource(
+  url: string,
+  options: { cacheTtlMs?: number; minRefreshMs?: number } = {}
+): KeySource {
+  const cacheTtlMs = options.cacheTtlMs ?? 10 * 60 * 1000;
+  const minRefreshMs = options.minRefreshMs ?? 30 * 1000;
+  let jwks: Array<JsonWebKey & { kid?: string; alg?: string }> = [];
+  let fetchedAt = 0;
+  let attemptedAt = 0;
+  let inflight: Promise<void> | null = null;
+  const imported = new Map<string, CryptoKey>();
+
+  function refresh(): Promise<void> {
+    // Concurrent requests share one fetch
+    if (!inflight) {
+      // Stamped before awaiting, so a provider outage is retried once per minRefreshMs
+      attemptedAt = Date.now();
+      inflight = (async () => {
+        const res = await fetch(url);
+        if (!res.ok) throw new Error(`JWKS fetch failed: ${res.status}`);
+        const body = (await res.json()) as { keys?: typeof jwks };
+        jwks = body.keys ?? [];
+        fetchedAt = Date.now();
+        imported.clear();
+      })().finally(() => {
+        inflight = null;
+      });
+    }
+    return inflight;
+  }
+
+  return {
+    async getVerifyKey(header) {
+      const params = JWK_ALGORITHMS[header.alg];
+      if (!params) return null;
+
+      const now = Date.now();
+      const find = () => jwks.find(k => (header.kid ? k.kid === header.kid : true) && (!k.alg || k.alg === header.alg));
+      const stale = now - fetchedAt > cacheTtlMs || !find();
+      if (inflight || (stale && now - attemptedAt > minRefreshMs)) {
+        await refresh().catch((err) => {
+          // Keep verifying with the last good keys while the provider is down
+          if (jwks.length === 0) throw err;
+        });
+      }
+
+      const jwk = find();
+      if (!jwk) return null;
+
+      const cacheKey = `${jwk.kid ?? ''}:${header.alg}`;
+      let key = imported.get(cacheKey);
+      if (!key) {
+        key = await crypto.subtle.importKey('jwk', jwk, params.import, false, ['verify']);
+        imported.set(cacheKey, key);
+      }
+      return { key, algorithm: params.verify };
+    },
+  };
+}
+
 /**
  * Creates an auth middleware that validates JWT tokens.
  */
@@ -74,6 +199,8 @@ export function createAuth(config: AuthConfig) {
   const encoder = new TextEncoder();
   const secretKey = encoder.encode(config.secret);
 
+  const keySource = config.keySource ?? secretKeySource(config.secret);
+
   let cachedKey: CryptoKey | null = null;
 
   async function getKey(): Promise<CryptoKey> {
@@ -95,11 +222,14 @@ export function createAuth(config: AuthConfig) {
       if (parts.length !== 3) return { ok: false, error: 'Invalid token format' };
       const [headerB64, payloadB64, signatureB64] = parts;
 
+      const header = JSON.parse(new TextDecoder().decode(base64UrlDecode(headerB64))) as JwtHeader;
+      const verifyKey = await keySource.getVerifyKey(header);
+      if (!verifyKey) return { ok: false, error: 'Unsupported signing key' };
+
       const data = encoder.encode(`${headerB64}.${payloadB64}`);
       const signature = base64UrlDecode(signatureB64);
-      const key = await getKey();
-      
-      const valid = await crypto.subtle.verify('HMAC', key, signature, data);
+
+      const valid = await crypto.subtle.verify(verifyKey.algorithm, verifyKey.key, signature, data);
       if (!valid) return { ok: false, error: 'Invalid signature' };
 
       // Use proper base64url decoding for payload
@@ -37,6 +37,26 @@ export type AuthState = {
 export interface ClientConfig {
   baseUrl: string;
   defaultTenant?: string;
+  conflictRetries?: number; // default 3; commands answered with 409 are re-sent
+}
+
+/**
+ * Encode a command payload as multipart form data. Files become file parts;
+ * strings are sent as-is and other values JSON-encoded, as the server decodes them.
+ */
+export function toFormData(payload: Record<string, unknown>): FormData {
+  const form = new FormData();
+  for (const [key, value] of Object.entries(payload)) {
+    if (value === undefined) continue;
+    if (value instanceof Blob) {
+      form.append(key, value);
+    } else if (Array.isArray(value) && value.length > 0 && value.every(v => v instanceof Blob)) {
+      for (const file of value) form.append(key, file);
+    } else {
+      form.append(key, typeof value === 'string' ? value : JSON.stringify(value));
+    }
+  }
+  return form;
 }
 
 export class SpiteClient {
@@ -44,9 +64,12 @@ export class SpiteClient {
   public defaultTenant?: string;
   public auth: Writable<AuthState>;
   private refreshPromise: Promise<void> | null = null;
+  private conflictRetries: number;
 
   constructor(config: ClientConfig) {
     this.baseUrl = config.baseUrl.replace(/\/$/, '');
+    this.defaultTenant = config.defaultTenant;
+    this.conflictRetries = config.conflictRetries ?? 3;
 
     // Initialize auth state as unauthenticated
     // Will be updated after checking session with server
@@ -161,7 +184,10 @@ export class SpiteClient {
 
   protected async fetch(path: string, options: RequestInit = {}, tenant?: string) {
     const headers = new Headers(options.headers);
-    if (!headers.has('Content-Type')) headers.set('Content-Type', 'application/json');
+    // Multipart bodies get their Content-Type (with boundary) from fetch
+    if (!headers.has('Content-Type') && !(options.body instanceof FormData)) {
+      headers.set('Content-Type', 'application/json');
+    }
 
     const targetTenant = tenant || this.defaultTenant;
     if (targetTenant) headers.set('X-Tenant-ID', targetTenant);
@@ -192,24 +218,55 @@ export class SpiteClient {
     return response;
   }
 
-  async query<T>(aggregate: string, streamId: string, tenant?: string): Promise<{ streamId: string; state: T }> {
+  async query<T>(aggregate: string, streamId: string, tenant?: string): Promise<{ streamId: string; revision: number; state: T }> {
     const res = await this.fetch(`/${aggregate}/${streamId}`, {}, tenant);
     if (!res.ok) throw new Error(`Query failed: ${res.statusText}`);
     return res.json();
   }
 
-  async command<T>(
+  async command<T, E = any>(
     aggregate: string,
     streamId: string,
     command: string,
     payload: unknown,
     tenant?: string
-  ): Promise<{ streamId: string; events: any[]; state: T }> {
-    const res = await this.fetch(`/${aggregate}/${streamId}/${command}`, {
+  ): Promise<{ streamId: string; revision: number; events: E[]; state: T }> {
+    // Upload commands pass FormData (see toFormData); everything else is JSON
+    const body = payload instanceof FormData ? payload : JSON.stringify(payload);
+    let res = await this.fetch(`/${aggregate}/${streamId}/${command}`, {
       method: 'POST',
-      body: JSON.stringify(payload),
+      body,
     }, tenant);
 
+    // 409 means another writer appended first; the server re-reads the stream on each attempt
+    for (let attempt = 1; res.status === 409 && attempt <= this.conflictRetries; attempt++) {
+      await new Promise(resolve => setTimeout(resolve, 25 * 2 ** attempt * (0.5 + Math.random())));
+      res = await this.fetch(`/${aggregate}/${streamId}/${command}`, {
+        method: 'POST',
+        body,
+      }, tenant);
+    }
+
+    if (!res.ok) {
+      const err = await res.json().catch(() => ({ error: res.statusText }));
+      throw new Error(err.error || res.statusText);
+    }
+    return res.json();
+  }
+
+  async projection<T>(
+    projection: string,
+    query: string,
+    params: Record<string, string | number | boolean | undefined> = {},
+    tenant?: string
+  ): Promise<T> {
+    const search = new URLSearchParams();
+    for (const [key, value] of Object.entries(params)) {
+      if (value !== undefined) search.set(key, String(value));
+    }
+    const qs = search.toString();
+    const res = await this.fetch(`/projections/${projection}/${query}${qs ? `?${qs}` : ''}`, {}, tenant);
+
     if (!res.ok) {
       const err = await res.json().catch(() => ({ error: res.statusText }));
       throw new Error(err.error || res.statusText);
@@ -0,0 +1,116 @@
+/**
+ * SpiteStack Event Log Module
+ *
+ * `readGlobal(from, limit)` spans every tenant, and its events carry only a
+ * hash of their tenant. These helpers page through the global log and
+ * attribute events to tenants by reading one event per hash back through the
+ * tenant-scoped `readStream`.
+ */
+
+/** An event as returned by `readGlobal` / `readStream`. */
+export interface LogEvent {
+  globalPos: number | bigint;
+  streamId: string;
+  streamRev: number | bigint;
+  tenantHash: number | bigint;
+  timestampMs: number | bigint;
+  data: Buffer;
+}
+
+/** The parts of `SpiteDbNapi` the helpers need. */
+export interface EventLog {
+  readGlobal(fromPos: number, limit: number): Promise<LogEvent[]>;
+  readStream(streamId: string, fromRev: number, limit: number, tenant: string): Promise<LogEvent[]>;
+}
+
+/** Global position of the newest event, or 0 for an empty log. */
+export async function globalHead(db: EventLog): Promise<number> {
+  const latest = await db.readGlobal(Number.MAX_SAFE_INTEGER - 1000000, 1);
+  return latest.length > 0 ? Number(latest[0].globalPos) : 0;
+}
+
+/**
+ * Read the global log in pages, starting after `afterPos`.
+ */
+export async function* readGlobalFrom(db: EventLog, afterPos: number, batchSize = 1000): AsyncGenerator<LogEvent[]> {
+  let position = afterPos;
+  while (true) {
+    const events = await db.readGlobal(position + 1, batchSize);
+    if (events.length === 0) return;
+    yield events;
+    position = Math.max(position, ...events.map((event) => Number(event.globalPos)));
+  }
+}
+
+/**
+ * Attributes global events to one of a known set of tenants.
+ *
+ * A tenant always hashes the same way, so each hash is resolved once: the
+ * first event seen with it is looked up in each candidate tenant's stream.
+ */
+export class TenantResolver {
+  private readonly hashes = new Map<string, string | null>();
+
+  constructor(
+    private readonly db: EventLog,
+    private readonly tenants: string[]
+  ) {}
+
+  /** Add a candidate tenant; hashes no candidate matched are retried. */
+  addTenant(tenant: string): void {
+    if (this.tenants.includes(tenant)) return;
+    this.tenants.push(tenant);
+    for (const [hash, owner] of this.hashes) {
+      if (owner === null) this.hashes.delete(hash);
+    }
+  }
+
+  /** The tenant that wrote `event`, or null if it is none of the candidates. */
+  async tenantOf(event: LogEvent): Promise<string | null> {
+    const hash = String(event.tenantHash);
+    if (this.hashes.has(hash)) return this.hashes.get(hash)!;
+
+    let owner: string | null = null;
+    const rev = Number(event.streamRev);
+    for (const tenant of this.tenants) {
+      // Two events around the revision, so inclusive and exclusive fromRev both find it
+      const candidates = await this.db.readStream(event.streamId, Math.max(0, rev - 1), 2, tenant);
+      if (candidates.some((candidate) => Number(candidate.globalPos) === Number(event.globalPos))) {
+        owner = tenant;
+        break;
+      }
+    }
+    this.hashes.set(hash, owner);
+    return owner;
+  }
+}
+
+/** Tenants every app has, before any are created. */
+export const BUILTIN_TENANTS = ['system', 'public', 'default'];
+
+/**
+ * Every tenant that can own events: the built-in ones plus each tenant
+ * created through runtime/tenant (`TenantCreated` on a system `tenant-*` stream).
+ */
+export async function discoverTenants(db: EventLog): Promise<string[]> {
+  const tenants = new Set(BUILTIN_TENANTS);
+  const system = new TenantResolver(db, ['system']);
+  for await (const events of readGlobalFrom(db, 0)) {
+    for (const event of events) {
+      const created = createdTenant(event);
+      if (created && (await system.tenantOf(event)) === 'system') tenants.add(created);
+    }
+  }
+  return [...tenants];
+}
+
+/** The tenant id of a `TenantCreated` event, if `event` is one. */
+export function createdTenant(event: LogEvent): string | null {
+  if (!event.streamId.startsWith('tenant-')) return null;
+  try {
+    const payload = JSON.parse(event.data.toString());
+    return payload?.type === 'TenantCreated' && typeof payload.tenantId === 'string' ? payload.tenantId : null;
+  } catch {
+    return null;
+  }
+}
@@ -0,0 +1,151 @@
+/**
+ * SpiteStack Fixtures Module
+ *
+ * Imports seed data from NDJSON fixture files. Each line is one event:
+ *
+ *   {"stream": "todo-1", "type": "Created", "payload": {"title": "Buy milk"}}
+ *
+ * An optional "tenant" field overrides the default tenant for that line.
+ *
+ * Imports are idempotent: the Nth fixture event of a stream belongs at
+ * revision N, so events already present are skipped, and command ids are
+ * derived from the fixture content so a concurrent re-run is deduplicated by
+ * the store.
+ */
+
+import type { SpiteDbNapi } from '@spitestack/db';
+
+export type Fixture = {
+  stream: string;
+  type: string;
+  payload: Record<string, unknown>;
+  tenant?: string;
+  line: number;
+};
+
+export type ImportFixturesOptions = {
+  tenant?: string; // default 'system'
+};
+
+export type ImportFixturesResult = {
+  appended: number;
+  skipped: number;
+  streams: number;
+  conflicts: { stream: string; tenant: string; message: string }[];
+};
+
+/**
+ * Parse NDJSON fixture text. Blank lines and lines starting with `//` are
+ * ignored. Throws with the offending line number on malformed input.
+ */
+export function parseFixtures(text: string): Fixture[] {
+  const fixtures: Fixture[] = [];
+  const lines = text.split('\n');
+
+  for (let i = 0; i < lines.length; i++) {
+    const raw = lines[i].trim();
+    if (!raw || raw.startsWith('//')) continue;
+
+    let value: unknown;
+    try {
+      value = JSON.parse(raw);
+    } catch (err) {
+      throw new Error(`Line ${i + 1}: invalid JSON (${(err as Error).message})`);
+    }
+
+    const obj = value as Record<string, unknown>;
+    if (typeof obj !== 'object' || obj === null) {
+      throw new Error(`Line ${i + 1}: expected an object`);
+    }
+    if (typeof obj.stream !== 'string' || !obj.stream) {
+      throw new Error(`Line ${i + 1}: "stream" must be a non-empty string`);
+    }
+    if (typeof obj.type !== 'string' || !obj.type) {
+      throw new Error(`Line ${i + 1}: "type" must be a non-empty string`);
+    }
+    const payload = obj.payload ?? {};
+    if (typeof payload !== 'object' || payload === null || Array.isArray(payload)) {
+      throw new Error(`Line ${i + 1}: "payload" must be an object`);
+    }
+    if (obj.tenant !== undefined && typeof obj.tenant !== 'string') {
+      throw new Error(`Line ${i + 1}: "tenant" must be a string`);
+    }
+
+    fixtures.push({
+      stream: obj.stream,
+      type: obj.type,
+      payload: payload as Record<string, unknown>,
+      tenant: obj.tenant as string | undefined,
+      line: i + 1,
+    });
+  }
+
+  return fixtures;
+}
+
+/**
+ * Append fixture events to the store, skipping what is already there.
+ */
+export async function importFixtures(
+  db: SpiteDbNapi,
+  path: string,
+  options: ImportFixturesOptions = {}
+): Promise<ImportFixturesResult> {
+  const fixtures = parseFixtures(await Bun.file(path).text());
+  const defaultTenant = options.tenant ?? 'system';
+
+  // Group by (tenant, stream), keeping file order within each stream
+  const groups = new Map<string, { tenant: string; stream: string; events: Fixture[] }>();
+  for (const fixture of fixtures) {
+    const tenant = fixture.tenant ?? defaultTenant;
+    const key = `${tenant}\u0000${fixture.stream}`;
+    const group = groups.get(key) ?? { tenant, stream: fixture.stream, events: [] };
+    group.events.push(fixture);
+    groups.set(key, group);
+  }
+
+  const result: ImportFixturesResult = { appended: 0, skipped: 0, streams: groups.size, conflicts: [] };
+
+  for (const { tenant, stream, events } of groups.values()) {
+    const existing = await db.readStream(stream, 0, events.length, tenant);
+
+    // Events already in the store must match the fixture, or the stream has diverged
+    const mismatch = existing.findIndex((e, i) => {
+      const stored = JSON.parse(e.data.toString()) as { type?: string };
+      return i < events.length && stored.type !== events[i].type;
+    });
+    if (mismatch !== -1) {
+      result.conflicts.push({
+        stream,
+        tenant,
+        message: `revision ${mismatch + 1} is not a ${events[mismatch].type} event (line ${events[mismatch].line})`,
+      });
+      continue;
+    }
+
+    const currentRev = existing.length > 0 ? existing[existing.length - 1].streamRev : 0;
+    const pending = events.slice(existing.length);
+    result.skipped += events.length - pending.length;
+    if (pending.length === 0) continue;
+
+    const buffers = pending.map(f => Buffer.from(JSON.stringify({ type: f.type, ...f.payload })));
+    const commandId = deriveCommandId(tenant, stream, currentRev, pending);
+    await db.append(stream, commandId, currentRev, buffers, tenant);
+    result.appended += pending.length;
+  }
+
+  return result;
+}
+
+/**
+ * Deterministic UUID-shaped command id for a batch of fixture events.
+ */
+function deriveCommandId(tenant: string, stream: string, fromRev: number, events: Fixture[]): string {
+  const hasher = new Bun.CryptoHasher('sha256');
+  hasher.update(`${tenant}\n${stream}\n${fromRev}\n`);
+  for (const e of events) {
+    hasher.update(`${e.type}\n${JSON.stringify(e.payload)}\n`);
+  }
+  const hex = hasher.digest('hex');
+  return `${hex.slice(0, 8)}-${hex.slice(8, 12)}-${hex.slice(12, 16)}-${hex.slice(16, 20)}-${hex.slice(20, 32)}`;
+}
@@ -0,0 +1,145 @@
+/**
+ * SpiteStack Read-Your-Writes Module
+ *
+ * A command sent with `X-Wait-For-Projections: TodoStats,UserDirectory`
+ * responds only once those projections have applied the events it appended
+ * (or after `X-Wait-Timeout-Ms`, default 2000). The response body gains a
+ * `freshness` field and an `X-Projections-Fresh` header says whether every
+ * named projection caught up. Custom handlers can do the same with
+ * `appendAndWait`.
+ */
+
+import type { EventLog } from './event-log';
+
+/** Reads a projection's checkpoint for the tenant that wrote. */
+export type ProjectionPosition = (tenant: string) => number;
+
+export interface ProjectionFreshness {
+  /** Global position of the last appended event */
+  position: number;
+  /** Checkpoint reached by each projection (-1 for unknown names) */
+  projections: Record<string, number>;
+  /** Whether every named projection reached `position` */
+  fresh: boolean;
+}
+
+/** The parts of `SpiteDbNapi` `appendAndWait` needs. */
+export interface AppendLog extends Pick<EventLog, 'readStream'> {
+  append(streamId: string, commandId: string, expectedRev: number, events: Buffer[], tenant: string): Promise<unknown>;
+}
+
+export interface AppendCommand {
+  streamId: string;
+  tenant: string;
+  /** Revision the stream must be at; the append fails otherwise */
+  expectedRev: number;
+  events: unknown[];
+  commandId?: string;
+}
+
+const DEFAULT_TIMEOUT_MS = 2000;
+const MAX_TIMEOUT_MS = 10000;
+const POLL_INTERVAL_MS = 25;
+
+/** Projection names from `X-Wait-For-Projections`, if any. */
+export function requestedProjections(req: Request): string[] {
+  return (req.headers.get('x-wait-for-projections') ?? '')
+    .split(',')
+    .map((name) => name.trim())
+    .filter((name) => name.length > 0);
+}
+
+/** Wait until each projection's checkpoint reaches `position`. */
+export async function waitForProjections(
+  names: string[],
+  positions: Record<string, ProjectionPosition>,
+  tenant: string,
+  position: number,
+  timeoutMs: number
+): Promise<ProjectionFreshness> {
+  const deadline = Date.now() + timeoutMs;
+  const projections: Record<string, number> = {};
+
+  while (true) {
+    for (const name of names) {
+      projections[name] = positions[name] ? positions[name](tenant) : -1;
+    }
+    const fresh = names.every((name) => projections[name] >= position);
+    if (fresh || Date.now() >= deadline) {
+      return { position, projections, fresh };
+    }
+    await Bun.sleep(POLL_INTERVAL_MS);
+  }
+}
+
+/**
+ * Global position of the event at `revision` of a stream, read back from the
+ * store. Undefined if the stream has no such event.
+ */
+export async function streamEventPosition(
+  db: Pick<EventLog, 'readStream'>,
+  streamId: string,
+  revision: number,
+  tenant: string
+): Promise<number | undefined> {
+  // Two events around the revision, so inclusive and exclusive fromRev both find it
+  const events = await db.readStream(streamId, Math.max(0, revision - 1), 2, tenant);
+  const event = events.find((candidate) => Number(candidate.streamRev) === revision);
+  return event ? Number(event.globalPos) : undefined;
+}
+
+/**
+ * Append events, then wait until the named projections have applied them.
+ *
+ * For handlers that read a projection right after writing. Returns the new
+ * stream revision and the global position of the last appended event along
+ * with the projections' freshness; `fresh` is false if they did not catch up
+ * within `timeoutMs`.
+ */
+export async function appendAndWait(
+  db: AppendLog,
+  command: AppendCommand,
+  options: {
+    projections: string[];
+    positions: Record<string, ProjectionPosition>;
+    timeoutMs?: number;
+  }
+): Promise<{ revision: number; position: number; freshness: ProjectionFreshness }> {
+  const buffers = command.events.map((event) => Buffer.from(JSON.stringify(event)));
+  await db.append(command.streamId, command.commandId ?? crypto.randomUUID(), command.expectedRev, buffers, command.tenant);
+
+  const revision = command.expectedRev + buffers.length;
+  const position = (await streamEventPosition(db, command.streamId, revision, command.tenant)) ?? 0;
+  const timeoutMs = Math.min(options.timeoutMs ?? DEFAULT_TIMEOUT_MS, MAX_TIMEOUT_MS);
+  const freshness = await waitForProjections(options.projections, options.positions, command.tenant, position, timeoutMs);
+  return { revision, position, freshness };
+}
+
+/**
+ * Hold a successful command response until the requested projections have
+ * caught up with the command's append. The handler reports the position of
+ * its last event (see `streamEventPosition`) in `X-Global-Position`; commands
+ * that appended nothing have nothing to wait for.
+ */
+export async function readYourWrites(
+  req: Request,
+  response: Response,
+  options: {
+    tenant: string;
+    positions: Record<string, ProjectionPosition>;
+  }
+): Promise<Response> {
+  const names = requestedProjections(req);
+  if (names.length === 0 || !response.ok) return response;
+
+  const requested = Number(req.headers.get('x-wait-timeout-ms') ?? DEFAULT_TIMEOUT_MS);
+  const timeoutMs = Math.min(Number.isFinite(requested) && requested >= 0 ? requested : DEFAULT_TIMEOUT_MS, MAX_TIMEOUT_MS);
+
+  const position = Number(response.headers.get('x-global-position') ?? 0);
+  const freshness = await waitForProjections(names, options.positions, options.tenant, position, timeoutMs);
+
+  const body = await response.json();
+  const headers = new Headers(response.headers);
+  headers.set('X-Projections-Fresh', String(freshness.fresh));
+  return new Response(JSON.stringify({ ...body, freshness }), { status: response.status, headers });
+}
@@ -0,0 +1,98 @@
+/**
+ * SpiteStack Live Query Module
+ *
+ * Serves projection queries as Server-Sent Events. The query runs once when
+ * the client connects and again whenever the projection's checkpoint moves,
+ * so clients receive a fresh result after each applied batch of events.
+ */
+
+export interface LiveQueryOptions {
+  /** How often the checkpoint is checked, in milliseconds (default: 1000) */
+  pollIntervalMs?: number;
+  /** How often a keep-alive comment is sent, in milliseconds (default: 15000) */
+  keepAliveMs?: number;
+}
+
+/** Whether the client asked for a live (SSE) response. */
+export function wantsLiveQuery(req: Request): boolean {
+  return (req.headers.get('accept') ?? '').includes('text/event-stream');
+}
+
+/**
+ * Stream a query's results as SSE.
+ *
+ * `run` produces the same Response as the plain GET; its status and JSON body
+ * become a `result` event (`{ status, body }`). `position` returns the
+ * projection's checkpoint; the query re-runs only when it changes.
+ */
+export function liveQuery(
+  req: Request,
+  run: () => Promise<Response>,
+  position: () => number,
+  options: LiveQueryOptions = {}
+): Response {
+  const pollIntervalMs = options.pollIntervalMs ?? 1000;
+  const keepAliveMs = options.keepAliveMs ?? 15000;
+  const encoder = new TextEncoder();
+  let poll: ReturnType<typeof setInterval> | undefined;
+  let keepAlive: ReturnType<typeof setInterval> | undefined;
+
+  const stop = () => {
+    clearInterval(poll);
+    clearInterval(keepAlive);
+  };
+
+  const stream = new ReadableStream<Uint8Array>({
+    async start(controller) {
+      let lastPosition = -1;
+      let running = false;
+
+      const send = (chunk: string) => {
+        try {
+          controller.enqueue(encoder.encode(chunk));
+        } catch {
+          stop();
+        }
+      };
+
+      const refresh = async () => {
+        const current = position();
+        if (running || current === lastPosition) return;
+        running = true;
+        try {
+          const response = await run();
+          const body = await response.json().catch(() => null);
+          lastPosition = current;
+          send(`event: result\nid: ${current}\ndata: ${JSON.stringify({ status: response.status, body })}\n\n`);
+        } finally {
+          running = false;
+        }
+      };
+
+      await refresh();
+      poll = setInterval(() => void refresh(), pollIntervalMs);
+      keepAlive = setInterval(() => send(': keep-alive\n\n'), keepAliveMs);
+
+      req.signal.addEventListener('abort', () => {
+        stop();
+        try {
+          controller.close();
+        } catch {
+          // Already closed
+        }
+      });
+    },
+    cancel() {
+      stop();
+    },
+  });
+
+  return new Response(stream, {
+    status: 200,
+    headers: {
+      'Content-Type': 'text/event-stream',
+      'Cache-Control': 'no-cache',
+      Connection: 'keep-alive',
+    },
+  });
+}
@@ -0,0 +1,438 @@
+/**
+ * SpiteStack Session Module
+ *
+ * Cookie-based server-side sessions for apps that don't want to hand JWTs to
+ * the browser. Sessions are events in the system tenant (`session-{hash}`
+ * streams) projected into a `sessions` table next to the projection
+ * databases, and verification yields the same AuthResult as the JWT path so
+ * access levels and role checks in the router work unchanged.
+ */
+
+import { Database } from 'bun:sqlite';
+import type { AuthResult, AuthUser } from './auth';
+import { readGlobalFrom, TenantResolver, type EventLog } from './event-log';
+import { SYSTEM_TENANT_ID } from './tenant';
+
+export type SessionConfig = {
+  cookieName?: string; // default 'spite_session'
+  csrfCookieName?: string; // default 'spite_csrf'
+  csrfHeaderName?: string; // default 'x-csrf-token'
+  ttlSeconds?: number; // default 604800 (7d) - Absolute session life
+  idleTimeoutSeconds?: number; // default 86400 (24h) - Expire after inactivity
+  allowedOrigins?: string[]; // default: the origin of the request URL
+  replayIntervalMs?: number; // default 1000 - How long revocations by other instances may go unseen
+  dataDir?: string; // default PROJECTION_DATA_DIR or ./data/projections
+};
+
+export type SessionRecord = {
+  sessionId: string;
+  user: AuthUser;
+  csrfToken: string;
+  createdAt: number;
+  lastSeenAt: number;
+  expiresAt: number;
+};
+
+/** The parts of `SpiteDbNapi` the session store needs. */
+export interface SessionLog extends EventLog {
+  append(streamId: string, commandId: string, expectedRev: number, events: Buffer[], tenant: string): Promise<unknown>;
+  getStreamRevision(streamId: string, tenant: string): Promise<number | bigint>;
+}
+
+export type SessionEvent =
+  | { type: 'SessionStarted'; sessionHash: string; user: AuthUser; csrfToken: string; createdAt: number; expiresAt: number }
+  | { type: 'SessionRevoked'; sessionHash: string; revokedAt: number };
+
+const SAFE_METHODS = new Set(['GET', 'HEAD', 'OPTIONS']);
+const STREAM_PREFIX = 'session-';
+
+/**
+ * Session projection.
+ *
+ * Starting and revoking a session appends to the event log and is applied to
+ * the table right away, so the instance that wrote it can use it immediately;
+ * sessions written by other instances are picked up by replaying the log from
+ * the table's checkpoint, at most `replayIntervalMs` apart or when a cookie
+ * names an unknown session (`sessions_position`, the same layout as projection
+ * workers use, so a negative checkpoint rebuilds the table). Activity
+ * timestamps are local to the table: a replay restarts the idle clock rather
+ * than expiring every session. Session ids are stored hashed, in the log and
+ * the table, so neither can be replayed as a cookie.
+ */
+export class SessionStore {
+  private db: Database;
+  private tenants: TenantResolver;
+  private catchingUp: Promise<void> | null = null;
+  private lastReplayAt = 0;
+
+  constructor(private log: SessionLog, dataDir: string, private replayIntervalMs = 1000) {
+    this.tenants = new TenantResolver(log, [SYSTEM_TENANT_ID]);
+    this.db = new Database(`${dataDir}/sessions.db`, { create: true });
+    this.db.run('PRAGMA journal_mode = WAL');
+    this.db.run(`
+      CREATE TABLE IF NOT EXISTS sessions (
+        session_hash TEXT PRIMARY KEY,
+        user_id TEXT NOT NULL,
+        user_json TEXT NOT NULL,
+        csrf_token TEXT NOT NULL,
+        created_at INTEGER NOT NULL,
+        last_seen_at INTEGER NOT NULL,
+        expires_at INTEGER NOT NULL
+      )
+    `);
+    this.db.run('CREATE INDEX IF NOT EXISTS sessions_user_id ON sessions (user_id)');
+    this.db.run(`
+      CREATE TABLE IF NOT EXISTS sessions_position (
+        tenant_id TEXT PRIMARY KEY,
+        last_event_id INTEGER NOT NULL DEFAULT 0,
+        updated_at TEXT
+      )
+    `);
+  }
+
+  async create(user: AuthUser, ttlSeconds: number): Promise<SessionRecord> {
+    const now = Date.now();
+    const record: SessionRecord = {
+      sessionId: randomToken(),
+      user,
+      csrfToken: randomToken(),
+      createdAt: now,
+      lastSeenAt: now,
+      expiresAt: now + ttlSeconds * 1000,
+    };
+    const event: SessionEvent = {
+      type: 'SessionStarted',
+      sessionHash: await hashToken(record.sessionId),
+      user,
+      csrfToken: record.csrfToken,
+      createdAt: now,
+      expiresAt: record.expiresAt,
+    };
+    await this.log.append(STREAM_PREFIX + event.sessionHash, crypto.randomUUID(), 0, [Buffer.from(JSON.stringify(event))], SYSTEM_TENANT_ID);
+    this.apply(event);
+    return record;
+  }
+
+  async get(sessionId: string): Promise<SessionRecord | null> {
+    const sessionHash = await hashToken(sessionId);
+    if (Date.now() - this.lastReplayAt >= this.replayIntervalMs) await this.catchUp();
+    let row = this.row(sessionHash);
+    if (!row) {
+      // Not in the table yet: it may have been started by another instance
+      await this.catchUp();
+      row = this.row(sessionHash);
+    }
+    if (!row) return null;
+
+    return {
+      sessionId,
+      user: JSON.parse(row.user_json) as AuthUser,
+      csrfToken: row.csrf_token,
+      createdAt: row.created_at,
+      lastSeenAt: row.last_seen_at,
+      expiresAt: row.expires_at,
+    };
+  }
+
+  async touch(sessionId: string, now: number): Promise<void> {
+    this.db.run('UPDATE sessions SET last_seen_at = ? WHERE session_hash = ?', [now, await hashToken(sessionId)]);
+  }
+
+  async revoke(sessionId: string): Promise<void> {
+    await this.revokeHash(await hashToken(sessionId));
+  }
+
+  async revokeAllForUser(userId: string): Promise<void> {
+    const rows = this.db.query('SELECT session_hash FROM sessions WHERE user_id = ?').all(userId) as { session_hash: string }[];
+    for (const row of rows) await this.revokeHash(row.session_hash);
+  }
+
+  /**
+   * Delete expired sessions. Returns the number of rows removed.
+   */
+  prune(now: number = Date.now()): number {
+    return this.db.run('DELETE FROM sessions WHERE expires_at <= ?', [now]).changes;
+  }
+
+  /**
+   * Apply session events appended since the checkpoint. Concurrent callers
+   * share one replay.
+   */
+  catchUp(): Promise<void> {
+    this.catchingUp ??= this.replay().finally(() => {
+      this.catchingUp = null;
+    });
+    return this.catchingUp;
+  }
+
+  private async replay(): Promise<void> {
+    this.lastReplayAt = Date.now();
+    let position = this.position();
+    if (position < 0) {
+      this.db.run('DELETE FROM sessions');
+      position = 0;
+    }
+
+    for await (const events of readGlobalFrom(this.log, position)) {
+      for (const event of events) {
+        if (!event.streamId.startsWith(STREAM_PREFIX)) continue;
+        if ((await this.tenants.tenantOf(event)) !== SYSTEM_TENANT_ID) continue;
+        this.apply(JSON.parse(event.data.toString()) as SessionEvent);
+      }
+      position = Math.max(position, ...events.map((event) => Number(event.globalPos)));
+      this.db.run(
+        `INSERT INTO sessions_position (tenant_id, last_event_id, updated_at) VALUES (?, ?, datetime('now'))
+         ON CONFLICT(tenant_id) DO UPDATE SET last_event_id = excluded.last_event_id, updated_at = excluded.updated_at`,
+        [SYSTEM_TENANT_ID, position]
+      );
+    }
+  }
+
+  private apply(event: SessionEvent): void {
+    if (event.type === 'SessionStarted') {
+      this.db.run(
+        `INSERT OR IGNORE INTO sessions (session_hash, user_id, user_json, csrf_token, created_at, last_seen_at, expires_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?)`,
+        [event.sessionHash, event.user.sub, JSON.stringify(event.user), event.csrfToken, event.createdAt, Date.now(), event.expiresAt]
+      );
+    } else if (event.type === 'SessionRevoked') {
+      this.db.run('DELETE FROM sessions WHERE session_hash = ?', [event.sessionHash]);
+    }
+  }
+
+  private async revokeHash(sessionHash: string): Promise<void> {
+    const streamId = STREAM_PREFIX + sessionHash;
+    const rev = Number(await this.log.getStreamRevision(streamId, SYSTEM_TENANT_ID));
+    const event: SessionEvent = { type: 'SessionRevoked', sessionHash, revokedAt: Date.now() };
+    // A cookie that never named a session has nothing to revoke
+    if (rev > 0) {
+      await this.log.append(streamId, crypto.randomUUID(), rev, [Buffer.from(JSON.stringify(event))], SYSTEM_TENANT_ID);
+    }
+    this.apply(event);
+  }
+
+  private row(sessionHash: string) {
+    return this.db.query(
+      'SELECT user_json, csrf_token, created_at, last_seen_at, expires_at FROM sessions WHERE session_hash = ?'
+    ).get(sessionHash) as {
+      user_json: string;
+      csrf_token: string;
+      created_at: number;
+      last_seen_at: number;
+      expires_at: number;
+    } | null;
+  }
+
+  private position(): number {
+    const row = this.db.query('SELECT last_event_id FROM sessions_position WHERE tenant_id = ?').get(SYSTEM_TENANT_ID) as
+      | { last_event_id: number }
+      | null;
+    return row?.last_event_id ?? 0;
+  }
+}
+
+/**
+ * Creates a session authenticator.
+ *
+ * `verifyRequest` mirrors `createAuth().verifyRequest` and additionally
+ * rejects state-changing requests from other origins or without the
+ * double-submit CSRF token.
+ */
+export function createSessionAuth(log: SessionLog, config: SessionConfig = {}) {
+  const cookieName = config.cookieName ?? 'spite_session';
+  const csrfCookieName = config.csrfCookieName ?? 'spite_csrf';
+  const csrfHeaderName = config.csrfHeaderName ?? 'x-csrf-token';
+  const ttlSeconds = config.ttlSeconds ?? 604800;
+  const idleTimeoutMs = (config.idleTimeoutSeconds ?? 86400) * 1000;
+  const store = new SessionStore(
+    log,
+    config.dataDir ?? process.env.PROJECTION_DATA_DIR ?? './data/projections',
+    config.replayIntervalMs
+  );
+
+  async function load(req: Request): Promise<SessionRecord | null> {
+    const sessionId = getCookie(req, cookieName);
+    if (!sessionId) return null;
+
+    const session = await store.get(sessionId);
+    if (!session) return null;
+
+    const now = Date.now();
+    if (session.expiresAt <= now || now - session.lastSeenAt > idleTimeoutMs) {
+      await store.revoke(sessionId);
+      return null;
+    }
+
+    await store.touch(sessionId, now);
+    return session;
+  }
+
+  async function verifyRequest(req: Request): Promise<AuthResult> {
+    const session = await load(req);
+    if (!session) {
+      return { ok: false, error: 'Missing or expired session' };
+    }
+
+    const csrfError = checkCsrf(req, session);
+    if (csrfError) {
+      return { ok: false, error: csrfError };
+    }
+
+    return { ok: true, user: { ...session.user, sid: session.sessionId } };
+  }
+
+  /**
+   * Why a state-changing request may not act on `session`, or null if it may.
+   * A present `Origin` must be an allowed one, and the CSRF header must echo
+   * the session's token.
+   */
+  function checkCsrf(req: Request, session: SessionRecord): string | null {
+    if (SAFE_METHODS.has(req.method)) return null;
+
+    const origin = req.headers.get('Origin');
+    const allowedOrigins = config.allowedOrigins ?? [new URL(req.url).origin];
+    if (origin && !allowedOrigins.includes(origin)) {
+      return 'Cross-origin request rejected';
+    }
+
+    const headerToken = req.headers.get(csrfHeaderName);
+    if (!headerToken || !timingSafeEqual(headerToken, session.csrfToken)) {
+      return 'CSRF token missing or invalid';
+    }
+    return null;
+  }
+
+  /**
+   * Start a session for a user and attach the session and CSRF cookies.
+   */
+  async function issue(user: AuthUser, headers: Headers): Promise<SessionRecord> {
+    const session = await store.create(user, ttlSeconds);
+    setSessionCookies(headers, session, ttlSeconds);
+    return session;
+  }
+
+  /**
+   * Revoke the request's session (if any) and clear its cookies.
+   */
+  async function revoke(req: Request, headers: Headers): Promise<void> {
+    const sessionId = getCookie(req, cookieName);
+    if (sessionId) await store.revoke(sessionId);
+    clearSessionCookies(headers);
+  }
+
+  function setSessionCookies(headers: Headers, session: SessionRecord, maxAge: number) {
+    const secureCookieFlag = process.env.NODE_ENV === 'production' ? '; Secure' : '';
+    headers.append('Set-Cookie', `${cookieName}=${session.sessionId}; Path=/; HttpOnly; SameSite=Lax; Max-Age=${maxAge}${secureCookieFlag}`);
+    // Readable by client JS so it can echo the token in the CSRF header
+    headers.append('Set-Cookie', `${csrfCookieName}=${session.csrfToken}; Path=/; SameSite=Strict; Max-Age=${maxAge}${secureCookieFlag}`);
+  }
+
+  function clearSessionCookies(headers: Headers) {
+    const secureCookieFlag = process.env.NODE_ENV === 'production' ? '; Secure' : '';
+    headers.append('Set-Cookie', `${cookieName}=; Path=/; HttpOnly; SameSite=Lax; Max-Age=0${secureCookieFlag}`);
+    headers.append('Set-Cookie', `${csrfCookieName}=; Path=/; SameSite=Strict; Max-Age=0${secureCookieFlag}`);
+  }
+
+  return { store, load, verifyRequest, checkCsrf, issue, revoke };
+}
+
+export type SessionAuth = ReturnType<typeof createSessionAuth>;
+
+// =============================================================================
+// Handlers
+// =============================================================================
+
+/**
+ * Converts a successful login response (`handleAuthLogin`, `handleAuthMfaVerify`,
+ * `handleAuthSocialCallback`) into a server-side session.
+ *
+ * The JWT cookies set by the identity module are dropped and replaced with the
+ * session and CSRF cookies. Non-success responses (MFA required, password
+ * change required, errors) are passed through untouched.
+ */
+export async function handleSessionLogin(sessions: SessionAuth, loginResponse: Response): Promise<Response> {
+  if (loginResponse.status !== 200) return loginResponse;
+
+  const body = await loginResponse.clone().json().catch(() => null) as
+    | { status?: string; user?: { sub: string; orgs?: AuthUser['orgs'] } }
+    | null;
+  if (!body || body.status !== 'success' || !body.user) return loginResponse;
+
+  const headers = new Headers({ 'Content-Type': 'application/json' });
+  const session = await sessions.issue({ sub: body.user.sub, orgs: body.user.orgs }, headers);
+
+  return new Response(JSON.stringify({
+    status: 'success',
+    userId: body.user.sub,
+    user: body.user,
+    csrfToken: session.csrfToken,
+  }), { status: 200, headers });
+}
+
+export async function handleSessionInfo(sessions: SessionAuth, req: Request): Promise<Response> {
+  const session = await sessions.load(req);
+  if (!session) {
+    return new Response(JSON.stringify({ error: 'Not authenticated' }), {
+      status: 401,
+      headers: { 'Content-Type': 'application/json' },
+    });
+  }
+
+  return new Response(JSON.stringify({
+    user: { sub: session.user.sub, orgs: session.user.orgs },
+    csrfToken: session.csrfToken,
+    expiresAt: session.expiresAt,
+  }), {
+    status: 200,
+    headers: { 'Content-Type': 'application/json' },
+  });
+}
+
+/**
+ * Ends the request's session. Logging out is state-changing too, so another
+ * site cannot sign the user out: a live session must pass the CSRF check.
+ */
+export async function handleSessionLogout(sessions: SessionAuth, req: Request): Promise<Response> {
+  const session = await sessions.load(req);
+  const csrfError = session ? sessions.checkCsrf(req, session) : null;
+  if (csrfError) {
+    return new Response(JSON.stringify({ error: csrfError }), {
+      status: 403,
+      headers: { 'Content-Type': 'application/json' },
+    });
+  }
+
+  const headers = new Headers({ 'Content-Type': 'application/json' });
+  await sessions.revoke(req, headers);
+  return new Response(JSON.stringify({ ok: true }), { status: 200, headers });
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+function randomToken(): string {
+  const bytes = crypto.getRandomValues(new Uint8Array(32));
+  return Buffer.from(bytes).toString('base64url');
+}
+
+async function hashToken(value: string): Promise<string> {
+  const data = new TextEncoder().encode(value);
+  const hash = await crypto.subtle.digest('SHA-256', data);
+  return Buffer.from(hash).toString('hex');
+}
+
+function timingSafeEqual(a: string, b: string): boolean {
+  if (a.length !== b.length) return false;
+  let diff = 0;
+  for (let i = 0; i < a.length; i++) {
+    diff |= a.charCodeAt(i) ^ b.charCodeAt(i);
+  }
+  return diff === 0;
+}
+
+function getCookie(req: Request, name: string): string | null {
+  const cookieHeader = req.headers.get('Cookie') || '';
+  const match = cookieHeader.split(';').find(c => c.trim().startsWith(name + '='));
+  return match ? match.split('=')[1]?.trim() || null : null;
+}
@@ -0,0 +1,405 @@
+/**
+ * SpiteStack Orchestrator Simulator
+ *
+ * Deterministic test harness for generated orchestrators. Runs them against an
+ * in-memory event store with a virtual clock and seeded ids, so saga logic
+ * (including timeouts and compensations) can be tested in `bun test` without
+ * a running server or a real database.
+ *
+ * Usage, with `processOrder` a saga written against the context:
+ *   const sim = createSimulation({ seed: 1 });
+ *   sim.given('order-1', [{ type: 'Placed', total: 10 }]);
+ *   sim.failNextAppend('payment-1', 'card declined');
+ *   const result = await sim.run(() => processOrder(sim.ctx, 'order-1', 'payment-1'));
+ *   sim.expectAppended('order-1', ['Cancelled']);
+ */
+
+import type { SpiteDbNapi, TelemetryDbNapi, TelemetryRecordNapi } from '@spitestack/db';
+
+export type SimulationOptions = {
+  seed?: number; // default 1 - Seed for crypto.randomUUID / Math.random
+  startTime?: number; // default 0 - Virtual epoch in milliseconds
+  tenant?: string; // default 'default'
+};
+
+export type SimulatedEvent = {
+  globalPos: number;
+  streamId: string;
+  streamRev: number;
+  timestampMs: number;
+  tenantHash: number;
+  commandId: string;
+  data: Buffer;
+};
+
+/**
+ * A single append observed during the simulation.
+ */
+export type AppendRecord = {
+  streamId: string;
+  tenant: string;
+  commandId: string;
+  expectedRev: number;
+  events: unknown[];
+  at: number;
+};
+
+// =============================================================================
+// Virtual clock
+// =============================================================================
+
+type Timer = { id: number; at: number; fn: () => void; interval?: number };
+
+/**
+ * Virtual time. Timers only fire when the clock is advanced, in order of
+ * their due time (ties broken by registration order).
+ */
+export class VirtualClock {
+  private current: number;
+  private timers: Timer[] = [];
+  private nextId = 1;
+
+  constructor(startTime: number) {
+    this.current = startTime;
+  }
+
+  now(): number {
+    return this.current;
+  }
+
+  setTimeout(fn: () => void, ms = 0): number {
+    const id = this.nextId++;
+    this.timers.push({ id, at: this.current + Math.max(0, ms), fn });
+    return id;
+  }
+
+  setInterval(fn: () => void, ms = 0): number {
+    const id = this.nextId++;
+    const interval = Math.max(1, ms);
+    this.timers.push({ id, at: this.current + interval, fn, interval });
+    return id;
+  }
+
+  clear(id: number | undefined): void {
+    this.timers = this.timers.filter(t => t.id !== id);
+  }
+
+  /**
+   * Number of timers that have not fired yet.
+   */
+  pending(): number {
+    return this.timers.length;
+  }
+
+  /**
+   * Move time forward, firing every timer that falls due on the way. Pending
+   * promise continuations run between timers so awaited sleeps resume in order.
+   */
+  async advance(ms: number): Promise<void> {
+    const target = this.current + ms;
+
+    for (;;) {
+      await flushMicrotasks();
+      const due = this.timers
+        .filter(t => t.at <= target)
+        .sort((a, b) => a.at - b.at || a.id - b.id)[0];
+      if (!due) break;
+
+      this.current = due.at;
+      if (due.interval) {
+        due.at += due.interval;
+      } else {
+        this.clear(due.id);
+      }
+      due.fn();
+    }
+
+    this.current = target;
+    await flushMicrotasks();
+  }
+
+  /**
+   * Advance until no timers remain (bounded to avoid runaway intervals).
+   */
+  async runAll(maxSteps = 10_000): Promise<void> {
+    for (let i = 0; i < maxSteps && this.timers.length > 0; i++) {
+      const next = Math.min(...this.timers.map(t => t.at));
+      await this.advance(Math.max(0, next - this.current));
+    }
+  }
+}
+
+async function flushMicrotasks(): Promise<void> {
+  for (let i = 0; i < 10; i++) {
+    await Promise.resolve();
+  }
+}
+
+// =============================================================================
+// In-memory event store
+// =============================================================================
+
+/**
+ * In-memory stand-in for SpiteDbNapi covering what handlers and orchestrators
+ * use. Optimistic concurrency is enforced the same way as the real store.
+ */
+export class SimulatedDb {
+  readonly appends: AppendRecord[] = [];
+  private log: (SimulatedEvent & { tenant: string })[] = [];
+  private failures = new Map<string, string[]>();
+
+  constructor(private clock: VirtualClock, private defaultTenant: string) {}
+
+  /**
+   * Seed a stream with events that "already happened" before the run.
+   */
+  given(streamId: string, events: unknown[], tenant = this.defaultTenant): void {
+    const rev = this.revision(streamId, tenant);
+    this.write(streamId, tenant, 'given', events, rev);
+  }
+
+  /**
+   * Make the next append to a stream fail, e.g. to exercise compensations.
+   */
+  failNextAppend(streamId: string, message = 'simulated append failure'): void {
+    const queue = this.failures.get(streamId) ?? [];
+    queue.push(message);
+    this.failures.set(streamId, queue);
+  }
+
+  async readStream(streamId: string, fromRev: number, limit: number, tenant: string): Promise<SimulatedEvent[]> {
+    return this.log
+      .filter(e => e.tenant === tenant && e.streamId === streamId && e.streamRev >= fromRev)
+      .slice(0, limit);
+  }
+
+  async readGlobal(fromPos: number, limit: number): Promise<SimulatedEvent[]> {
+    return this.log.filter(e => e.globalPos >= fromPos).slice(0, limit);
+  }
+
+  async getStreamRevision(streamId: string, tenant: string): Promise<number> {
+    return this.revision(streamId, tenant);
+  }
+
+  async append(streamId: string, commandId: string, expectedRev: number, buffers: Buffer[], tenant: string): Promise<void> {
+    const failure = this.failures.get(streamId)?.shift();
+    if (failure !== undefined) {
+      throw new Error(failure);
+    }
+
+    const current = this.revision(streamId, tenant);
+    if (current !== expectedRev) {
+      throw new Error(`Conflict: expected revision ${expectedRev}, stream ${streamId} is at ${current}`);
+    }
+
+    const events = buffers.map(b => JSON.parse(b.toString()));
+    this.write(streamId, tenant, commandId, events, current);
+    this.appends.push({ streamId, tenant, commandId, expectedRev, events, at: this.clock.now() });
+  }
+
+  private revision(streamId: string, tenant: string): number {
+    const events = this.log.filter(e => e.tenant === tenant && e.streamId === streamId);
+    return events.length > 0 ? events[events.length - 1].streamRev : 0;
+  }
+
+  private write(streamId: string, tenant: string, commandId: string, events: unknown[], fromRev: number): void {
+    events.forEach((event, i) => {
+      this.log.push({
+        globalPos: this.log.length + 1,
+        streamId,
+        streamRev: fromRev + i + 1,
+        timestampMs: this.clock.now(),
+        tenantHash: 0,
+        commandId,
+        tenant,
+        data: Buffer.from(JSON.stringify(event)),
+      });
+    });
+  }
+}
+
+/**
+ * Telemetry sink that keeps records in memory for assertions.
+ */
+export class SimulatedTelemetry {
+  readonly records: TelemetryRecordNapi[] = [];
+
+  writeBatch(records: TelemetryRecordNapi[]): void {
+    this.records.push(...records);
+  }
+}
+
+// =============================================================================
+// Simulation
+// =============================================================================
+
+/**
+ * Create a deterministic simulation.
+ *
+ * While `run` is executing, `Date.now`, timers, `crypto.randomUUID` and
+ * `Math.random` are replaced with virtual, seeded versions and restored
+ * afterwards. Runs begun with `start` restore them in `finish`, when the
+ * function throws or rejects, or on `dispose()`; `dispose()` on the
+ * simulation ends every run still holding them (e.g. from `afterEach`).
+ */
+export function createSimulation(options: SimulationOptions = {}) {
+  const tenant = options.tenant ?? 'default';
+  const clock = new VirtualClock(options.startTime ?? 0);
+  const db = new SimulatedDb(clock, tenant);
+  const telemetry = new SimulatedTelemetry();
+  const random = seededRandom(options.seed ?? 1);
+  const active = new Set<() => void>();
+
+  const ctx = {
+    db: db as unknown as SpiteDbNapi,
+    telemetry: telemetry as unknown as TelemetryDbNapi,
+    tenant,
+  };
+
+  /**
+   * Run an orchestrator (or any async function) inside virtual time. The
+   * returned promise settles once the function settles; timers it is waiting
+   * on only fire when `advance` is called from within or after the run.
+   */
+  async function run<T>(fn: () => Promise<T>): Promise<T> {
+    const restore = installVirtualGlobals(clock, random);
+    try {
+      return await fn();
+    } finally {
+      restore();
+    }
+  }
+
+  /**
+   * Start a run without awaiting it, so the test can advance time while the
+   * orchestrator is blocked on a timeout.
+   */
+  function start<T>(fn: () => Promise<T>) {
+    const restore = installVirtualGlobals(clock, random);
+    const dispose = () => {
+      if (active.delete(dispose)) restore();
+    };
+    active.add(dispose);
+
+    let promise: Promise<T>;
+    try {
+      promise = fn();
+    } catch (err) {
+      dispose();
+      throw err;
+    }
+    // A run that fails before `finish` must not leave the globals replaced
+    promise.catch(dispose);
+
+    return {
+      promise,
+      async advance(ms: number): Promise<void> {
+        await clock.advance(ms);
+      },
+      async finish(): Promise<T> {
+        try {
+          await clock.runAll();
+          return await promise;
+        } finally {
+          dispose();
+        }
+      },
+      dispose,
+    };
+  }
+
+  /**
+   * Restore the real globals for every run that has not finished.
+   */
+  function dispose(): void {
+    for (const end of [...active].reverse()) end();
+  }
+
+  /**
+   * Events appended to a stream during the simulation (excluding `given`).
+   */
+  function appended(streamId?: string): unknown[] {
+    return db.appends
+      .filter(a => streamId === undefined || a.streamId === streamId)
+      .flatMap(a => a.events);
+  }
+
+  /**
+   * Assert the event types appended to a stream, in order.
+   */
+  function expectAppended(streamId: string, types: string[]): void {
+    const actual = appended(streamId).map(e => (e as { type?: string }).type);
+    if (JSON.stringify(actual) !== JSON.stringify(types)) {
+      throw new Error(
+        `Expected ${streamId} to receive [${types.join(', ')}] but got [${actual.join(', ')}]`
+      );
+    }
+  }
+
+  return {
+    ctx,
+    db,
+    telemetry,
+    clock,
+    given: db.given.bind(db),
+    failNextAppend: db.failNextAppend.bind(db),
+    run,
+    start,
+    advance: (ms: number) => clock.advance(ms),
+    appended,
+    expectAppended,
+    dispose,
+  };
+}
+
+export type Simulation = ReturnType<typeof createSimulation>;
+
+function installVirtualGlobals(clock: VirtualClock, random: () => number): () => void {
+  const original = {
+    now: Date.now,
+    setTimeout: globalThis.setTimeout,
+    clearTimeout: globalThis.clearTimeout,
+    setInterval: globalThis.setInterval,
+    clearInterval: globalThis.clearInterval,
+    randomUUID: crypto.randomUUID,
+    random: Math.random,
+  };
+
+  Date.now = () => clock.now();
+  globalThis.setTimeout = ((fn: () => void, ms?: number) => clock.setTimeout(fn, ms)) as unknown as typeof setTimeout;
+  globalThis.clearTimeout = ((id: number) => clock.clear(id)) as unknown as typeof clearTimeout;
+  globalThis.setInterval = ((fn: () => void, ms?: number) => clock.setInterval(fn, ms)) as unknown as typeof setInterval;
+  globalThis.clearInterval = ((id: number) => clock.clear(id)) as unknown as typeof clearInterval;
+  crypto.randomUUID = (() => seededUuid(random)) as typeof crypto.randomUUID;
+  Math.random = random;
+
+  return () => {
+    Date.now = original.now;
+    globalThis.setTimeout = original.setTimeout;
+    globalThis.clearTimeout = original.clearTimeout;
+    globalThis.setInterval = original.setInterval;
+    globalThis.clearInterval = original.clearInterval;
+    crypto.randomUUID = original.randomUUID;
+    Math.random = original.random;
+  };
+}
+
+// mulberry32
+function seededRandom(seed: number): () => number {
+  let state = seed >>> 0;
+  return () => {
+    state = (state + 0x6d2b79f5) >>> 0;
+    let t = state;
+    t = Math.imul(t ^ (t >>> 15), t | 1);
+    t ^= t + Math.imul(t ^ (t >>> 7), t | 61);
+    return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+  };
+}
+
+function seededUuid(random: () => number): `${string}-${string}-${string}-${string}-${string}` {
+  const hex = Array.from({ length: 32 }, () => Math.floor(random() * 16).toString(16));
+  hex[12] = '4';
+  hex[16] = ((parseInt(hex[16], 16) & 0x3) | 0x8).toString(16);
+  const s = hex.join('');
+  return `${s.slice(0, 8)}-${s.slice(8, 12)}-${s.slice(12, 16)}-${s.slice(16, 20)}-${s.slice(20)}`;
+}
@@ -0,0 +1,237 @@
+//! Build cache for incremental recompilation.
+//!
+//! `recompile_domain` runs on every change in watch mode. The cache
+//! (`.spitestack-cache.json` in the output directory) records a content hash
+//! for each domain source file, so a change event that left every source
+//! untouched is a no-op, and an IR fingerprint for each aggregate that passed
+//! validation, so unchanged aggregates are not re-validated. Projections are
+//! fingerprinted too, so dev mode can rebuild the ones that changed. Generated
+//! files are compared against what is already on disk and only rewritten when
+//! their content differs.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::diagnostic::CompilerError;
+use crate::ir::{AggregateIR, ProjectionIR};
+
+/// File name of the cache, relative to the output directory.
+pub const CACHE_FILE: &str = ".spitestack-cache.json";
+
+/// Persisted hashes from the last successful compilation.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BuildCache {
+    /// Compiler version that wrote the cache. A different version discards it.
+    #[serde(rename = "compilerVersion")]
+    pub compiler_version: String,
+
+    /// Content hash of each domain source file, keyed by path.
+    pub sources: BTreeMap<String, String>,
+
+    /// Config the cache was built with (language, API style, ...).
+    #[serde(default)]
+    pub settings: String,
+
+    /// IR fingerprint of each validated aggregate, keyed by name.
+    pub aggregates: BTreeMap<String, String>,
+
+    /// IR fingerprint of each projection, keyed by name.
+    #[serde(default)]
+    pub projections: BTreeMap<String, String>,
+
+    /// Result of the last compilation, reused when no source changed.
+    #[serde(default)]
+    pub counts: Option<CachedCounts>,
+}
+
+/// Counts from the last compilation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedCounts {
+    pub aggregates: usize,
+    pub orchestrators: usize,
+    pub events: usize,
+    /// Number of generated files.
+    pub files: usize,
+}
+
+impl BuildCache {
+    /// Loads the cache from `out_dir`.
+    ///
+    /// A missing, unreadable or outdated cache yields an empty one; the
+    /// cache only ever saves work, so it never fails a build.
+    pub fn load(out_dir: &Path) -> Self {
+        std::fs::read_to_string(out_dir.join(CACHE_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str::<Self>(&content).ok())
+            .filter(|cache| cache.compiler_version == env!("CARGO_PKG_VERSION"))
+            .unwrap_or_default()
+    }
+
+    /// Saves the cache to `out_dir`.
+    pub fn save(&mut self, out_dir: &Path) -> Result<(), CompilerError> {
+        self.compiler_version = env!("CARGO_PKG_VERSION").to_string();
+        let path = out_dir.join(CACHE_FILE);
+        let content = serde_json::to_string_pretty(self).map_err(|e| CompilerError::IoError {
+            path: path.clone(),
+            message: format!("Failed to serialize build cache: {}", e),
+        })?;
+        std::fs::write(&path, content).map_err(|e| CompilerError::IoError {
+            path,
+            message: e.to_string(),
+        })
+    }
+
+    /// Returns true if the aggregate's IR matches the last validated version.
+    pub fn aggregate_unchanged(&self, aggregate: &AggregateIR) -> bool {
+        self.aggregates.get(&aggregate.name) == Some(&aggregate_fingerprint(aggregate))
+    }
+
+    /// Records an aggregate as validated.
+    pub fn record_aggregate(&mut self, aggregate: &AggregateIR) {
+        self.aggregates
+            .insert(aggregate.name.clone(), aggregate_fingerprint(aggregate));
+    }
+
+    /// Records the domain's projections and returns the names of those whose
+    /// IR changed since they were last recorded.
+    ///
+    /// New projections are not reported: they have no stored state to rebuild.
+    pub fn record_projections(&mut self, projections: &[ProjectionIR]) -> Vec<String> {
+        let previous = std::mem::take(&mut self.projections);
+        let mut changed = Vec::new();
+        for projection in projections {
+            let fingerprint = projection_fingerprint(projection);
+            if previous.get(&projection.name).is_some_and(|old| old != &fingerprint) {
+                changed.push(projection.name.clone());
+            }
+            self.projections.insert(projection.name.clone(), fingerprint);
+        }
+        changed
+    }
+}
+
+/// Hashes every file under `dir` with one of the given extensions.
+pub fn hash_sources(dir: &Path, extensions: &[&str]) -> BTreeMap<String, String> {
+    walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter(|entry| {
+            entry
+                .path()
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| extensions.contains(&ext))
+        })
+        .filter_map(|entry| {
+            let content = std::fs::read(entry.path()).ok()?;
+            Some((entry.path().display().to_string(), hash_bytes(&content)))
+        })
+        .collect()
+}
+
+/// Writes `content` to `path` unless the file already holds exactly that.
+///
+/// Returns true if the file was written.
+pub fn write_if_changed(path: &Path, content: &str) -> Result<bool, CompilerError> {
+    if std::fs::read(path).is_ok_and(|existing| existing == content.as_bytes()) {
+        return Ok(false);
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| CompilerError::IoError {
+            path: parent.to_path_buf(),
+            message: e.to_string(),
+        })?;
+    }
+    std::fs::write(path, content).map_err(|e| CompilerError::IoError {
+        path: PathBuf::from(path),
+        message: e.to_string(),
+    })?;
+    Ok(true)
+}
+
+/// Fingerprints an aggregate by its IR, so edits to any file that feeds it
+/// (events, state, the class itself) are picked up.
+fn aggregate_fingerprint(aggregate: &AggregateIR) -> String {
+    hash_bytes(format!("{:?}", aggregate).as_bytes())
+}
+
+/// Fingerprints what a projection stores: its subscriptions, schema and
+/// build logic. Queries and access rules read the same rows, so changing
+/// them does not call for a rebuild.
+fn projection_fingerprint(projection: &ProjectionIR) -> String {
+    let stored = (
+        &projection.kind,
+        &projection.subscribed_events,
+        &projection.schema,
+        &projection.raw_build_body,
+    );
+    hash_bytes(format!("{:?}", stored).as_bytes())
+}
+
+fn hash_bytes(bytes: &[u8]) -> String {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn write_if_changed_skips_identical_content() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("nested").join("file.ts");
+
+        assert!(write_if_changed(&path, "export {};").unwrap());
+        assert!(!write_if_changed(&path, "export {};").unwrap());
+        assert!(write_if_changed(&path, "export const x = 1;").unwrap());
+    }
+
+    #[test]
+    fn reports_changed_projections() {
+        let mut projection = crate::ir::ProjectionIR {
+            name: "TodoStats".to_string(),
+            source_path: PathBuf::new(),
+            kind: crate::ir::ProjectionKind::Aggregator,
+            subscribed_events: vec![],
+            schema: crate::ir::ProjectionSchema {
+                state_property_name: "stats".to_string(),
+                primary_keys: vec![],
+                columns: vec![],
+                indexes: vec![],
+            },
+            queries: vec![],
+            raw_build_body: Some("this.stats.count += 1;".to_string()),
+            access: crate::ir::AccessLevel::Internal,
+            roles: vec![],
+        };
+        let mut cache = BuildCache::default();
+
+        assert!(cache.record_projections(std::slice::from_ref(&projection)).is_empty());
+        assert!(cache.record_projections(std::slice::from_ref(&projection)).is_empty());
+        projection.raw_build_body = Some("this.stats.count += 2;".to_string());
+        assert_eq!(cache.record_projections(&[projection]), vec!["TodoStats".to_string()]);
+    }
+
+    #[test]
+    fn cache_roundtrip_and_version_check() {
+        let dir = TempDir::new().unwrap();
+        let mut cache = BuildCache::default();
+        cache.sources.insert("a.ts".to_string(), "1".to_string());
+        cache.save(dir.path()).unwrap();
+
+        let loaded = BuildCache::load(dir.path());
+        assert_eq!(loaded.sources.get("a.ts").map(String::as_str), Some("1"));
+
+        let stale = r#"{"compilerVersion":"0.0.0-old","sources":{"a.ts":"1"},"aggregates":{}}"#;
+        std::fs::write(dir.path().join(CACHE_FILE), stale).unwrap();
+        assert!(BuildCache::load(dir.path()).sources.is_empty());
+    }
+}
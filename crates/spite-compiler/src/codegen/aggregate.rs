@@ -1,9 +1,16 @@
 //! Aggregate class code generation for TypeScript.
+//!
+//! Used for domains written in languages the generated project cannot import
+//! (Python): the aggregate class is rebuilt from the IR with the same shape as
+//! a hand-written TypeScript aggregate (`events`, `currentState`, `apply`).
 
-use crate::ir::{AggregateIR, StatementIR, ExpressionIR, BinaryOp, UnaryOp, DomainType};
-use super::ts_types::{to_ts_type, to_snake_case};
+use crate::ir::{AggregateIR, StatementIR, ExpressionIR, BinaryOp, UnaryOp};
+use super::ts_types::to_ts_type;
 
 /// Generates TypeScript code for an aggregate class.
+///
+/// Lives next to the generated `events.ts` and `state.ts`. Without a raw
+/// TypeScript apply body, each event copies its same-named fields into state.
 pub fn generate_aggregate(aggregate: &AggregateIR) -> String {
     let class_name = format!("{}Aggregate", aggregate.name);
     let state_type = format!("{}State", aggregate.name);
@@ -13,57 +20,34 @@ pub fn generate_aggregate(aggregate: &AggregateIR) -> String {
     let mut output = String::new();
 
     // Imports
-    output.push_str(&format!(
-        "import type {{ {} }} from '../events/{}.events';\n",
-        event_type,
-        to_snake_case(&aggregate.name)
-    ));
-    output.push_str(&format!(
-        "import type {{ {} }} from '../state/{}.state';\n",
-        state_type,
-        to_snake_case(&aggregate.name)
-    ));
-    output.push_str(&format!(
-        "import {{ {} }} from '../state/{}.state';\n\n",
-        initial_state,
-        to_snake_case(&aggregate.name)
-    ));
+    output.push_str(&format!("import type {{ {} }} from './events';\n", event_type));
+    output.push_str(&format!("import {{ {}, type {} }} from './state';\n\n", initial_state, state_type));
 
     // Class definition
     output.push_str(&format!("export class {} {{\n", class_name));
-    output.push_str(&format!("  private state: {} = {{ ...{} }};\n", state_type, initial_state));
-    output.push_str(&format!("  private pendingEvents: {}[] = [];\n\n", event_type));
+    output.push_str(&format!("  static readonly initialState: {} = {};\n\n", state_type, initial_state));
+    output.push_str(&format!("  readonly events: {}[] = [];\n", event_type));
+    output.push_str(&format!("  private state: {};\n\n", state_type));
 
-    // Static factory method
-    output.push_str(&format!("  static create(events: {}[]): {} {{\n", event_type, class_name));
-    output.push_str(&format!("    const agg = new {}();\n", class_name));
-    output.push_str("    for (const event of events) {\n");
-    output.push_str("      agg.applyEvent(event);\n");
-    output.push_str("    }\n");
-    output.push_str("    return agg;\n");
+    output.push_str(&format!(
+        "  constructor(initialState: {} = {}.initialState) {{\n",
+        state_type, class_name
+    ));
+    output.push_str("    this.state = structuredClone(initialState);\n");
     output.push_str("  }\n\n");
 
-    // Getters
-    output.push_str(&format!("  getState(): Readonly<{}> {{\n", state_type));
+    output.push_str(&format!("  get currentState(): {} {{\n", state_type));
     output.push_str("    return this.state;\n");
     output.push_str("  }\n\n");
 
-    output.push_str(&format!("  getPendingEvents(): {}[] {{\n", event_type));
-    output.push_str("    return [...this.pendingEvents];\n");
-    output.push_str("  }\n\n");
-
-    output.push_str("  clearPendingEvents(): void {\n");
-    output.push_str("    this.pendingEvents = [];\n");
-    output.push_str("  }\n\n");
-
     // Emit method
-    output.push_str(&format!("  private emit(event: {}): void {{\n", event_type));
-    output.push_str("    this.pendingEvents.push(event);\n");
-    output.push_str("    this.applyEvent(event);\n");
+    output.push_str(&format!("  protected emit(event: {}): void {{\n", event_type));
+    output.push_str("    this.events.push(event);\n");
+    output.push_str("    this.apply(event);\n");
     output.push_str("  }\n\n");
 
     // Apply method - use raw body if available (preserves user's apply logic)
-    output.push_str(&format!("  private applyEvent(event: {}): void ", event_type));
+    output.push_str(&format!("  apply(event: {}): void ", event_type));
 
     if let Some(raw_body) = &aggregate.raw_apply_body {
         // Use the raw apply body from source (preserves user's custom logic)
@@ -79,24 +63,11 @@ pub fn generate_aggregate(aggregate: &AggregateIR) -> String {
 
             // Generate state assignments
             for event_field in &variant.fields {
-                // Find matching state field
-                if let Some(state_field) = aggregate.state.fields.iter().find(|sf| sf.name == event_field.name) {
-                    let needs_some = matches!(&state_field.typ, DomainType::Option(inner)
-                        if !matches!(&event_field.typ, DomainType::Option(_))
-                        && **inner == event_field.typ);
-
-                    if needs_some {
-                        // State field is Option<T>, event field is T
-                        output.push_str(&format!(
-                            "        this.state.{} = event.{};\n",
-                            event_field.name, event_field.name
-                        ));
-                    } else {
-                        output.push_str(&format!(
-                            "        this.state.{} = event.{};\n",
-                            event_field.name, event_field.name
-                        ));
-                    }
+                if aggregate.state.fields.iter().any(|sf| sf.name == event_field.name) {
+                    output.push_str(&format!(
+                        "        this.state.{} = event.{};\n",
+                        event_field.name, event_field.name
+                    ));
                 }
             }
 
@@ -124,7 +95,8 @@ pub fn generate_aggregate(aggregate: &AggregateIR) -> String {
         output.push_str("  }\n\n");
     }
 
-    output.push_str("}\n");
+    output.truncate(output.trim_end().len());
+    output.push_str("\n}\n");
     output
 }
 
@@ -197,6 +169,8 @@ fn generate_expression(expr: &ExpressionIR) -> String {
         ExpressionIR::StringLiteral(s) => format!("\"{}\"", s.replace('\"', "\\\"")),
         ExpressionIR::NumberLiteral(n) => n.to_string(),
         ExpressionIR::BooleanLiteral(b) => b.to_string(),
+        // Python's None
+        ExpressionIR::Identifier(name) if name == "None" => "undefined".to_string(),
         ExpressionIR::Identifier(name) => name.clone(),
         ExpressionIR::StateAccess(field) => format!("this.state.{}", field),
         ExpressionIR::PropertyAccess { object, property } => {
@@ -208,15 +182,15 @@ fn generate_expression(expr: &ExpressionIR) -> String {
             arguments,
         } => {
             let obj = generate_expression(object);
-            let args: Vec<String> = arguments.iter().map(|a| generate_expression(a)).collect();
+            let args: Vec<String> = arguments.iter().map(generate_expression).collect();
             format!("{}.{}({})", obj, method, args.join(", "))
         }
         ExpressionIR::Call { callee, arguments } => {
-            let args: Vec<String> = arguments.iter().map(|a| generate_expression(a)).collect();
+            let args: Vec<String> = arguments.iter().map(generate_expression).collect();
             format!("{}({})", callee, args.join(", "))
         }
         ExpressionIR::New { callee, arguments } => {
-            let args: Vec<String> = arguments.iter().map(|a| generate_expression(a)).collect();
+            let args: Vec<String> = arguments.iter().map(generate_expression).collect();
             format!("new {}({})", callee, args.join(", "))
         }
         ExpressionIR::Binary {
@@ -257,7 +231,7 @@ fn generate_expression(expr: &ExpressionIR) -> String {
             format!("{{ {} }}", entries.join(", "))
         }
         ExpressionIR::Array(elements) => {
-            let elems: Vec<String> = elements.iter().map(|e| generate_expression(e)).collect();
+            let elems: Vec<String> = elements.iter().map(generate_expression).collect();
             format!("[{}]", elems.join(", "))
         }
     }
@@ -0,0 +1,396 @@
+//! Typed client SDK generation.
+//!
+//! Emits a standalone TypeScript package (`client/` in the project) with one
+//! method per command, aggregate read and projection query. Requests go
+//! through the `SpiteClient` runtime, which handles cookies, tenants and
+//! retrying commands that hit a concurrent write.
+
+use crate::ir::{AggregateIR, DomainIR, DomainType, ObjectType, ProjectionIR, ATTACHMENT_TYPE};
+use super::runtime::CLIENT;
+use super::ts_types::{to_camel_case, to_pascal_case, to_snake_case};
+
+/// Generates the client package as (filename, content) pairs relative to `client/`.
+pub fn generate_client(domain: &DomainIR, project_name: &str) -> Vec<(String, String)> {
+    vec![
+        ("package.json".to_string(), generate_client_package_json(project_name)),
+        ("runtime.ts".to_string(), CLIENT.to_string()),
+        ("index.ts".to_string(), generate_client_index(domain)),
+    ]
+}
+
+fn generate_client_package_json(project_name: &str) -> String {
+    format!(
+        r#"{{
+  "name": "{}-client",
+  "version": "0.1.0",
+  "type": "module",
+  "main": "index.ts",
+  "types": "index.ts"
+}}
+"#,
+        project_name
+    )
+}
+
+/// Generates index.ts with domain types and the typed client classes.
+pub fn generate_client_index(domain: &DomainIR) -> String {
+    let mut output = String::new();
+
+    output.push_str("/**\n");
+    output.push_str(" * Typed client for the SpiteStack API.\n");
+    output.push_str(" * DO NOT EDIT - regenerate with `spitestack compile --emit-client`\n");
+    output.push_str(" */\n\n");
+    let uses_attachments = domain
+        .aggregates
+        .iter()
+        .any(|a| a.commands.iter().any(|c| c.accepts_uploads()));
+    if uses_attachments {
+        output.push_str("import { SpiteClient, toFormData, type ClientConfig } from './runtime';\n\n");
+    } else {
+        output.push_str("import { SpiteClient, type ClientConfig } from './runtime';\n\n");
+    }
+    output.push_str("export * from './runtime';\n\n");
+
+    if uses_attachments {
+        output.push_str(&format!(
+            "export type {} = {{ hash: string; size: number; contentType: string; filename?: string }};\n\n",
+            ATTACHMENT_TYPE
+        ));
+    }
+
+    for aggregate in &domain.aggregates {
+        output.push_str(&generate_aggregate_client(aggregate));
+    }
+    for projection in &domain.projections {
+        if !projection.queries.is_empty() {
+            output.push_str(&generate_projection_client(projection));
+        }
+    }
+
+    // Root client
+    output.push_str("export class AppClient extends SpiteClient {\n");
+    for aggregate in &domain.aggregates {
+        output.push_str(&format!(
+            "  readonly {}: {}Client;\n",
+            to_camel_case(&aggregate.name),
+            aggregate.name
+        ));
+    }
+    for projection in domain.projections.iter().filter(|p| !p.queries.is_empty()) {
+        output.push_str(&format!(
+            "  readonly {}: {}ProjectionClient;\n",
+            to_camel_case(&projection.name),
+            projection.name
+        ));
+    }
+    output.push_str("\n  constructor(config: ClientConfig) {\n");
+    output.push_str("    super(config);\n");
+    for aggregate in &domain.aggregates {
+        output.push_str(&format!(
+            "    this.{} = new {}Client(this);\n",
+            to_camel_case(&aggregate.name),
+            aggregate.name
+        ));
+    }
+    for projection in domain.projections.iter().filter(|p| !p.queries.is_empty()) {
+        output.push_str(&format!(
+            "    this.{} = new {}ProjectionClient(this);\n",
+            to_camel_case(&projection.name),
+            projection.name
+        ));
+    }
+    output.push_str("  }\n");
+    output.push_str("}\n\n");
+
+    output.push_str("export function createClient(config: ClientConfig): AppClient {\n");
+    output.push_str("  return new AppClient(config);\n");
+    output.push_str("}\n");
+
+    output
+}
+
+fn generate_aggregate_client(aggregate: &AggregateIR) -> String {
+    let name = &aggregate.name;
+    let snake = to_snake_case(name);
+    let mut output = String::new();
+
+    output.push_str(&format!("// {}\n\n", name));
+    output.push_str(&format!("export type {}State = {};\n\n", name, client_object_type(&aggregate.state)));
+
+    if aggregate.events.variants.is_empty() {
+        output.push_str(&format!("export type {}Event = never;\n\n", name));
+    } else {
+        let variants: Vec<String> = aggregate
+            .events
+            .variants
+            .iter()
+            .map(|v| {
+                let mut fields = vec![format!("type: '{}'", v.name)];
+                fields.extend(v.fields.iter().map(|f| client_field(&f.name, &f.typ, false)));
+                format!("{{ {} }}", fields.join("; "))
+            })
+            .collect();
+        output.push_str(&format!("export type {}Event =\n  | {};\n\n", name, variants.join("\n  | ")));
+    }
+
+    for cmd in aggregate.commands.iter().filter(|c| !c.parameters.is_empty()) {
+        // Upload commands take the files themselves
+        let fields: Vec<String> = cmd
+            .parameters
+            .iter()
+            .map(|p| if cmd.accepts_uploads() { upload_field(&p.name, &p.typ) } else { client_field(&p.name, &p.typ, false) })
+            .collect();
+        output.push_str(&format!(
+            "export type {}{}Input = {{ {} }};\n",
+            name,
+            to_pascal_case(&cmd.name),
+            fields.join("; ")
+        ));
+    }
+    if aggregate.commands.iter().any(|c| !c.parameters.is_empty()) {
+        output.push('\n');
+    }
+
+    output.push_str(&format!("export class {}Client {{\n", name));
+    output.push_str("  constructor(private readonly client: SpiteClient) {}\n\n");
+    output.push_str("  get(streamId: string, tenant?: string) {\n");
+    output.push_str(&format!(
+        "    return this.client.query<{}State>('{}', streamId, tenant);\n",
+        name, snake
+    ));
+    output.push_str("  }\n");
+
+    for cmd in &aggregate.commands {
+        // Commands with a stream id template are addressed by its parameters instead
+        let (target, route) = match cmd.stream_id_template() {
+            Some(template) => {
+                let params = template.params();
+                let target: Vec<String> = params.iter().map(|p| format!("{}: string", p)).collect();
+                let segments: Vec<String> = params.iter().map(|p| format!("${{encodeURIComponent({})}}", p)).collect();
+                (target.join(", "), format!("`{}`", segments.join("/")))
+            }
+            None => ("streamId: string".to_string(), "streamId".to_string()),
+        };
+        let (params, payload) = if cmd.parameters.is_empty() {
+            (format!("{}, tenant?: string", target), "{}")
+        } else {
+            (
+                format!("{}, input: {}{}Input, tenant?: string", target, name, to_pascal_case(&cmd.name)),
+                if cmd.accepts_uploads() { "toFormData(input)" } else { "input" },
+            )
+        };
+        output.push('\n');
+        output.push_str(&format!("  {}({}) {{\n", to_camel_case(&cmd.name), params));
+        output.push_str(&format!(
+            "    return this.client.command<{}State, {}Event>('{}', {}, '{}', {}, tenant);\n",
+            name, name, snake, route, cmd.name, payload
+        ));
+        output.push_str("  }\n");
+    }
+    output.push_str("}\n\n");
+
+    output
+}
+
+fn generate_projection_client(projection: &ProjectionIR) -> String {
+    let name = &projection.name;
+    let snake = to_snake_case(name);
+    let mut output = String::new();
+
+    output.push_str(&format!("// {} projection\n\n", name));
+    output.push_str(&format!("export class {}ProjectionClient {{\n", name));
+    output.push_str("  constructor(private readonly client: SpiteClient) {}\n");
+
+    for query in &projection.queries {
+        let query_snake = to_snake_case(&query.name);
+        let result = match &query.return_type {
+            Some(typ) => client_type(typ),
+            None => "unknown".to_string(),
+        };
+        output.push('\n');
+        if query.parameters.is_empty() {
+            output.push_str(&format!("  {}(tenant?: string) {{\n", to_camel_case(&query.name)));
+            output.push_str(&format!(
+                "    return this.client.projection<{}>('{}', '{}', {{}}, tenant);\n",
+                result, snake, query_snake
+            ));
+        } else {
+            // Range query bounds are optional, as over REST
+            let fields: Vec<String> = query
+                .parameters
+                .iter()
+                .map(|p| client_field(&p.name, &p.typ, query.is_range_query))
+                .collect();
+            output.push_str(&format!(
+                "  {}(params: {{ {} }}, tenant?: string) {{\n",
+                to_camel_case(&query.name),
+                fields.join("; ")
+            ));
+            output.push_str(&format!(
+                "    return this.client.projection<{}>('{}', '{}', params, tenant);\n",
+                result, snake, query_snake
+            ));
+        }
+        output.push_str("  }\n");
+    }
+    output.push_str("}\n\n");
+
+    output
+}
+
+fn client_field(name: &str, typ: &DomainType, optional: bool) -> String {
+    match typ {
+        DomainType::Option(inner) => format!("{}?: {}", name, client_type(inner)),
+        _ if optional => format!("{}?: {}", name, client_type(typ)),
+        _ => format!("{}: {}", name, client_type(typ)),
+    }
+}
+
+/// Field of an upload command's input: attachments are passed as files.
+fn upload_field(name: &str, typ: &DomainType) -> String {
+    let (typ, optional) = match typ {
+        DomainType::Option(inner) => (inner.as_ref(), "?"),
+        typ => (typ, ""),
+    };
+    let ts = match typ {
+        t if t.is_attachment() => "Blob".to_string(),
+        DomainType::Array(inner) if inner.is_attachment() => "Blob[]".to_string(),
+        t => client_type(t),
+    };
+    format!("{}{}: {}", name, optional, ts)
+}
+
+/// Converts a DomainType to a TypeScript type usable outside the domain
+/// folder. Named types other than attachments are not resolved in the IR,
+/// so they become `unknown`.
+fn client_type(typ: &DomainType) -> String {
+    match typ {
+        DomainType::String => "string".to_string(),
+        DomainType::Number => "number".to_string(),
+        DomainType::Boolean => "boolean".to_string(),
+        DomainType::Array(inner) => match inner.as_ref() {
+            DomainType::Option(_) => format!("({})[]", client_type(inner)),
+            _ => format!("{}[]", client_type(inner)),
+        },
+        DomainType::Option(inner) => format!("{} | undefined", client_type(inner)),
+        DomainType::Object(obj) => client_object_type(obj),
+        DomainType::Reference(name) if name == ATTACHMENT_TYPE => name.clone(),
+        DomainType::Reference(_) => "unknown".to_string(),
+    }
+}
+
+fn client_object_type(obj: &ObjectType) -> String {
+    if obj.fields.is_empty() {
+        return "Record<string, never>".to_string();
+    }
+    let fields: Vec<String> = obj
+        .fields
+        .iter()
+        .map(|f| client_field(&f.name, &f.typ, f.optional))
+        .collect();
+    format!("{{ {} }}", fields.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{AccessLevel, CommandIR, EventField, EventTypeIR, EventVariant, ParameterIR};
+    use std::path::PathBuf;
+
+    #[test]
+    fn generates_typed_methods() {
+        let mut domain = DomainIR::new(PathBuf::new());
+        domain.aggregates.push(AggregateIR {
+            name: "Todo".to_string(),
+            source_path: PathBuf::new(),
+            span: None,
+            state: ObjectType { fields: vec![] },
+            initial_state: vec![],
+            events: EventTypeIR {
+                name: "TodoEvent".to_string(),
+                variants: vec![EventVariant {
+                    name: "Created".to_string(),
+                    span: None,
+                    fields: vec![EventField { name: "title".to_string(), typ: DomainType::String }],
+                }],
+            },
+            commands: vec![
+                CommandIR {
+                    name: "create".to_string(),
+                    span: None,
+                    parameters: vec![ParameterIR { name: "title".to_string(), typ: DomainType::String }],
+                    body: vec![],
+                    access: AccessLevel::Private,
+                    roles: vec![],
+                    stream_id: None,
+                },
+                CommandIR {
+                    name: "complete".to_string(),
+                    span: None,
+                    parameters: vec![],
+                    body: vec![],
+                    access: AccessLevel::Private,
+                    roles: vec![],
+                    stream_id: None,
+                },
+                CommandIR {
+                    name: "archive".to_string(),
+                    span: None,
+                    parameters: vec![],
+                    body: vec![],
+                    access: AccessLevel::Private,
+                    roles: vec![],
+                    stream_id: Some("todo-{listId}-{todoId}".to_string()),
+                },
+            ],
+            raw_apply_body: None,
+        });
+
+        let code = generate_client_index(&domain);
+
+        assert!(code.contains("export type TodoEvent =\n  | { type: 'Created'; title: string };"));
+        assert!(code.contains("export type TodoCreateInput = { title: string };"));
+        assert!(code.contains("  create(streamId: string, input: TodoCreateInput, tenant?: string) {"));
+        assert!(code.contains("this.client.command<TodoState, TodoEvent>('todo', streamId, 'complete', {}, tenant);"));
+        assert!(code.contains("  archive(listId: string, todoId: string, tenant?: string) {"));
+        assert!(code.contains("('todo', `${encodeURIComponent(listId)}/${encodeURIComponent(todoId)}`, 'archive', {}, tenant);"));
+        assert!(code.contains("    this.todo = new TodoClient(this);"));
+    }
+    #[test]
+    fn upload_commands_send_form_data() {
+        let mut domain = DomainIR::new(PathBuf::new());
+        domain.aggregates.push(AggregateIR {
+            name: "Doc".to_string(),
+            source_path: PathBuf::new(),
+            span: None,
+            state: ObjectType { fields: vec![] },
+            initial_state: vec![],
+            events: EventTypeIR { name: "DocEvent".to_string(), variants: vec![] },
+            commands: vec![CommandIR {
+                name: "upload".to_string(),
+                span: None,
+                parameters: vec![
+                    ParameterIR { name: "title".to_string(), typ: DomainType::String },
+                    ParameterIR { name: "file".to_string(), typ: DomainType::Reference(ATTACHMENT_TYPE.to_string()) },
+                    ParameterIR {
+                        name: "pages".to_string(),
+                        typ: DomainType::Option(Box::new(DomainType::Array(Box::new(DomainType::Reference(
+                            ATTACHMENT_TYPE.to_string(),
+                        ))))),
+                    },
+                ],
+                body: vec![],
+                access: AccessLevel::Private,
+                roles: vec![],
+                stream_id: None,
+            }],
+            raw_apply_body: None,
+        });
+
+        let code = generate_client_index(&domain);
+
+        assert!(code.contains("import { SpiteClient, toFormData, type ClientConfig } from './runtime';"));
+        assert!(code.contains("export type DocUploadInput = { title: string; file: Blob; pages?: Blob[] };"));
+        assert!(code.contains("('doc', streamId, 'upload', toFormData(input), tenant);"));
+    }
+}
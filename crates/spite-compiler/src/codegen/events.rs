@@ -33,22 +33,5 @@ pub fn generate_event_type(events: &EventTypeIR) -> String {
     output.push_str(&variants.join("\n"));
     output.push_str(";\n");
 
-    // Generate event type guard helpers
-    output.push('\n');
-    for variant in &events.variants {
-        output.push_str(&format!(
-            "export function is{}(event: {}): event is {{ type: \"{}\" }} & {} {{\n",
-            variant.name,
-            events.name,
-            variant.name,
-            events.name
-        ));
-        output.push_str(&format!(
-            "  return event.type === \"{}\";\n",
-            variant.name
-        ));
-        output.push_str("}\n\n");
-    }
-
     output
 }
\ No newline at end of file
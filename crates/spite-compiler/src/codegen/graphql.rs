@@ -0,0 +1,443 @@
+//! GraphQL schema and resolver generation for TypeScript.
+//!
+//! Resolvers call the same handlers as the REST router, so validation,
+//! telemetry and appends are identical; only the transport differs. Access
+//! rules are checked per field with the router's helpers.
+
+use crate::ir::{AccessLevel, DomainIR, DomainType, ParameterIR};
+use super::router::stream_id_expr;
+use super::ts_types::{to_camel_case, to_pascal_case, to_snake_case};
+
+/// Generates `graphql.ts`: the SDL, root resolvers and `executeGraphQL`.
+pub fn generate_graphql(domain: &DomainIR) -> String {
+    let mut output = String::new();
+
+    output.push_str("/**\n");
+    output.push_str(" * GraphQL API: mutations per command, queries per aggregate read and projection query.\n");
+    output.push_str(" * DO NOT EDIT - regenerate with `spitestack compile`\n");
+    output.push_str(" */\n\n");
+
+    // Imports
+    output.push_str("import { buildSchema, graphql, GraphQLError, type ExecutionResult } from 'graphql';\n");
+    output.push_str("import type { SpiteDbNapi, TelemetryDbNapi } from '@spitestack/db';\n");
+    output.push_str("import type { AuthUser } from './runtime/auth';\n");
+    output.push_str("import { SYSTEM_TENANT_ID } from './runtime/tenant';\n");
+    for aggregate in &domain.aggregates {
+        let handler_names: Vec<String> = aggregate
+            .commands
+            .iter()
+            .map(|cmd| format!("handle{}{}", aggregate.name, to_pascal_case(&cmd.name)))
+            .chain(std::iter::once(format!("handle{}Get", aggregate.name)))
+            .collect();
+        output.push_str(&format!(
+            "import {{ {} }} from './handlers/{}.handlers';\n",
+            handler_names.join(", "),
+            to_snake_case(&aggregate.name)
+        ));
+    }
+    for projection in &domain.projections {
+        if projection.queries.is_empty() {
+            continue;
+        }
+        let handler_names: Vec<String> = projection
+            .queries
+            .iter()
+            .map(|q| format!("handle{}{}", projection.name, to_pascal_case(&q.name)))
+            .collect();
+        output.push_str(&format!(
+            "import {{ {} }} from './handlers/{}.projection';\n",
+            handler_names.join(", "),
+            to_snake_case(&projection.name)
+        ));
+    }
+    if domain.projections.iter().any(|p| !p.queries.is_empty()) {
+        output.push_str("import { ensureProjection, projectionCapacityResponse } from './projections/manager';\n");
+    }
+    output.push('\n');
+
+    output.push_str("export const typeDefs = `\n");
+    output.push_str(&generate_sdl(domain));
+    output.push_str("`;\n\n");
+    output.push_str("const schema = buildSchema(typeDefs);\n\n");
+
+    // Per-request context supplied by the router
+    output.push_str("export type GraphQLContext = {\n");
+    output.push_str("  db: SpiteDbNapi;\n");
+    output.push_str("  telemetry: TelemetryDbNapi;\n");
+    output.push_str("  user?: AuthUser;\n");
+    output.push_str("  checkInternal: () => Response | null;\n");
+    output.push_str("  checkPrivate: () => { error: Response } | { user: AuthUser; tenant: string };\n");
+    output.push_str("  checkRoles: (user: AuthUser, tenant: string | undefined, roles: string[]) => Response | null;\n");
+    output.push_str("  createFinalize: (tenant: string, user?: { sub?: string }) => {\n");
+    output.push_str("    traceId: string;\n");
+    output.push_str("    spanId: string;\n");
+    output.push_str("    finalize: (response: Response, err?: unknown) => Response;\n");
+    output.push_str("  };\n");
+    output.push_str("};\n\n");
+
+    output.push_str(GRAPHQL_HELPERS);
+
+    // Root resolvers
+    output.push_str("const rootValue = {\n");
+    for aggregate in &domain.aggregates {
+        let field = to_camel_case(&aggregate.name);
+        output.push_str(&format!("  // {}\n", aggregate.name));
+        output.push_str(&format!(
+            "  {}: async ({{ streamId }}: {{ streamId: string }}, gql: GraphQLContext) => {{\n",
+            field
+        ));
+        output.push_str("    const { tenant, user } = await authorize(gql, 'internal', []);\n");
+        output.push_str("    const { traceId, spanId, finalize } = gql.createFinalize(tenant, user);\n");
+        output.push_str(&format!(
+            "    return unwrap(finalize(await handle{}Get({{ db: gql.db, telemetry: gql.telemetry, tenant }}, streamId, traceId, spanId)));\n",
+            aggregate.name
+        ));
+        output.push_str("  },\n");
+
+        for cmd in &aggregate.commands {
+            let template = cmd.stream_id_template();
+            let route_params = template.as_ref().map(|t| t.params()).unwrap_or_default();
+            let has_input = cmd.parameters.iter().any(|p| !route_params.contains(&p.name.as_str()));
+
+            if let Some(template) = &template {
+                // Stream id built from the template arguments, as the REST route does
+                let fields: Vec<String> = route_params.iter().map(|p| format!("{}: unknown", p)).collect();
+                output.push_str(&format!(
+                    "  {}{}: async ({{ input, ...params }}: {{ {}; input?: Record<string, unknown> }}, gql: GraphQLContext) => {{\n",
+                    field,
+                    to_pascal_case(&cmd.name),
+                    fields.join("; ")
+                ));
+                output.push_str(&format!("    const streamId = {};\n", stream_id_expr(template)));
+            } else {
+                output.push_str(&format!(
+                    "  {}{}: async ({{ streamId, input }}: {{ streamId: string; input?: unknown }}, gql: GraphQLContext) => {{\n",
+                    field,
+                    to_pascal_case(&cmd.name)
+                ));
+            }
+            output.push_str(&format!(
+                "    const {{ tenant, user }} = await authorize(gql, '{}', [{}]);\n",
+                access_name(cmd.access),
+                roles_list(&cmd.roles)
+            ));
+            output.push_str("    const { traceId, spanId, finalize } = gql.createFinalize(tenant, user);\n");
+
+            // Template arguments that are command parameters go into the body
+            let merged: Vec<String> = route_params
+                .iter()
+                .filter(|param| cmd.parameters.iter().any(|p| p.name == **param))
+                .map(|param| format!("{0}: params.{0}", param))
+                .collect();
+            let body = match (has_input, merged.is_empty()) {
+                (false, true) => "{}".to_string(),
+                (true, true) => "input".to_string(),
+                (false, false) => format!("{{ {} }}", merged.join(", ")),
+                (true, false) => format!("{{ ...input, {} }}", merged.join(", ")),
+            };
+            output.push_str(&format!(
+                "    return unwrap(finalize(await handle{}{}({{ db: gql.db, telemetry: gql.telemetry, tenant }}, streamId, {}, traceId, spanId)));\n",
+                aggregate.name,
+                to_pascal_case(&cmd.name),
+                body
+            ));
+            output.push_str("  },\n");
+        }
+    }
+    for projection in &domain.projections {
+        if projection.queries.is_empty() {
+            continue;
+        }
+        output.push_str(&format!("  // {} projection\n", projection.name));
+        for query in &projection.queries {
+            output.push_str(&format!(
+                "  {}{}: async (args: Record<string, unknown>, gql: GraphQLContext) => {{\n",
+                to_camel_case(&projection.name),
+                to_pascal_case(&query.name)
+            ));
+            output.push_str(&format!(
+                "    const {{ tenant, user }} = await authorize(gql, '{}', [{}]);\n",
+                access_name(projection.access),
+                roles_list(&projection.roles)
+            ));
+            output.push_str("    const { finalize } = gql.createFinalize(tenant, user);\n");
+            output.push_str(&format!(
+                "    if (!ensureProjection('{}', tenant)) return unwrap(finalize(projectionCapacityResponse()));\n",
+                projection.name
+            ));
+            let call_args = if query.parameters.is_empty() && !query.is_range_query {
+                "{ tenant, telemetry: gql.telemetry }"
+            } else {
+                "{ tenant, telemetry: gql.telemetry }, args as any"
+            };
+            output.push_str(&format!(
+                "    return unwrap(finalize(await handle{}{}({})));\n",
+                projection.name,
+                to_pascal_case(&query.name),
+                call_args
+            ));
+            output.push_str("  },\n");
+        }
+    }
+    output.push_str("};\n\n");
+
+    output.push_str("/**\n");
+    output.push_str(" * Execute a GraphQL request. Errors from handlers and access checks are\n");
+    output.push_str(" * reported in `errors` with the HTTP status under `extensions.status`.\n");
+    output.push_str(" */\n");
+    output.push_str("export async function executeGraphQL(\n");
+    output.push_str("  request: { query: string; variables?: Record<string, unknown>; operationName?: string },\n");
+    output.push_str("  gql: GraphQLContext\n");
+    output.push_str("): Promise<ExecutionResult> {\n");
+    output.push_str("  return graphql({\n");
+    output.push_str("    schema,\n");
+    output.push_str("    source: request.query,\n");
+    output.push_str("    rootValue,\n");
+    output.push_str("    contextValue: gql,\n");
+    output.push_str("    variableValues: request.variables,\n");
+    output.push_str("    operationName: request.operationName,\n");
+    output.push_str("  });\n");
+    output.push_str("}\n");
+
+    output
+}
+
+/// Generates the schema definition language for the domain.
+fn generate_sdl(domain: &DomainIR) -> String {
+    let mut query_fields = Vec::new();
+    let mut mutation_fields = Vec::new();
+    let mut inputs = String::new();
+
+    for aggregate in &domain.aggregates {
+        let field = to_camel_case(&aggregate.name);
+        query_fields.push(format!("  {}(streamId: ID!): JSON", field));
+
+        for cmd in &aggregate.commands {
+            let cmd_pascal = to_pascal_case(&cmd.name);
+            let template = cmd.stream_id_template();
+            let route_params = template.as_ref().map(|t| t.params()).unwrap_or_default();
+
+            // Commands with a stream id template take its parameters instead of a stream id
+            let mut args: Vec<String> = if template.is_some() {
+                route_params
+                    .iter()
+                    .map(|name| {
+                        let typ = cmd
+                            .parameters
+                            .iter()
+                            .find(|p| p.name == *name)
+                            .map(|p| graphql_type(&p.typ))
+                            .unwrap_or_else(|| "ID!".to_string());
+                        format!("{}: {}", name, typ)
+                    })
+                    .collect()
+            } else {
+                vec!["streamId: ID!".to_string()]
+            };
+
+            let input_params: Vec<&ParameterIR> = cmd
+                .parameters
+                .iter()
+                .filter(|p| !route_params.contains(&p.name.as_str()))
+                .collect();
+            if input_params.is_empty() {
+                mutation_fields.push(format!("  {}{}({}): JSON", field, cmd_pascal, args.join(", ")));
+                continue;
+            }
+
+            let input_name = format!("{}{}Input", aggregate.name, cmd_pascal);
+            args.push(format!("input: {}!", input_name));
+            mutation_fields.push(format!("  {}{}({}): JSON", field, cmd_pascal, args.join(", ")));
+            inputs.push_str(&format!("input {} {{\n", input_name));
+            for param in input_params {
+                inputs.push_str(&format!("  {}: {}\n", param.name, graphql_type(&param.typ)));
+            }
+            inputs.push_str("}\n\n");
+        }
+    }
+
+    for projection in &domain.projections {
+        for query in &projection.queries {
+            query_fields.push(format!(
+                "  {}{}{}: JSON",
+                to_camel_case(&projection.name),
+                to_pascal_case(&query.name),
+                graphql_args(&query.parameters, query.is_range_query)
+            ));
+        }
+    }
+
+    if query_fields.is_empty() {
+        // A schema must have at least one query field
+        query_fields.push("  _empty: Boolean".to_string());
+    }
+
+    let mut sdl = String::from("scalar JSON\n\n");
+    sdl.push_str(&format!("type Query {{\n{}\n}}\n\n", query_fields.join("\n")));
+    if !mutation_fields.is_empty() {
+        sdl.push_str(&format!("type Mutation {{\n{}\n}}\n\n", mutation_fields.join("\n")));
+    }
+    sdl.push_str(&inputs);
+    sdl
+}
+
+/// Formats query arguments. Range query bounds are optional, as over REST.
+fn graphql_args(params: &[ParameterIR], optional: bool) -> String {
+    if params.is_empty() {
+        return String::new();
+    }
+    let args: Vec<String> = params
+        .iter()
+        .map(|p| {
+            let typ = graphql_type(&p.typ);
+            let typ = if optional { typ.trim_end_matches('!').to_string() } else { typ };
+            format!("{}: {}", p.name, typ)
+        })
+        .collect();
+    format!("({})", args.join(", "))
+}
+
+/// Converts a DomainType to a GraphQL input type. Objects and references
+/// (including attachments) are passed as JSON.
+fn graphql_type(typ: &DomainType) -> String {
+    match typ {
+        DomainType::String => "String!".to_string(),
+        DomainType::Number => "Float!".to_string(),
+        DomainType::Boolean => "Boolean!".to_string(),
+        DomainType::Array(inner) => format!("[{}]!", graphql_type(inner)),
+        DomainType::Option(inner) => graphql_type(inner).trim_end_matches('!').to_string(),
+        DomainType::Object(_) | DomainType::Reference(_) => "JSON!".to_string(),
+    }
+}
+
+fn access_name(access: AccessLevel) -> &'static str {
+    match access {
+        AccessLevel::Public => "public",
+        AccessLevel::Internal => "internal",
+        AccessLevel::Private => "private",
+    }
+}
+
+fn roles_list(roles: &[String]) -> String {
+    roles.iter().map(|r| format!("'{}'", r)).collect::<Vec<_>>().join(", ")
+}
+
+/// Shared resolver helpers: access checks and handler response unwrapping.
+const GRAPHQL_HELPERS: &str = r#"async function toGraphQLError(response: Response): Promise<GraphQLError> {
+  const body = (await response.json().catch(() => ({}))) as { error?: string };
+  return new GraphQLError(body.error ?? response.statusText ?? 'Request failed', {
+    extensions: { status: response.status },
+  });
+}
+
+/** Return the handler's JSON body, or raise its error as a GraphQL error. */
+async function unwrap(response: Response): Promise<unknown> {
+  if (!response.ok) throw await toGraphQLError(response);
+  return response.json();
+}
+
+/** Apply the same access rules as the REST routes and resolve the tenant. */
+async function authorize(
+  gql: GraphQLContext,
+  access: 'public' | 'internal' | 'private',
+  roles: string[]
+): Promise<{ tenant: string; user?: AuthUser }> {
+  if (access === 'public') return { tenant: 'public', user: gql.user };
+
+  if (access === 'internal') {
+    const denied = gql.checkInternal() ?? gql.checkRoles(gql.user!, SYSTEM_TENANT_ID, roles);
+    if (denied) throw await toGraphQLError(denied);
+    return { tenant: SYSTEM_TENANT_ID, user: gql.user };
+  }
+
+  const result = gql.checkPrivate();
+  if ('error' in result) throw await toGraphQLError(result.error);
+  const denied = gql.checkRoles(result.user, result.tenant, roles);
+  if (denied) throw await toGraphQLError(denied);
+  return { tenant: result.tenant, user: result.user };
+}
+
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{AggregateIR, CommandIR, EventTypeIR, ObjectType};
+    use std::path::PathBuf;
+
+    fn make_test_domain() -> DomainIR {
+        let mut domain = DomainIR::new(PathBuf::new());
+        domain.aggregates.push(AggregateIR {
+            name: "Todo".to_string(),
+            source_path: PathBuf::new(),
+            span: None,
+            state: ObjectType { fields: vec![] },
+            initial_state: vec![],
+            events: EventTypeIR {
+                name: "TodoEvent".to_string(),
+                variants: vec![],
+            },
+            commands: vec![
+                CommandIR {
+                    name: "create".to_string(),
+                    span: None,
+                    parameters: vec![
+                        ParameterIR { name: "title".to_string(), typ: DomainType::String },
+                        ParameterIR {
+                            name: "tags".to_string(),
+                            typ: DomainType::Option(Box::new(DomainType::Array(Box::new(DomainType::String)))),
+                        },
+                    ],
+                    body: vec![],
+                    access: AccessLevel::Private,
+                    roles: vec!["editor".to_string()],
+                    stream_id: None,
+                },
+                CommandIR {
+                    name: "complete".to_string(),
+                    span: None,
+                    parameters: vec![],
+                    body: vec![],
+                    access: AccessLevel::Internal,
+                    roles: vec![],
+                    stream_id: None,
+                },
+            ],
+            raw_apply_body: None,
+        });
+        domain
+    }
+
+    #[test]
+    fn generates_schema_per_command() {
+        let code = generate_graphql(&make_test_domain());
+
+        assert!(code.contains("  todo(streamId: ID!): JSON"));
+        assert!(code.contains("  todoCreate(streamId: ID!, input: TodoCreateInput!): JSON"));
+        assert!(code.contains("  todoComplete(streamId: ID!): JSON"));
+        assert!(code.contains("input TodoCreateInput {\n  title: String!\n  tags: [String!]\n}"));
+    }
+
+    #[test]
+    fn resolvers_reuse_handlers_and_access_rules() {
+        let code = generate_graphql(&make_test_domain());
+
+        assert!(code.contains("import { handleTodoCreate, handleTodoComplete, handleTodoGet } from './handlers/todo.handlers';"));
+        assert!(code.contains("await authorize(gql, 'private', ['editor']);"));
+        assert!(code.contains("handleTodoCreate({ db: gql.db, telemetry: gql.telemetry, tenant }, streamId, input, traceId, spanId)"));
+        assert!(code.contains("handleTodoComplete({ db: gql.db, telemetry: gql.telemetry, tenant }, streamId, {}, traceId, spanId)"));
+    }
+    #[test]
+    fn templated_commands_build_the_stream_id() {
+        let mut domain = make_test_domain();
+        let create = &mut domain.aggregates[0].commands[0];
+        create.stream_id = Some("list-{listId}/todo-{title}".to_string());
+        let code = generate_graphql(&domain);
+
+        assert!(code.contains("  todoCreate(listId: ID!, title: String!, input: TodoCreateInput!): JSON"));
+        assert!(code.contains("input TodoCreateInput {\n  tags: [String!]\n}"));
+        assert!(code.contains("todoCreate: async ({ input, ...params }: { listId: unknown; title: unknown; input?: Record<string, unknown> }, gql: GraphQLContext)"));
+        assert!(code.contains("const streamId = `list-${params.listId}/todo-${params.title}`;"));
+        assert!(code.contains("streamId, { ...input, title: params.title }, traceId, spanId)"));
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! Generates TypeScript handlers directly without templates.
 
-use crate::ir::{AggregateIR, CommandIR};
+use crate::ir::{AggregateIR, CommandIR, DomainType};
 use super::ts_types::{to_snake_case, to_pascal_case};
 
 /// Generates TypeScript handlers for an aggregate.
@@ -20,9 +20,14 @@ pub fn generate_handlers(aggregate: &AggregateIR, domain_import_path: &str) -> S
 import {{ {name}Aggregate }} from '{domain_import_path}/{name}/aggregate';
 import type {{ {name}Event }} from '{domain_import_path}/{name}/events';
 import {{ emitTelemetry, finishSpan, logError, logWarn, metricCounter, metricHistogram, startSpan }} from '../runtime/telemetry';
+import {{ streamEventPosition }} from '../runtime/freshness';
 "#
     ));
 
+    if aggregate.commands.iter().any(|cmd| cmd.accepts_uploads()) {
+        code.push_str("import { AttachmentTooLargeError, findMissingAttachment, storeAttachments, type AttachmentFieldSpec } from '../runtime/attachments';\n");
+    }
+
     // Validator imports
     if !aggregate.commands.is_empty() {
         let validator_imports: Vec<String> = aggregate
@@ -111,13 +116,15 @@ export async function handle{name}Get(
     for (const e of storedEvents) {{
       aggregate.apply(JSON.parse(e.data.toString()) as {name}Event);
     }}
+    const revision = storedEvents.length > 0 ? storedEvents[storedEvents.length - 1].streamRev : 0;
 
     const response = new Response(JSON.stringify({{
       streamId,
+      revision,
       state: aggregate.currentState,
     }}), {{
       status: 200,
-      headers: {{ 'Content-Type': 'application/json' }},
+      headers: {{ 'Content-Type': 'application/json', ETag: `"${{revision}}"` }},
     }});
     return finalize(response, 'Ok');
   }} catch (err) {{
@@ -149,6 +156,13 @@ fn generate_command_handler(aggregate: &AggregateIR, cmd: &CommandIR) -> String
         format!("aggregate.{}({});", cmd.name, args.join(", "))
     };
 
+    // Upload commands store multipart files first and validate the resulting references
+    let (upload_block, validation_input) = if cmd.accepts_uploads() {
+        (generate_upload_block(cmd), "payload")
+    } else {
+        (String::new(), "body")
+    };
+
     format!(
         r#"
 export async function handle{name}{cmd_pascal}(
@@ -156,7 +170,8 @@ export async function handle{name}{cmd_pascal}(
   streamId: string,
   body: unknown,
   traceId?: string,
-  parentSpanId?: string
+  parentSpanId?: string,
+  expectedRev?: number
 ): Promise<Response> {{
   const resolvedTraceId = traceId ?? crypto.randomUUID();
   const span = startSpan(ctx.tenant, resolvedTraceId, 'command.{name}.{cmd_pascal}', parentSpanId, {{
@@ -195,7 +210,7 @@ export async function handle{name}{cmd_pascal}(
     return response;
   }};
 
-  const validation = validate{name}{cmd_pascal}Input(body);
+{upload_block}  const validation = validate{name}{cmd_pascal}Input({validation_input});
   if (!validation.ok) {{
     const response = new Response(JSON.stringify({{ errors: validation.errors }}), {{
       status: 400,
@@ -212,7 +227,18 @@ export async function handle{name}{cmd_pascal}(
     for (const e of storedEvents) {{
       aggregate.apply(JSON.parse(e.data.toString()) as {name}Event);
     }}
-    const currentRev = storedEvents.length > 0 ? storedEvents[storedEvents.length - 1].streamRev : 0;
+    // The store may return revisions as bigint; compare and add as numbers
+    const currentRev = storedEvents.length > 0 ? Number(storedEvents[storedEvents.length - 1].streamRev) : 0;
+    if (expectedRev !== undefined && expectedRev !== currentRev) {{
+      const response = new Response(JSON.stringify({{
+        error: `Expected revision ${{expectedRev}}, stream is at ${{currentRev}}`,
+        revision: currentRev,
+      }}), {{
+        status: 412,
+        headers: {{ 'Content-Type': 'application/json', ETag: `"${{currentRev}}"` }},
+      }});
+      return finalize(response, 'Error');
+    }}
 
     try {{
       {command_call}
@@ -226,6 +252,8 @@ export async function handle{name}{cmd_pascal}(
     }}
 
     const newEvents = aggregate.events;
+    // Global position of the last appended event, for read-your-writes
+    let position: number | undefined;
     if (newEvents.length > 0) {{
       const eventBuffers = newEvents.map(e => Buffer.from(JSON.stringify(e)));
       const commandId = crypto.randomUUID();
@@ -233,6 +261,8 @@ export async function handle{name}{cmd_pascal}(
       const payloadBytes = eventBuffers.reduce((sum, buf) => sum + buf.byteLength, 0);
       try {{
         await ctx.db.append(streamId, commandId, currentRev, eventBuffers, ctx.tenant);
+        // Read back from the store; the append already succeeded, so a failed read only drops the header
+        position = await streamEventPosition(ctx.db, streamId, currentRev + newEvents.length, ctx.tenant).catch(() => undefined);
         records.push(
           metricCounter(ctx.tenant, 'events.appended', newEvents.length, {{
             aggregate: '{name}',
@@ -248,6 +278,24 @@ export async function handle{name}{cmd_pascal}(
           }}, resolvedTraceId, span.spanId, commandId)
         );
       }} catch (err) {{
+        // If a concurrent writer moved the stream, the command can be retried against the new state
+        let latestRev = currentRev;
+        try {{
+          latestRev = Number(await ctx.db.getStreamRevision(streamId, ctx.tenant));
+        }} catch {{
+          // Report the append error below
+        }}
+        if (latestRev !== currentRev) {{
+          const response = new Response(JSON.stringify({{
+            error: `Stream ${{streamId}} moved from revision ${{currentRev}} to ${{latestRev}}`,
+            code: 'conflict',
+            revision: latestRev,
+          }}), {{
+            status: 409,
+            headers: {{ 'Content-Type': 'application/json', ETag: `"${{latestRev}}"` }},
+          }});
+          return finalize(response, 'Error');
+        }}
         const response = new Response(JSON.stringify({{ error: (err as Error).message }}), {{
           status: 500,
           headers: {{ 'Content-Type': 'application/json' }},
@@ -256,13 +304,17 @@ export async function handle{name}{cmd_pascal}(
       }}
     }}
 
+    const revision = currentRev + newEvents.length;
+    const headers: Record<string, string> = {{ 'Content-Type': 'application/json', ETag: `"${{revision}}"` }};
+    if (position !== undefined) headers['X-Global-Position'] = String(position);
     const response = new Response(JSON.stringify({{
       streamId,
+      revision,
       events: newEvents,
       state: aggregate.currentState,
     }}), {{
       status: 200,
-      headers: {{ 'Content-Type': 'application/json' }},
+      headers,
     }});
     return finalize(response, 'Ok');
   }} catch (err) {{
@@ -277,6 +329,58 @@ export async function handle{name}{cmd_pascal}(
     )
 }
 
+/// Generates the multipart handling for a command with `Attachment` parameters.
+///
+/// Files are streamed into the blob store and replaced with their references
+/// before validation, so the command (and its events) only ever see references.
+fn generate_upload_block(cmd: &CommandIR) -> String {
+    let spec: Vec<String> = cmd
+        .parameters
+        .iter()
+        .map(|p| format!("{}: '{}'", p.name, multipart_field_mode(&p.typ)))
+        .collect();
+
+    format!(
+        r#"  const uploadFields: AttachmentFieldSpec = {{ {spec} }};
+  let payload: unknown = body;
+  if (body instanceof FormData) {{
+    try {{
+      payload = await storeAttachments(body, uploadFields, ctx.tenant);
+    }} catch (err) {{
+      const tooLarge = err instanceof AttachmentTooLargeError;
+      const response = new Response(JSON.stringify({{ error: (err as Error).message }}), {{
+        status: tooLarge ? 413 : 500,
+        headers: {{ 'Content-Type': 'application/json' }},
+      }});
+      return finalize(response, 'Error', tooLarge ? undefined : err);
+    }}
+  }} else {{
+    // JSON bodies may only reference blobs this tenant already uploaded
+    const missing = await findMissingAttachment(body, uploadFields, ctx.tenant);
+    if (missing) {{
+      const response = new Response(JSON.stringify({{ error: `Attachment '${{missing}}' was not uploaded` }}), {{
+        status: 400,
+        headers: {{ 'Content-Type': 'application/json' }},
+      }});
+      return finalize(response, 'Error');
+    }}
+  }}
+"#,
+        spec = spec.join(", ")
+    )
+}
+
+/// How a multipart field is decoded for a parameter of the given type.
+fn multipart_field_mode(typ: &DomainType) -> &'static str {
+    match typ {
+        DomainType::Option(inner) => multipart_field_mode(inner),
+        DomainType::Array(inner) if inner.is_attachment() => "many",
+        t if t.is_attachment() => "one",
+        DomainType::String => "text",
+        _ => "json",
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -286,6 +390,7 @@ mod tests {
         AggregateIR {
             name: name.to_string(),
             source_path: std::path::PathBuf::new(),
+            span: None,
             state: ObjectType { fields: vec![] },
             initial_state: vec![],
             events: EventTypeIR {
@@ -300,6 +405,7 @@ mod tests {
     fn make_test_command(name: &str, params: Vec<(&str, DomainType)>) -> CommandIR {
         CommandIR {
             name: name.to_string(),
+            span: None,
             parameters: params
                 .into_iter()
                 .map(|(n, t)| ParameterIR {
@@ -310,6 +416,7 @@ mod tests {
             body: vec![],
             access: crate::ir::AccessLevel::Internal,
             roles: vec![],
+            stream_id: None,
         }
     }
 
@@ -344,6 +451,31 @@ mod tests {
         assert!(code.contains("new TodoAggregate()"));
     }
 
+    #[test]
+    fn reports_global_position_of_append() {
+        let agg = make_test_aggregate("Todo", vec![make_test_command("complete", vec![])]);
+        let code = generate_handlers(&agg, "../../domain");
+
+        assert!(code.contains("import { streamEventPosition } from '../runtime/freshness';"));
+        assert!(code.contains("await ctx.db.append(streamId, commandId, currentRev, eventBuffers, ctx.tenant);"));
+        assert!(code.contains("position = await streamEventPosition(ctx.db, streamId, currentRev + newEvents.length, ctx.tenant).catch(() => undefined);"));
+        assert!(!code.contains("lastPos"));
+        assert!(code.contains("if (position !== undefined) headers['X-Global-Position'] = String(position);"));
+    }
+
+    #[test]
+    fn reports_conflicts_from_the_stream_revision() {
+        let agg = make_test_aggregate("Todo", vec![make_test_command("complete", vec![])]);
+        let code = generate_handlers(&agg, "../../domain");
+
+        // Revisions may come back as bigint
+        assert!(code.contains("const currentRev = storedEvents.length > 0 ? Number(storedEvents[storedEvents.length - 1].streamRev) : 0;"));
+        assert!(code.contains("latestRev = Number(await ctx.db.getStreamRevision(streamId, ctx.tenant));"));
+        assert!(code.contains("if (latestRev !== currentRev) {"));
+        assert!(code.contains("code: 'conflict',"));
+        assert!(!code.contains("/conflict|expected revision/i"));
+    }
+
     #[test]
     fn generates_command_handler_with_params() {
         let agg = make_test_aggregate(
@@ -403,4 +535,32 @@ mod tests {
         assert!(!code.contains("flushTelemetry"));
         assert!(!code.contains("const finalize = async"));
     }
+
+    #[test]
+    fn stores_uploads_before_validation() {
+        let agg = make_test_aggregate(
+            "Document",
+            vec![
+                make_test_command(
+                    "upload",
+                    vec![
+                        ("title", DomainType::String),
+                        ("file", DomainType::Reference("Attachment".to_string())),
+                        ("pages", DomainType::Array(Box::new(DomainType::Reference("Attachment".to_string())))),
+                    ],
+                ),
+                make_test_command("rename", vec![("title", DomainType::String)]),
+            ],
+        );
+        let code = generate_handlers(&agg, "../../domain");
+
+        assert!(code.contains("import { AttachmentTooLargeError, findMissingAttachment, storeAttachments, type AttachmentFieldSpec } from '../runtime/attachments';"));
+        assert!(code.contains("const uploadFields: AttachmentFieldSpec = { title: 'text', file: 'one', pages: 'many' };"));
+        assert!(code.contains("payload = await storeAttachments(body, uploadFields, ctx.tenant);"));
+        assert!(code.contains("const missing = await findMissingAttachment(body, uploadFields, ctx.tenant);"));
+        // Shared blobs are left to the GC pass, never deleted by a failed command
+        assert!(!code.contains("discardAttachments"));
+        assert!(code.contains("validateDocumentUploadInput(payload)"));
+        assert!(code.contains("validateDocumentRenameInput(body)"));
+    }
 }
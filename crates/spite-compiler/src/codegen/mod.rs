@@ -4,21 +4,31 @@
 //! - Validators (pure TypeScript runtime validation)
 //! - Handlers (HTTP handlers that wire aggregates to SpiteDB)
 //! - Router (Bun.serve routing)
+//! - GraphQL schema and resolvers (when `api_style` is GraphQL)
+//! - OpenAPI spec describing the generated endpoints
 //! - Runtime modules (auth, utilities, etc.)
 //! - Projections (SQLite-backed read models with Bun workers)
 //!
 //! User's source files (events.ts, state.ts, aggregate.ts) are NOT regenerated -
-//! we import them directly from the domain folder.
+//! we import them directly from the domain folder. Frontends whose sources the
+//! project can't import get those files from [`generate_domain_sources`].
 
 mod ts_types;
+mod aggregate;
+mod events;
+mod state;
 mod validators;
 mod handlers;
 mod router;
+mod graphql;
+mod openapi;
 mod orchestrator;
 mod runtime;
 mod projection;
 pub mod project;
+pub mod client;
 
+use crate::config::ApiStyle;
 use crate::diagnostic::CompilerError;
 use crate::ir::DomainIR;
 use ts_types::to_snake_case;
@@ -29,6 +39,29 @@ pub struct GeneratedCode {
     pub files: Vec<(String, String)>,
 }
 
+/// Prefix of the SQLite files a projection's workers write under
+/// `data/projections`: `{prefix}{tenant}.db`.
+pub(crate) fn projection_data_prefix(name: &str) -> String {
+    format!("{}_", to_snake_case(name))
+}
+
+/// Generates TypeScript events, state and aggregate classes from domain IR.
+///
+/// Files are laid out like a TypeScript domain (`domain/{Name}/aggregate.ts`)
+/// so the handlers can import them with a `domain_import_path` of `../domain`.
+pub fn generate_domain_sources(domain: &DomainIR) -> Vec<(String, String)> {
+    let mut files = Vec::new();
+
+    for aggregate in &domain.aggregates {
+        let dir = format!("domain/{}", aggregate.name);
+        files.push((format!("{}/events.ts", dir), events::generate_event_type(&aggregate.events)));
+        files.push((format!("{}/state.ts", dir), state::generate_state_type(aggregate)));
+        files.push((format!("{}/aggregate.ts", dir), aggregate::generate_aggregate(aggregate)));
+    }
+
+    files
+}
+
 /// Generates TypeScript code from domain IR.
 /// 
 /// Only generates wiring code (validators, handlers, router).
@@ -36,7 +69,14 @@ pub struct GeneratedCode {
 /// 
 /// `domain_import_path` is the relative path from the generated handlers directory 
 /// to the domain source directory (e.g., "../../../../domain" for typical project structure).
-pub fn generate(domain: &DomainIR, domain_import_path: &str) -> Result<GeneratedCode, CompilerError> {
+///
+/// `api_style` picks the HTTP surface: REST routes, or a GraphQL endpoint whose
+/// resolvers call the same handlers.
+pub fn generate(
+    domain: &DomainIR,
+    domain_import_path: &str,
+    api_style: ApiStyle,
+) -> Result<GeneratedCode, CompilerError> {
     let mut files = Vec::new();
 
     // Generate code for each aggregate
@@ -66,6 +106,10 @@ pub fn generate(domain: &DomainIR, domain_import_path: &str) -> Result<Generated
             format!("orchestrators/{}.orchestrator.ts", snake_name),
             orchestrator_code,
         ));
+        files.push((
+            format!("orchestrators/{}.simulation.ts", snake_name),
+            orchestrator::generate_orchestrator_simulation(orch),
+        ));
     }
 
     // Generate projections (SQLite-backed read models with Bun workers)
@@ -73,9 +117,17 @@ pub fn generate(domain: &DomainIR, domain_import_path: &str) -> Result<Generated
     files.extend(projection_files);
 
     // Generate router
-    let router_code = router::generate_router(domain);
+    let router_code = router::generate_router(domain, api_style);
     files.push(("router.ts".to_string(), router_code));
 
+    // Describe the HTTP surface for client generators
+    files.push(("openapi.json".to_string(), openapi::generate_openapi(domain, api_style)));
+
+    // Generate GraphQL schema and resolvers
+    if api_style == ApiStyle::GraphQl {
+        files.push(("graphql.ts".to_string(), graphql::generate_graphql(domain)));
+    }
+
     // Generate index re-exports
     let aggregate_names: Vec<String> = domain.aggregates.iter().map(|a| a.name.clone()).collect();
     let index_code = project::generate_generated_index(&aggregate_names, domain_import_path);
@@ -0,0 +1,638 @@
+//! OpenAPI 3 spec generation.
+//!
+//! Describes the endpoints the generated router serves, with request and
+//! response schemas derived from command parameters, aggregate state, events
+//! and projection rows. Written as `openapi.json` next to `router.ts`.
+
+use serde_json::{json, Map, Value};
+
+use crate::config::ApiStyle;
+use crate::ir::{
+    AccessLevel, AggregateIR, ColumnDef, CommandIR, DomainIR, DomainType, ObjectType, ProjectionIR,
+    SqlType, ATTACHMENT_TYPE,
+};
+use super::ts_types::{to_camel_case, to_pascal_case, to_snake_case};
+
+/// OpenAPI version emitted.
+const OPENAPI_VERSION: &str = "3.0.3";
+
+/// Generates `openapi.json` for the domain.
+pub fn generate_openapi(domain: &DomainIR, api_style: ApiStyle) -> String {
+    let mut paths = Map::new();
+    let mut schemas = Map::new();
+
+    schemas.insert(
+        "Error".to_string(),
+        json!({
+            "type": "object",
+            "properties": { "error": { "type": "string" } },
+            "required": ["error"],
+        }),
+    );
+    schemas.insert(
+        "ValidationErrors".to_string(),
+        json!({
+            "type": "object",
+            "properties": {
+                "errors": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "field": { "type": "string" },
+                            "message": { "type": "string" },
+                        },
+                        "required": ["field", "message"],
+                    },
+                },
+            },
+            "required": ["errors"],
+        }),
+    );
+    if uses_attachments(domain) {
+        schemas.insert(
+            ATTACHMENT_TYPE.to_string(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "hash": { "type": "string" },
+                    "size": { "type": "number" },
+                    "contentType": { "type": "string" },
+                    "filename": { "type": "string" },
+                },
+                "required": ["hash", "size", "contentType"],
+            }),
+        );
+    }
+
+    for aggregate in &domain.aggregates {
+        add_aggregate_schemas(aggregate, &mut schemas);
+        if api_style == ApiStyle::Rest {
+            add_aggregate_paths(aggregate, &mut paths);
+        }
+    }
+    for projection in &domain.projections {
+        schemas.insert(format!("{}Row", projection.name), projection_row_schema(projection));
+        if api_style == ApiStyle::Rest {
+            add_projection_paths(projection, &mut paths);
+        }
+    }
+    if api_style == ApiStyle::GraphQl {
+        paths.insert("/graphql".to_string(), graphql_path());
+    }
+
+    let spec = json!({
+        "openapi": OPENAPI_VERSION,
+        "info": {
+            "title": "SpiteStack API",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "paths": paths,
+        "components": {
+            "schemas": schemas,
+            "securitySchemes": {
+                "bearerAuth": { "type": "http", "scheme": "bearer", "bearerFormat": "JWT" },
+                "cookieAuth": { "type": "apiKey", "in": "cookie", "name": "spite_token" },
+            },
+        },
+    });
+
+    // Serializing a Value cannot fail
+    serde_json::to_string_pretty(&spec).unwrap_or_default() + "\n"
+}
+
+/// State, event and command input schemas for an aggregate.
+fn add_aggregate_schemas(aggregate: &AggregateIR, schemas: &mut Map<String, Value>) {
+    let name = &aggregate.name;
+    schemas.insert(format!("{}State", name), object_schema(&aggregate.state));
+
+    let mut variants = Vec::new();
+    for variant in &aggregate.events.variants {
+        let mut properties = Map::new();
+        let mut required = vec![json!("type")];
+        properties.insert("type".to_string(), json!({ "type": "string", "enum": [variant.name] }));
+        for field in &variant.fields {
+            properties.insert(field.name.clone(), type_schema(&field.typ));
+            if !matches!(field.typ, DomainType::Option(_)) {
+                required.push(json!(field.name));
+            }
+        }
+        let key = format!("{}{}Event", name, variant.name);
+        schemas.insert(
+            key.clone(),
+            json!({ "type": "object", "properties": properties, "required": required }),
+        );
+        variants.push(json!({ "$ref": format!("#/components/schemas/{}", key) }));
+    }
+    schemas.insert(format!("{}Event", name), json!({ "oneOf": variants }));
+
+    for cmd in &aggregate.commands {
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+        for param in &cmd.parameters {
+            properties.insert(param.name.clone(), type_schema(&param.typ));
+            if !matches!(param.typ, DomainType::Option(_)) {
+                required.push(json!(param.name));
+            }
+        }
+        let mut schema = json!({ "type": "object", "properties": properties });
+        if !required.is_empty() {
+            schema["required"] = json!(required);
+        }
+        schemas.insert(format!("{}{}Input", name, to_pascal_case(&cmd.name)), schema);
+    }
+}
+
+/// Aggregate read and command endpoints.
+fn add_aggregate_paths(aggregate: &AggregateIR, paths: &mut Map<String, Value>) {
+    let name = &aggregate.name;
+    let snake = to_snake_case(name);
+    let stream_param = json!({
+        "name": "streamId",
+        "in": "path",
+        "required": true,
+        "schema": { "type": "string" },
+    });
+
+    let mut get = json!({
+        "operationId": format!("get{}", name),
+        "tags": [name],
+        "summary": format!("Read {} state", name),
+        "parameters": [stream_param],
+        "responses": with_access_errors(AccessLevel::Internal, json!({
+            "200": json_response("Current state", json!({
+                "type": "object",
+                "properties": {
+                    "streamId": { "type": "string" },
+                    "revision": { "type": "integer" },
+                    "state": schema_ref(&format!("{}State", name)),
+                },
+                "required": ["streamId", "revision", "state"],
+            })),
+            "500": json_response("Server error", schema_ref("Error")),
+        })),
+    });
+    apply_security(&mut get, AccessLevel::Internal);
+    paths.insert(format!("/{}/{{streamId}}", snake), json!({ "get": get }));
+
+    for cmd in &aggregate.commands {
+        let cmd_pascal = to_pascal_case(&cmd.name);
+        let input = schema_ref(&format!("{}{}Input", name, cmd_pascal));
+        let mut content = Map::new();
+        content.insert("application/json".to_string(), json!({ "schema": input }));
+        if cmd.accepts_uploads() {
+            content.insert("multipart/form-data".to_string(), multipart_media_type(cmd));
+        }
+
+        let mut responses = json!({
+            "200": json_response("Command accepted", json!({
+                "type": "object",
+                "properties": {
+                    "streamId": { "type": "string" },
+                    "revision": { "type": "integer" },
+                    "events": { "type": "array", "items": schema_ref(&format!("{}Event", name)) },
+                    "state": schema_ref(&format!("{}State", name)),
+                },
+                "required": ["streamId", "revision", "events", "state"],
+            })),
+            "400": json_response("Invalid input or rejected by the aggregate", json!({
+                "oneOf": [schema_ref("ValidationErrors"), schema_ref("Error")],
+            })),
+            "409": json_response("Stream changed concurrently; safe to retry", json!({
+                "type": "object",
+                "properties": {
+                    "error": { "type": "string" },
+                    "code": { "type": "string", "enum": ["conflict"] },
+                    "revision": { "type": "integer" },
+                },
+                "required": ["error", "code", "revision"],
+            })),
+            "412": json_response("Stream is not at the If-Match revision", schema_ref("Error")),
+            "500": json_response("Server error", schema_ref("Error")),
+        });
+        if cmd.accepts_uploads() {
+            responses["413"] = json_response("Upload too large", schema_ref("Error"));
+        }
+
+        // A stream id template replaces `{streamId}` with one segment per parameter
+        let template = cmd.stream_id_template();
+        let (route, path_params) = match &template {
+            Some(template) => {
+                let params = template.params();
+                let segments: Vec<String> = params.iter().map(|p| format!("{{{}}}", p)).collect();
+                let path_params: Vec<Value> = params
+                    .iter()
+                    .map(|p| json!({ "name": p, "in": "path", "required": true, "schema": { "type": "string" } }))
+                    .collect();
+                (format!("/{}/{}/{}", snake, segments.join("/"), cmd.name), path_params)
+            }
+            None => (format!("/{}/{{streamId}}/{}", snake, cmd.name), vec![stream_param.clone()]),
+        };
+
+        let mut post = json!({
+            "operationId": format!("{}{}", to_camel_case(name), cmd_pascal),
+            "tags": [name],
+            "parameters": path_params,
+            "requestBody": { "required": true, "content": content },
+            "responses": with_access_errors(cmd.access, responses),
+        });
+        if let Some(template) = &cmd.stream_id {
+            post["description"] = json!(format!("Targets stream `{}`.", template));
+        }
+        apply_tenant_header(&mut post, cmd.access);
+        apply_if_match_header(&mut post);
+        apply_security(&mut post, cmd.access);
+        paths.insert(route, json!({ "post": post }));
+    }
+}
+
+/// Projection query endpoints.
+fn add_projection_paths(projection: &ProjectionIR, paths: &mut Map<String, Value>) {
+    let snake = to_snake_case(&projection.name);
+    let row = schema_ref(&format!("{}Row", projection.name));
+
+    for query in &projection.queries {
+        let query_snake = to_snake_case(&query.name);
+        let mut path = format!("/projections/{}/{}", snake, query_snake);
+        let mut parameters = Vec::new();
+
+        if query.parameters.len() == 1 && !query.is_range_query {
+            // Single point-query parameter is taken from the path
+            let param = &query.parameters[0];
+            path.push_str(&format!("/{{{}}}", param.name));
+            parameters.push(json!({
+                "name": param.name,
+                "in": "path",
+                "required": true,
+                "schema": type_schema(&param.typ),
+            }));
+        } else {
+            for param in &query.parameters {
+                parameters.push(json!({
+                    "name": param.name,
+                    "in": "query",
+                    "required": !query.is_range_query,
+                    "schema": type_schema(&param.typ),
+                }));
+            }
+        }
+
+        let success = if query.is_range_query {
+            json!({
+                "type": "object",
+                "properties": {
+                    "total": { "type": "number" },
+                    "rows": { "type": "array", "items": row },
+                },
+                "required": ["total", "rows"],
+            })
+        } else {
+            row.clone()
+        };
+        let mut responses = json!({
+            "200": json_response("Query result", success),
+            "500": json_response("Server error", schema_ref("Error")),
+        });
+        if !query.is_range_query {
+            responses["404"] = json_response("Not found", schema_ref("Error"));
+        }
+
+        let mut get = json!({
+            "operationId": format!("{}{}", to_camel_case(&projection.name), to_pascal_case(&query.name)),
+            "tags": [projection.name],
+            "parameters": parameters,
+            "responses": with_access_errors(projection.access, responses),
+        });
+        apply_tenant_header(&mut get, projection.access);
+        apply_security(&mut get, projection.access);
+        paths.insert(path, json!({ "get": get }));
+    }
+}
+
+/// The single endpoint served when the API style is GraphQL.
+fn graphql_path() -> Value {
+    json!({
+        "post": {
+            "operationId": "graphql",
+            "summary": "GraphQL endpoint (access is checked per field)",
+            "requestBody": {
+                "required": true,
+                "content": {
+                    "application/json": {
+                        "schema": {
+                            "type": "object",
+                            "properties": {
+                                "query": { "type": "string" },
+                                "variables": { "type": "object" },
+                                "operationName": { "type": "string" },
+                            },
+                            "required": ["query"],
+                        },
+                    },
+                },
+            },
+            "responses": {
+                "200": json_response("GraphQL result", json!({
+                    "type": "object",
+                    "properties": {
+                        "data": { "type": "object", "nullable": true },
+                        "errors": { "type": "array", "items": { "type": "object" } },
+                    },
+                })),
+            },
+        },
+    })
+}
+
+/// Row schema from the projection's SQLite columns.
+fn projection_row_schema(projection: &ProjectionIR) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    let columns = projection
+        .schema
+        .primary_keys
+        .iter()
+        .chain(projection.schema.columns.iter());
+    for column in columns {
+        properties.insert(column.name.clone(), column_schema(column));
+        if !column.nullable {
+            required.push(json!(column.name));
+        }
+    }
+    json!({ "type": "object", "properties": properties, "required": required })
+}
+
+fn column_schema(column: &ColumnDef) -> Value {
+    let mut schema = match column.sql_type {
+        SqlType::Text => json!({ "type": "string" }),
+        SqlType::Integer => json!({ "type": "integer" }),
+        SqlType::Real => json!({ "type": "number" }),
+        SqlType::Blob => json!({ "type": "string", "format": "binary" }),
+        // Any JSON value; arrays and objects are returned parsed
+        SqlType::Json => json!({}),
+    };
+    if column.nullable {
+        schema["nullable"] = json!(true);
+    }
+    schema
+}
+
+/// Converts a DomainType to a schema. Optional types are handled by the
+/// caller leaving the property out of `required`.
+fn type_schema(typ: &DomainType) -> Value {
+    match typ {
+        DomainType::String => json!({ "type": "string" }),
+        DomainType::Number => json!({ "type": "number" }),
+        DomainType::Boolean => json!({ "type": "boolean" }),
+        DomainType::Array(inner) => json!({ "type": "array", "items": type_schema(inner) }),
+        DomainType::Option(inner) => type_schema(inner),
+        DomainType::Object(obj) => object_schema(obj),
+        DomainType::Reference(name) if name == ATTACHMENT_TYPE => schema_ref(ATTACHMENT_TYPE),
+        // Named types are not resolved in the IR; accept any value
+        DomainType::Reference(_) => json!({}),
+    }
+}
+
+/// Multipart body of an upload command. Attachment fields are files; other
+/// fields are text, JSON-encoded unless they are strings.
+fn multipart_media_type(cmd: &CommandIR) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    let mut encoding = Map::new();
+    for param in &cmd.parameters {
+        let typ = match &param.typ {
+            DomainType::Option(inner) => inner.as_ref(),
+            typ => typ,
+        };
+        let schema = match typ {
+            t if t.is_attachment() => json!({ "type": "string", "format": "binary" }),
+            DomainType::Array(inner) if inner.is_attachment() => {
+                json!({ "type": "array", "items": { "type": "string", "format": "binary" } })
+            }
+            DomainType::String => type_schema(typ),
+            _ => {
+                encoding.insert(param.name.clone(), json!({ "contentType": "application/json" }));
+                type_schema(typ)
+            }
+        };
+        properties.insert(param.name.clone(), schema);
+        if !matches!(param.typ, DomainType::Option(_)) {
+            required.push(json!(param.name));
+        }
+    }
+
+    let mut schema = json!({ "type": "object", "properties": properties });
+    if !required.is_empty() {
+        schema["required"] = json!(required);
+    }
+    let mut media_type = json!({ "schema": schema });
+    if !encoding.is_empty() {
+        media_type["encoding"] = json!(encoding);
+    }
+    media_type
+}
+
+fn object_schema(obj: &ObjectType) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for field in &obj.fields {
+        properties.insert(field.name.clone(), type_schema(&field.typ));
+        if !field.optional && !matches!(field.typ, DomainType::Option(_)) {
+            required.push(json!(field.name));
+        }
+    }
+    let mut schema = json!({ "type": "object", "properties": properties });
+    if !required.is_empty() {
+        schema["required"] = json!(required);
+    }
+    schema
+}
+
+fn uses_attachments(domain: &DomainIR) -> bool {
+    domain
+        .aggregates
+        .iter()
+        .any(|a| a.commands.iter().any(|c| c.accepts_uploads()))
+}
+
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{}", name) })
+}
+
+fn json_response(description: &str, schema: Value) -> Value {
+    json!({
+        "description": description,
+        "content": { "application/json": { "schema": schema } },
+    })
+}
+
+/// Adds the 401/403 responses the router's access checks can return.
+fn with_access_errors(access: AccessLevel, mut responses: Value) -> Value {
+    if access != AccessLevel::Public {
+        responses["401"] = json_response("Not authenticated", schema_ref("Error"));
+        responses["403"] = json_response("Forbidden", schema_ref("Error"));
+    }
+    responses
+}
+
+/// Commands accept the revision from a previous read as an optimistic concurrency check.
+fn apply_if_match_header(operation: &mut Value) {
+    if let Some(parameters) = operation["parameters"].as_array_mut() {
+        parameters.push(json!({
+            "name": "If-Match",
+            "in": "header",
+            "required": false,
+            "description": "Reject the command unless the stream is still at this revision",
+            "schema": { "type": "string" },
+        }));
+    }
+}
+
+/// Private endpoints resolve the tenant from `X-Tenant-ID` when the user belongs to several.
+fn apply_tenant_header(operation: &mut Value, access: AccessLevel) {
+    if access != AccessLevel::Private {
+        return;
+    }
+    if let Some(parameters) = operation["parameters"].as_array_mut() {
+        parameters.push(json!({
+            "name": "X-Tenant-ID",
+            "in": "header",
+            "required": false,
+            "schema": { "type": "string" },
+        }));
+    }
+}
+
+fn apply_security(operation: &mut Value, access: AccessLevel) {
+    if access != AccessLevel::Public {
+        operation["security"] = json!([{ "bearerAuth": [] }, { "cookieAuth": [] }]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{EventField, EventTypeIR, EventVariant, FieldDef, ParameterIR};
+    use std::path::PathBuf;
+
+    fn make_test_domain() -> DomainIR {
+        let mut domain = DomainIR::new(PathBuf::new());
+        domain.aggregates.push(AggregateIR {
+            name: "Todo".to_string(),
+            source_path: PathBuf::new(),
+            span: None,
+            state: ObjectType {
+                fields: vec![FieldDef {
+                    name: "title".to_string(),
+                    typ: DomainType::String,
+                    optional: false,
+                }],
+            },
+            initial_state: vec![],
+            events: EventTypeIR {
+                name: "TodoEvent".to_string(),
+                variants: vec![EventVariant {
+                    name: "Created".to_string(),
+                    span: None,
+                    fields: vec![EventField {
+                        name: "title".to_string(),
+                        typ: DomainType::String,
+                    }],
+                }],
+            },
+            commands: vec![CommandIR {
+                name: "create".to_string(),
+                span: None,
+                parameters: vec![
+                    ParameterIR { name: "title".to_string(), typ: DomainType::String },
+                    ParameterIR {
+                        name: "due".to_string(),
+                        typ: DomainType::Option(Box::new(DomainType::Number)),
+                    },
+                ],
+                body: vec![],
+                access: AccessLevel::Private,
+                roles: vec![],
+                stream_id: None,
+            }],
+            raw_apply_body: None,
+        });
+        domain
+    }
+
+    fn spec(domain: &DomainIR, api_style: ApiStyle) -> Value {
+        serde_json::from_str(&generate_openapi(domain, api_style)).unwrap()
+    }
+
+    #[test]
+    fn describes_command_endpoints() {
+        let spec = spec(&make_test_domain(), ApiStyle::Rest);
+
+        let post = &spec["paths"]["/todo/{streamId}/create"]["post"];
+        assert_eq!(post["operationId"], "todoCreate");
+        assert_eq!(
+            post["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/TodoCreateInput"
+        );
+        assert!(post["responses"]["401"].is_object());
+        assert_eq!(post["parameters"][1]["name"], "X-Tenant-ID");
+
+        let input = &spec["components"]["schemas"]["TodoCreateInput"];
+        assert_eq!(input["required"], json!(["title"]));
+        assert_eq!(input["properties"]["due"]["type"], "number");
+        assert_eq!(
+            spec["components"]["schemas"]["TodoCreatedEvent"]["properties"]["type"]["enum"],
+            json!(["Created"])
+        );
+        assert!(spec["paths"]["/todo/{streamId}"]["get"].is_object());
+    }
+
+    #[test]
+    fn stream_id_template_shapes_command_path() {
+        let mut domain = make_test_domain();
+        domain.aggregates[0].commands[0].stream_id = Some("todo-{listId}-{title}".to_string());
+        let spec = spec(&domain, ApiStyle::Rest);
+
+        assert!(spec["paths"].get("/todo/{streamId}/create").is_none());
+        let post = &spec["paths"]["/todo/{listId}/{title}/create"]["post"];
+        assert_eq!(post["parameters"][0]["name"], "listId");
+        assert_eq!(post["parameters"][1]["name"], "title");
+        assert_eq!(post["parameters"][3]["name"], "If-Match");
+        assert!(post["responses"]["412"].is_object());
+    }
+
+    #[test]
+    fn graphql_style_describes_single_endpoint() {
+        let spec = spec(&make_test_domain(), ApiStyle::GraphQl);
+
+        assert!(spec["paths"]["/graphql"]["post"].is_object());
+        assert!(spec["paths"].get("/todo/{streamId}").is_none());
+        assert!(spec["components"]["schemas"]["TodoState"].is_object());
+    }
+    #[test]
+    fn upload_commands_describe_files_as_binary() {
+        let mut domain = make_test_domain();
+        let params = &mut domain.aggregates[0].commands[0].parameters;
+        params.push(ParameterIR { name: "photo".to_string(), typ: DomainType::Reference(ATTACHMENT_TYPE.to_string()) });
+        params.push(ParameterIR {
+            name: "scans".to_string(),
+            typ: DomainType::Option(Box::new(DomainType::Array(Box::new(DomainType::Reference(
+                ATTACHMENT_TYPE.to_string(),
+            ))))),
+        });
+        let spec = spec(&domain, ApiStyle::Rest);
+
+        let multipart = &spec["paths"]["/todo/{streamId}/create"]["post"]["requestBody"]["content"]["multipart/form-data"];
+        let properties = &multipart["schema"]["properties"];
+        assert_eq!(properties["photo"], json!({ "type": "string", "format": "binary" }));
+        assert_eq!(properties["scans"]["items"]["format"], "binary");
+        assert_eq!(properties["title"]["type"], "string");
+        assert_eq!(multipart["schema"]["required"], json!(["title", "photo"]));
+        assert_eq!(multipart["encoding"]["due"]["contentType"], "application/json");
+        assert!(multipart["encoding"].get("title").is_none());
+        assert_eq!(
+            spec["components"]["schemas"]["TodoCreateInput"]["properties"]["photo"]["$ref"],
+            "#/components/schemas/Attachment"
+        );
+    }
+}
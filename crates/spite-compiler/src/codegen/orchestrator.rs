@@ -188,6 +188,37 @@ pub fn generate_orchestrator(orchestrator: &OrchestratorIR) -> String {
     output
 }
 
+/// Generates a simulation helper for an orchestrator.
+///
+/// Wraps `runtime/simulator` so tests can drive the orchestrator against an
+/// in-memory store with virtual time and seeded ids.
+pub fn generate_orchestrator_simulation(orchestrator: &OrchestratorIR) -> String {
+    let name = &orchestrator.name;
+    let snake_name = to_snake_case(name);
+
+    format!(
+        r#"/**
+ * Simulation helpers for the {name} orchestrator.
+ * DO NOT EDIT - regenerate with `spitestack compile`
+ */
+
+import {{ createSimulation, type SimulationOptions }} from '../runtime/simulator';
+import {{ execute{name}, type {name}Input }} from './{snake_name}.orchestrator';
+
+export function simulate{name}(options: SimulationOptions = {{}}) {{
+  const sim = createSimulation(options);
+  return {{
+    ...sim,
+    /** Run to completion; timers only fire if the test advances the clock. */
+    execute: (input: {name}Input) => sim.run(() => execute{name}(sim.ctx, input)),
+    /** Start without awaiting so the test can advance time, then `finish()`. */
+    start: (input: {name}Input) => sim.start(() => execute{name}(sim.ctx, input)),
+  }};
+}}
+"#
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -218,4 +249,14 @@ mod tests {
         assert!(!code.contains("flushTelemetry"));
         assert!(!code.contains("const finalize = async"));
     }
+
+    #[test]
+    fn generates_simulation_helper() {
+        let orchestrator = make_test_orchestrator("ProcessOrder");
+        let code = generate_orchestrator_simulation(&orchestrator);
+
+        assert!(code.contains("import { executeProcessOrder, type ProcessOrderInput } from './process_order.orchestrator';"));
+        assert!(code.contains("export function simulateProcessOrder(options: SimulationOptions = {}) {"));
+        assert!(code.contains("sim.run(() => executeProcessOrder(sim.ctx, input))"));
+    }
 }
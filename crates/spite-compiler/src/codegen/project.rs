@@ -2,13 +2,19 @@
 
 use std::path::Path;
 
+use crate::config::ApiStyle;
+
 /// Generates package.json for the SpiteStack project.
 /// If `spitedb_napi_path` is provided, uses a file: reference. Otherwise uses workspace:*.
-pub fn generate_package_json(name: &str, spitedb_napi_path: Option<&str>) -> String {
-    let db_dep = match spitedb_napi_path {
+/// GraphQL projects also depend on `graphql`.
+pub fn generate_package_json(name: &str, spitedb_napi_path: Option<&str>, api_style: ApiStyle) -> String {
+    let mut deps = match spitedb_napi_path {
         Some(path) => format!("\"@spitestack/db\": \"file:{}\"", path),
         None => "\"@spitestack/db\": \"workspace:*\"".to_string(),
     };
+    if api_style == ApiStyle::GraphQl {
+        deps.push_str(",\n    \"graphql\": \"^16.8.0\"");
+    }
 
     format!(
         r#"{{
@@ -18,6 +24,7 @@ pub fn generate_package_json(name: &str, spitedb_napi_path: Option<&str>) -> Str
     "dev": "bun run --hot src/index.ts",
     "build": "bun build src/index.ts --outdir dist --target bun",
     "start": "bun run dist/index.js",
+    "seed": "bun run src/seed.ts",
     "typecheck": "tsc --noEmit"
   }},
   "dependencies": {{
@@ -32,7 +39,7 @@ pub fn generate_package_json(name: &str, spitedb_napi_path: Option<&str>) -> Str
   }}
 }}
 "#,
-        name, db_dep
+        name, deps
     )
 }
 
@@ -92,13 +99,28 @@ pub fn generate_index_ts(port: u16, app_name: &str, projection_names: &[String])
         .collect::<Vec<_>>()
         .join(", ");
 
+    // Projection workers read the same event store as the server
+    let (projection_import, projection_start) = if projection_names.is_empty() {
+        (String::new(), String::new())
+    } else {
+        (
+            "import { startProjections } from './generated/projections/manager';\n".to_string(),
+            format!(
+                "\n// Build read models in background workers\nconst projectionsDir = './data/projections';\nawait mkdir(projectionsDir, {{ recursive: true }});\nstartProjections({{ eventDbPath: `${{eventsDir}}/{}.db`, dataDir: projectionsDir }});\n",
+                app_name
+            ),
+        )
+    };
+
     format!(
         r#"import {{ SpiteDbNapi, TelemetryDbNapi }} from '@spitestack/db';
 import {{ mkdir }} from 'node:fs/promises';
 import {{ createRouter }} from './generated/router';
 import {{ ensureSystemAdmin }} from './generated/runtime/identity';
 import {{ createAdminWebSocketHandler }} from './generated/runtime/admin-ws';
-
+import {{ jwksKeySource }} from './generated/runtime/auth';
+import {{ collectAttachmentGarbage }} from './generated/runtime/attachments';
+{projection_import}
 const eventsDir = './data/events';
 const telemetryDir = './data/telemetry';
 
@@ -109,7 +131,7 @@ await mkdir(telemetryDir, {{ recursive: true }});
 const startTime = Date.now();
 const db = await SpiteDbNapi.open(`${{eventsDir}}/{}.db`);
 const telemetry = await TelemetryDbNapi.open(telemetryDir, {{ appName: '{}' }});
-
+{projection_start}
 const adminEmail = process.env.SYSTEM_ADMIN_EMAIL || (process.env.NODE_ENV === 'production' ? '' : 'admin@local');
 if (!adminEmail) {{
   throw new Error('SYSTEM_ADMIN_EMAIL is required in production to bootstrap the system admin.');
@@ -131,6 +153,10 @@ const authConfig = {{
   secret: process.env.AUTH_SECRET || 'dev-secret-do-not-use-in-prod',
   issuer: process.env.AUTH_ISSUER,
   audience: process.env.AUTH_AUDIENCE,
+  // AUTH_JWKS_URL verifies tokens from an external identity provider instead of AUTH_SECRET
+  keySource: process.env.AUTH_JWKS_URL ? jwksKeySource(process.env.AUTH_JWKS_URL) : undefined,
+  // AUTH_MODE=session swaps bearer JWTs for HttpOnly cookie sessions with CSRF protection
+  session: process.env.AUTH_MODE === 'session' ? {{}} : undefined,
 }};
 
 // Projection names for admin dashboard
@@ -157,6 +183,11 @@ const server = Bun.serve({{
 console.log(`🚀 SpiteStack server running at http://localhost:${{server.port}}`);
 console.log(`📊 Admin dashboard available at http://localhost:${{server.port}}/admin`);
 
+// Uploads no event references (e.g. from failed commands) are removed by a sweep
+setInterval(() => {{
+  void collectAttachmentGarbage(db).catch((err) => console.error('Attachment GC failed:', err));
+}}, 6 * 60 * 60 * 1000);
+
 // Best-effort startup telemetry
 void telemetry.writeBatch([{{
   tsMs: Date.now(),
@@ -180,10 +211,256 @@ process.on('SIGINT', () => {{
   }}]).finally(() => process.exit(0));
 }});
 "#,
-        app_name, app_name, projections_str, port
+        app_name,
+        app_name,
+        projections_str,
+        port,
+        projection_import = projection_import,
+        projection_start = projection_start,
+    )
+}
+
+/// Generates src/seed.ts, which imports an NDJSON fixture file into the event store.
+///
+/// Opens the same database as the server, so it should not run while the dev
+/// server holds the store open.
+pub fn generate_seed_ts(app_name: &str) -> String {
+    format!(
+        r#"import {{ SpiteDbNapi }} from '@spitestack/db';
+import {{ mkdir }} from 'node:fs/promises';
+import {{ importFixtures }} from './generated/runtime/fixtures';
+
+const [fixturesPath, tenant] = process.argv.slice(2);
+if (!fixturesPath) {{
+  console.error('Usage: bun run src/seed.ts <fixtures.ndjson> [tenant]');
+  process.exit(1);
+}}
+
+const eventsDir = './data/events';
+await mkdir(eventsDir, {{ recursive: true }});
+const db = await SpiteDbNapi.open(`${{eventsDir}}/{}.db`);
+
+const result = await importFixtures(db, fixturesPath, {{ tenant }});
+console.log(`Seeded ${{result.appended}} event(s) across ${{result.streams}} stream(s), ${{result.skipped}} already present`);
+for (const conflict of result.conflicts) {{
+  console.error(`Skipped ${{conflict.stream}} (${{conflict.tenant}}): ${{conflict.message}}`);
+}}
+if (result.conflicts.length > 0) process.exit(1);
+"#,
+        app_name
     )
 }
 
+/// Generates src/schema-sample.ts, which prints stored event payloads for
+/// `spitestack schema status --live`.
+///
+/// Output is one JSON line per tenant and event type: the number of stored
+/// events and up to `perType` of their payloads.
+pub fn generate_schema_sample_ts(app_name: &str) -> String {
+    format!(
+        r#"import {{ SpiteDbNapi }} from '@spitestack/db';
+import {{ existsSync }} from 'node:fs';
+import {{ readGlobalFrom, TenantResolver }} from './generated/runtime/event-log';
+
+const [perTypeArg, ...tenantArgs] = process.argv.slice(2);
+const perType = Number(perTypeArg ?? 20);
+const tenants = tenantArgs.length > 0 ? tenantArgs : ['default'];
+
+const eventDbPath = './data/events/{}.db';
+if (!existsSync(eventDbPath)) {{
+  console.error(`No event store at ${{eventDbPath}}`);
+  process.exit(1);
+}}
+const db = await SpiteDbNapi.open(eventDbPath);
+const resolver = new TenantResolver(db, tenants);
+
+const byTenant = new Map<string, Map<string, {{ count: number; samples: unknown[] }}>>();
+for await (const events of readGlobalFrom(db, 0)) {{
+  for (const event of events) {{
+    const tenant = await resolver.tenantOf(event);
+    if (tenant === null) continue;
+    const payload = JSON.parse(event.data.toString());
+    const type = typeof payload?.type === 'string' ? payload.type : '';
+    const byType = byTenant.get(tenant) ?? new Map();
+    const entry = byType.get(type) ?? {{ count: 0, samples: [] }};
+    entry.count++;
+    if (entry.samples.length < perType) entry.samples.push(payload);
+    byType.set(type, entry);
+    byTenant.set(tenant, byType);
+  }}
+}}
+
+for (const [tenant, byType] of byTenant) {{
+  for (const [type, entry] of byType) {{
+    console.log(JSON.stringify({{ tenant, type, count: entry.count, samples: entry.samples }}));
+  }}
+}}
+"#,
+        app_name
+    )
+}
+
+/// Generates the load generator script behind `spitestack db bench`.
+///
+/// Not part of the compiled project: the CLI writes it next to the project's
+/// sources for the duration of a run. Writers append straight to a throwaway
+/// `SpiteDbNapi` store under `data/bench` on a fixed schedule; a writer more
+/// than one slot behind drops the slots it missed. Output is one JSON line
+/// per writer with its counts and append latencies in microseconds.
+pub fn generate_bench_ts() -> &'static str {
+    r#"import { SpiteDbNapi } from '@spitestack/db';
+import { mkdirSync, rmSync } from 'node:fs';
+
+const [writersArg, rateArg, payloadArg, durationArg] = process.argv.slice(2);
+const writers = Number(writersArg ?? 4);
+const eventsPerSec = Number(rateArg ?? 1000);
+const payloadBytes = Number(payloadArg ?? 256);
+const durationMs = Number(durationArg ?? 10) * 1000;
+
+// Benchmarks never touch the app's own event store
+const dir = './data/bench';
+const dbPath = `${dir}/bench-${process.pid}.db`;
+mkdirSync(dir, { recursive: true });
+const db = await SpiteDbNapi.open(dbPath);
+
+const base = JSON.stringify({ type: 'BenchEvent', data: '' });
+const payload = Buffer.from(JSON.stringify({ type: 'BenchEvent', data: 'x'.repeat(Math.max(0, payloadBytes - base.length)) }));
+const intervalMs = (1000 * writers) / eventsPerSec;
+
+async function writer(index: number) {
+  const stream = `bench-${index}`;
+  const latenciesUs: number[] = [];
+  let failed = 0;
+  let dropped = 0;
+  let rev = 0;
+  const start = performance.now();
+  let next = start + (intervalMs * index) / writers;
+
+  while (next < start + durationMs) {
+    const now = performance.now();
+    if (now < next) {
+      await Bun.sleep(next - now);
+    } else if (now - next > intervalMs) {
+      const missed = Math.floor((now - next) / intervalMs);
+      dropped += missed;
+      next += missed * intervalMs;
+    }
+
+    const sent = performance.now();
+    try {
+      await db.append(stream, `${stream}-${rev}`, rev, [payload], 'bench');
+      rev++;
+      latenciesUs.push(Math.round((performance.now() - sent) * 1000));
+    } catch {
+      failed++;
+    }
+    next += intervalMs;
+  }
+
+  return { writer: index, appended: latenciesUs.length, failed, dropped, latenciesUs };
+}
+
+const results = await Promise.all(Array.from({ length: writers }, (_, i) => writer(i)));
+for (const result of results) {
+  console.log(JSON.stringify(result));
+}
+
+// SpiteDbNapi has no close(); the files are unlinked under the open handle
+for (const suffix of ['', '-wal', '-shm']) {
+  rmSync(dbPath + suffix, { force: true });
+}
+"#
+}
+
+/// Generates the store inspection script behind `spitestack db`.
+///
+/// Like the bench script, the CLI writes it into the project's `src/` for
+/// one command. It opens the app's event store and prints one JSON line per
+/// event: `scan` walks the whole log (types, no payloads), `read-stream`
+/// reads one stream of a tenant and `tail` follows the log from a position.
+/// Tenants are resolved by name where known, otherwise shown as `#<hash>`.
+pub fn generate_db_tool_ts() -> &'static str {
+    r#"import { SpiteDbNapi } from '@spitestack/db';
+import { existsSync, readdirSync } from 'node:fs';
+import {
+  createdTenant,
+  discoverTenants,
+  globalHead,
+  readGlobalFrom,
+  TenantResolver,
+  type LogEvent,
+} from './generated/runtime/event-log';
+
+const [command, ...args] = process.argv.slice(2);
+
+const eventsDir = './data/events';
+const storeFile = existsSync(eventsDir) ? readdirSync(eventsDir).find((file) => file.endsWith('.db')) : undefined;
+if (!storeFile) {
+  console.error(`No event store in ${eventsDir}`);
+  process.exit(1);
+}
+const db = await SpiteDbNapi.open(`${eventsDir}/${storeFile}`);
+
+function print(event: LogEvent, tenant: string | null, withData: boolean): void {
+  let payload: any = null;
+  try {
+    payload = JSON.parse(event.data.toString());
+  } catch {
+    // Reported as an event without a type
+  }
+  console.log(JSON.stringify({
+    pos: Number(event.globalPos),
+    stream: event.streamId,
+    rev: Number(event.streamRev),
+    tenant: tenant ?? `#${event.tenantHash}`,
+    ts: Number(event.timestampMs),
+    bytes: event.data.byteLength,
+    type: typeof payload?.type === 'string' ? payload.type : null,
+    ...(withData ? { data: payload } : {}),
+  }));
+}
+
+switch (command) {
+  case 'scan': {
+    const tenants = new TenantResolver(db, await discoverTenants(db));
+    for await (const events of readGlobalFrom(db, 0)) {
+      for (const event of events) print(event, await tenants.tenantOf(event), false);
+    }
+    break;
+  }
+  case 'head': {
+    const latest = await db.readGlobal(Number.MAX_SAFE_INTEGER - 1000000, 1);
+    for (const event of latest) print(event, null, false);
+    break;
+  }
+  case 'read-stream': {
+    const [stream, tenant, fromRev, limit] = args;
+    const events = await db.readStream(stream, Number(fromRev), Number(limit), tenant);
+    for (const event of events) print(event, tenant, true);
+    break;
+  }
+  case 'tail': {
+    const tenants = new TenantResolver(db, await discoverTenants(db));
+    let position = args[0] === undefined ? await globalHead(db) : Number(args[0]);
+    while (true) {
+      for await (const events of readGlobalFrom(db, position)) {
+        for (const event of events) {
+          const created = createdTenant(event);
+          if (created) tenants.addTenant(created);
+          print(event, await tenants.tenantOf(event), true);
+          position = Math.max(position, Number(event.globalPos));
+        }
+      }
+      await Bun.sleep(250);
+    }
+  }
+  default:
+    console.error(`Unknown command: ${command}`);
+    process.exit(1);
+}
+"#
+}
+
 /// Generates .gitignore for the project.
 pub fn generate_gitignore() -> &'static str {
     r#"node_modules/
@@ -193,6 +470,87 @@ data/
 *.db-shm
 *.db-wal
 .env
+.spitestack-cache.json
+"#
+}
+
+/// Generates a Dockerfile for the project.
+///
+/// The build context is `context_dir`, a common ancestor of the project and
+/// the domain source, so the generated handlers' relative imports of the
+/// domain resolve inside the image. `project_dir` and `domain_dir` are
+/// relative to it. The event store, telemetry and projection databases live
+/// in the `/data` volume, linked to the project's `./data`.
+pub fn generate_dockerfile(port: u16, project_dir: &str, domain_dir: &str) -> String {
+    // The project copy brings the domain along when it lives inside the project
+    let domain_copy = if project_dir == "." || Path::new(domain_dir).starts_with(project_dir) {
+        String::new()
+    } else {
+        format!("COPY {domain_dir} /app/{domain_dir}\n")
+    };
+
+    format!(
+        r#"# Generated by spitestack compile --emit-docker
+# Build from the context directory:
+#   docker build -f {project_dir}/Dockerfile .
+FROM oven/bun:1
+
+WORKDIR /app/{project_dir}
+COPY {project_dir}/package.json ./
+RUN bun install --production
+
+{domain_copy}COPY {project_dir} /app/{project_dir}
+
+# Event store, telemetry, projections and blobs all live under ./data
+RUN mkdir -p /data && rm -rf data && ln -s /data data && chmod +x docker-start.sh
+VOLUME /data
+
+ENV NODE_ENV=production
+EXPOSE {port}
+
+HEALTHCHECK --interval=10s --timeout=3s --start-period=10s --retries=3 \
+  CMD bun -e "fetch('http://localhost:{port}/healthz').then(r => process.exit(r.ok ? 0 : 1)).catch(() => process.exit(1))"
+
+CMD ["./docker-start.sh"]
+"#
+    )
+}
+
+/// Generates the `.dockerignore` for the Dockerfile.
+///
+/// It is written as `Dockerfile.dockerignore` next to the Dockerfile, so it
+/// applies without touching the build context root.
+pub fn generate_dockerignore(project_dir: &str) -> String {
+    format!(
+        r#"**/.git
+**/node_modules
+{project_dir}/data
+{project_dir}/dist
+{project_dir}/.env
+{project_dir}/.spitestack-cache.json
+"#
+    )
+}
+
+/// Generates the production start script run by the Docker image.
+pub fn generate_docker_start_script() -> &'static str {
+    r#"#!/bin/sh
+# Production entry point: prepares the data volume and starts the server.
+set -e
+
+mkdir -p /data/events /data/telemetry /data/projections /data/blobs
+
+if [ -z "$AUTH_SECRET" ] && [ -z "$AUTH_JWKS_URL" ]; then
+  echo "AUTH_SECRET or AUTH_JWKS_URL must be set in production" >&2
+  exit 1
+fi
+
+if [ -z "$SYSTEM_ADMIN_EMAIL" ]; then
+  echo "SYSTEM_ADMIN_EMAIL must be set to bootstrap the system admin" >&2
+  exit 1
+fi
+
+exec bun run src/index.ts
 "#
 }
 
@@ -225,10 +583,191 @@ bun run start
 Set `SYSTEM_ADMIN_EMAIL` to seed a system-tenant admin on first run. A one-time
 password is printed to the console and must be changed on first login.
 
+## Authentication
+
+Requests authenticate with JWTs (bearer header or `spite_token` cookie) by
+default. Set `AUTH_MODE=session` to use server-side cookie sessions instead;
+state-changing requests (logout included) must then echo the `spite_csrf`
+cookie in an `X-CSRF-Token` header. Sessions are events in the system tenant;
+`data/projections/sessions.db` is rebuilt from them if deleted.
+
+Tokens are HS256-signed with `AUTH_SECRET`. To accept tokens from an external
+identity provider, set `AUTH_JWKS_URL` to its JWKS endpoint (RS256 and ES256
+keys are supported), or pass any `KeySource` as `authConfig.keySource`. Roles
+required by the app access config are read from the token's `orgs[tenant].roles`,
+falling back to `roles`.
+
+## GraphQL
+
+Compiling with `--api-style graphql` replaces the per-command REST routes with
+`POST /graphql`: each command is a mutation (`todoCreate(streamId, input)`),
+each aggregate read and projection query is a query. Commands with a stream id
+template take its parameters instead of `streamId`. Access rules are the same
+as over REST; failures carry the HTTP status in `extensions.status`.
+
+## Client SDK
+
+`spitestack compile --emit-client` also writes a typed client package to
+`client/`, with one method per command, aggregate read and projection query:
+
+```ts
+const api = createClient({{ baseUrl: 'http://localhost:3000', defaultTenant: 'acme' }});
+await api.todo.create('todo-1', {{ title: 'Buy milk' }});
+```
+
+Commands that lose a race with another writer (409, `code: 'conflict'`) are
+retried up to `conflictRetries` times (default 3). Commands with `Attachment`
+parameters take `File`/`Blob` values and are sent as `multipart/form-data`.
+
+## File uploads
+
+Command parameters typed `Attachment` accept `multipart/form-data` uploads.
+Files are stored under `ATTACHMENT_DATA_DIR` (default `./data/blobs`) by
+content hash, and the command receives `{{ hash, size, contentType, filename }}`
+instead of the bytes. Uploads over `ATTACHMENT_MAX_BYTES` (default 25 MB) are
+rejected with 413. A JSON body may pass a reference instead of a file only if
+the tenant has already uploaded that blob.
+
+Identical files share one blob, so a failed command never deletes what it
+stored. The server sweeps blobs that no event references every six hours,
+once they are older than `ATTACHMENT_GC_GRACE_MS` (default one hour).
+
+## Testing orchestrators
+
+Each orchestrator gets a `simulate<Name>()` helper next to it in
+`src/generated/orchestrators/`. It runs the orchestrator against an in-memory
+event store with a virtual clock and seeded ids. Generated orchestrators load
+their aggregates and persist pending events, but the workflow body is not
+compiled yet, so a run appends nothing:
+
+```ts
+const sim = simulateProcessOrder({{ seed: 1 }});
+sim.given('order-1', [{{ type: 'Placed', total: 10 }}]);
+const result = await sim.execute({{ orderStreamId: 'order-1' }});
+expect(result.success).toBe(true);
+expect(sim.appended()).toEqual([]);
+```
+
+Saga logic written against the same context is tested with
+`createSimulation` from `src/generated/runtime/simulator`. Here `processOrder`
+stands for your own function:
+
+```ts
+const sim = createSimulation({{ seed: 1 }});
+afterEach(() => sim.dispose()); // restores Date.now and timers if a test fails mid-run
+sim.given('order-1', [{{ type: 'Placed', total: 10 }}]);
+sim.failNextAppend('payment-1', 'card declined');
+const run = sim.start(() => processOrder(sim.ctx, 'order-1', 'payment-1'));
+await run.advance(30_000); // fast-forward timeouts
+await run.finish();
+sim.expectAppended('order-1', ['Cancelled']);
+```
+
+## Live queries
+
+Every projection query also streams as Server-Sent Events when requested with
+`Accept: text/event-stream`. The query runs on connect and again each time the
+projection applies new events; each result arrives as a `result` event with
+`{{ status, body }}`:
+
+```ts
+const source = new EventSource('/projections/todo_stats/get_by_id/todo-1');
+source.addEventListener('result', (e) => render(JSON.parse(e.data).body));
+```
+
+## Reading your own writes
+
+Projections update asynchronously. A command sent with
+`X-Wait-For-Projections: TodoStats` responds once `TodoStats` has applied the
+events it appended, so a query right after it sees the change. The wait gives
+up after `X-Wait-Timeout-Ms` (default 2000, at most 10000); the response gains
+a `freshness` field (`{{ position, projections, fresh }}`) and an
+`X-Projections-Fresh` header either way.
+
+Custom handlers get the same behaviour from `appendAndWait`, which appends,
+reads the global position of the last event back from the store and waits
+for the named projections:
+
+```ts
+import {{ appendAndWait }} from './generated/runtime/freshness';
+import {{ getTodoStatsPosition }} from './generated/handlers/todo_stats.projection';
+
+const {{ revision, freshness }} = await appendAndWait(
+  db,
+  {{ streamId: 'todo-1', tenant, expectedRev: 0, events: [{{ type: 'Created', title: 'Buy milk' }}] }},
+  {{ projections: ['TodoStats'], positions: {{ TodoStats: getTodoStatsPosition }} }}
+);
+```
+
+## Seed data
+
+`spitestack seed --file fixtures.ndjson` (or `bun run seed fixtures.ndjson`)
+appends fixture events, one JSON object per line:
+
+```
+{{"stream": "todo-1", "type": "Created", "payload": {{"title": "Buy milk"}}}}
+```
+
+Re-running a fixture file only appends events that are not in the store yet.
+Stop the dev server first; the seed script opens the same database.
+
+## Checking stored events
+
+`spitestack schema status --live` samples the stored events of each type
+(`--tenant` selects the tenants, `--sample` the number per type) and reports
+those that no longer match the current event schemas, before a replay trips
+over them.
+
+## Inspecting the event store
+
+`spitestack db` reads the local store without a running server:
+
+- `db stats` prints event, stream and tenant counts and the most common event types.
+- `db list-streams --prefix todo-` lists streams with their revision.
+- `db read-stream todo-1 --tenant acme` prints one stream's events.
+- `db tail` follows new events.
+- `db verify` checks positions, stream revisions and payloads.
+- `db backup <dir>` writes a consistent snapshot of the store, even while the server runs.
+
+In the `spitestack` TUI, `/events` opens the same view interactively: the
+stream list, a live tail and the selected event's payload, filtered by stream
+prefix (`/`) and tenant (`t`). `/projections` lists each projection's
+checkpoint per tenant, its lag behind the log head and the worker's last
+error; `r` rebuilds the selected one from the start of the log.
+
+Tenants created through the tenant API are shown by name; other events show
+a `#<hash>` of their tenant.
+
+## Load testing
+
+`spitestack db bench --writers 8 --events-per-sec 5000 --payload-bytes 512`
+appends synthetic events to a throwaway store under `data/bench` for
+`--duration` seconds (default 10), then prints throughput, append latency
+percentiles and the appends dropped because writers fell behind.
+
+## Docker
+
+`spitestack compile --emit-docker` also writes a `Dockerfile`, a
+`docker-start.sh` production entry point and a `Dockerfile.dockerignore`. The
+first lines of the Dockerfile name the build context, a directory holding
+both this project and the domain source. All data (events, telemetry,
+projections, blobs) is kept in the `/data` volume:
+
+```bash
+docker run -p 3000:3000 -v app-data:/data \
+  -e AUTH_SECRET=... -e SYSTEM_ADMIN_EMAIL=admin@example.com <image>
+```
+
+`GET /healthz` answers 200 without authentication and backs the image's
+`HEALTHCHECK`. Projects compiled inside the SpiteStack monorepo depend on
+`@spitestack/db` by file path; publish or vendor the package before building
+the image.
+
 ## Structure
 
 - `src/index.ts` - Server entry point
 - `src/generated/` - Generated domain code (do not edit)
+- `src/generated/openapi.json` - OpenAPI 3 spec for the generated endpoints
 - `../domain/` - Source TypeScript domain logic
 "#,
         name = name
@@ -246,10 +785,11 @@ pub fn generate_generated_index(aggregates: &[String], domain_import_path: &str)
 
     // Adjust path: handlers are in generated/handlers/, index is in generated/
     // So we need one less "../" in the path
-    let adjusted_path = if let Some(stripped) = domain_import_path.strip_prefix("../") {
-        stripped // Remove first "../"
-    } else {
-        domain_import_path
+    let adjusted_path = match domain_import_path.strip_prefix("../") {
+        // "../domain" becomes "./domain", not the bare specifier "domain"
+        Some(stripped) if !stripped.starts_with('.') => format!("./{}", stripped),
+        Some(stripped) => stripped.to_string(), // Remove first "../"
+        None => domain_import_path.to_string(),
     };
 
     output.push_str("// Re-export user's domain types and generated wiring\n\n");
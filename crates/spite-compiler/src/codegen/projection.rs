@@ -4,8 +4,9 @@
 //! - SQLite schema for projection tables
 //! - Bun worker code for each projection
 //! - Query handlers for HTTP endpoints
+//! - A manager that starts the workers, wired into `index.ts` and the router
 
-use crate::ir::{ProjectionIR, ProjectionKind, DomainIR};
+use crate::ir::{AccessLevel, ProjectionIR, ProjectionKind, DomainIR, SqlType};
 use super::ts_types::{to_snake_case, to_pascal_case};
 
 /// Generates all projection-related code for a domain.
@@ -69,7 +70,8 @@ pub fn generate_projection_schema(projection: &ProjectionIR) -> String {
         let default = col.default.as_ref()
             .map(|d| format!(" DEFAULT {}", d))
             .unwrap_or_default();
-        sql.push_str(&format!("    {} {}{}{},\n", col.name, col.sql_type.to_sql(), nullable, default));
+        let check = col.sql_type.check_constraint(&col.name);
+        sql.push_str(&format!("    {} {}{}{}{},\n", col.name, col.sql_type.to_sql(), nullable, default, check));
     }
 
     // Timestamps
@@ -143,7 +145,7 @@ fn generate_projection_worker(projection: &ProjectionIR, domain_import_path: &st
         .iter()
         .map(|col| {
             let nullable = if col.nullable { "" } else { " NOT NULL" };
-            format!("{} {}{},", col.name, col.sql_type.to_sql(), nullable)
+            format!("{} {}{}{},", col.name, col.sql_type.to_sql(), nullable, col.sql_type.check_constraint(&col.name))
         })
         .collect::<Vec<_>>()
         .join("\n                ");
@@ -157,7 +159,8 @@ fn generate_projection_worker(projection: &ProjectionIR, domain_import_path: &st
     let index_creation = projection.schema.indexes
         .iter()
         .map(|idx| format!(
-            "this.db.run('CREATE INDEX IF NOT EXISTS {}_{} ON {} (tenant_id, {})');",
+            "this.db.run('CREATE {}INDEX IF NOT EXISTS {}_{} ON {} (tenant_id, {})');",
+            if idx.unique { "UNIQUE " } else { "" },
             snake_name, idx.name, snake_name, idx.columns.join(", ")
         ))
         .collect::<Vec<_>>()
@@ -172,6 +175,67 @@ fn generate_projection_worker(projection: &ProjectionIR, domain_import_path: &st
     let persist_logic = generate_persist_logic(projection);
     let query_methods = generate_worker_query_methods(projection);
 
+    // Unique columns: constraint failures become UniqueViolationError
+    let has_unique = projection.schema.indexes.iter().any(|idx| idx.unique);
+    let (unique_violation, batch_start, apply_event, persist_checked, poison_table) = if has_unique {
+        (
+            UNIQUE_VIOLATION_TS,
+            // One snapshot per batch; a poison event restores it and replays the rest
+            format!(
+                "const batch: AppliedBatch = {{ before: structuredClone(this.projection.{}), applied: [] }};\n            ",
+                state_property
+            ),
+            "this.projection.build(eventData);\n                    this.persistStateChecked(eventData, position, batch);".to_string(),
+            generate_persist_state_checked(&snake_name, state_property),
+            format!(
+                "\n\n        // Events whose unique violations were not resolved\n        this.db.run(`\n            CREATE TABLE IF NOT EXISTS {0}_poison (\n                tenant_id TEXT NOT NULL,\n                event_pos INTEGER NOT NULL,\n                event_type TEXT NOT NULL,\n                error TEXT NOT NULL,\n                recorded_at TEXT NOT NULL DEFAULT (datetime('now')),\n                PRIMARY KEY (tenant_id, event_pos)\n            )\n        `);",
+                snake_name
+            ),
+        )
+    } else {
+        (
+            "",
+            String::new(),
+            "this.projection.build(eventData);\n                    this.persistState();".to_string(),
+            String::new(),
+            String::new(),
+        )
+    };
+    // `CREATE TABLE IF NOT EXISTS` keeps tables created before JSON columns
+    // were checked, so the worker drops those and rebuilds from the log
+    let json_checks = json_columns(projection);
+    let (json_check_drop, json_check_rebuild) = if json_checks.is_empty() {
+        (String::new(), String::new())
+    } else {
+        let columns = json_checks.iter().map(|c| format!("'{}'", c)).collect::<Vec<_>>().join(", ");
+        (
+            format!(
+                r#"// Tables from before JSON columns had a json_valid check are rebuilt
+        const existing = this.db.query("SELECT sql FROM sqlite_master WHERE type = 'table' AND name = '{snake_name}'").get() as {{ sql: string }} | null;
+        const missingChecks = existing !== null && ![{columns}].every((column) => existing.sql.includes(`json_valid(${{column}})`));
+        if (missingChecks) {{
+            console.warn('[{name}] JSON columns gained a json_valid check; rebuilding the read model');
+            this.db.run('DROP TABLE {snake_name}');
+        }}
+
+        "#,
+                snake_name = snake_name,
+                name = projection.name,
+                columns = columns,
+            ),
+            "
+
+        // A negative checkpoint makes processBatch() rebuild
+        if (missingChecks) this.updatePosition(-1);".to_string(),
+        )
+    };
+
+    let poison_reset = if has_unique {
+        format!("\n            this.db.run('DELETE FROM {}_poison WHERE tenant_id = ?', [this.tenant]);", snake_name)
+    } else {
+        String::new()
+    };
+
     format!(
         r#"/**
  * Projection Worker: {name}
@@ -185,6 +249,7 @@ fn generate_projection_worker(projection: &ProjectionIR, domain_import_path: &st
 
 import {{ Database }} from 'bun:sqlite';
 import {{ SpiteDbNapi }} from '@spitestack/db';
+import {{ TenantResolver }} from '../runtime/event-log';
 {event_imports}
 
 // Import the projection class from domain
@@ -197,10 +262,12 @@ const DATA_DIR = process.env.PROJECTION_DATA_DIR ?? './data/projections';
 
 // Event types this projection subscribes to
 const SUBSCRIBED_EVENTS = [{subscribed_events_list}];
-
+{unique_violation}
 class {pascal_name}Worker {{
     private db: Database;
-    private eventDb: SpiteDbNapi;
+    private eventDb!: SpiteDbNapi;
+    private eventDbPath: string;
+    private tenants!: TenantResolver;
     private projection: {name};
     private running = false;
     private tenant: string;
@@ -217,12 +284,11 @@ class {pascal_name}Worker {{
         // Initialize schema
         this.initSchema();
 
-        // Connect to event store
-        this.eventDb = new SpiteDbNapi(eventDbPath);
+        this.eventDbPath = eventDbPath;
     }}
 
     private initSchema(): void {{
-        // Create projection table if not exists
+        {json_check_drop}// Create projection table if not exists
         this.db.run(`
             CREATE TABLE IF NOT EXISTS {snake_name} (
                 tenant_id TEXT NOT NULL,
@@ -235,7 +301,7 @@ class {pascal_name}Worker {{
         `);
 
         // Create indexes
-        {index_creation}
+        {index_creation}{poison_table}
 
         // Create position tracking table
         this.db.run(`
@@ -245,6 +311,15 @@ class {pascal_name}Worker {{
                 updated_at TEXT NOT NULL DEFAULT (datetime('now'))
             )
         `);
+
+        // Last batch error, for `spitestack` projection status
+        this.db.run(`
+            CREATE TABLE IF NOT EXISTS {snake_name}_last_error (
+                tenant_id TEXT PRIMARY KEY,
+                error TEXT NOT NULL,
+                recorded_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )
+        `);{json_check_rebuild}
     }}
 
     private getLastPosition(): number {{
@@ -264,7 +339,35 @@ class {pascal_name}Worker {{
         `, [this.tenant, eventId]);
     }}
 
+    private recordError(err: unknown): void {{
+        const message = err instanceof Error ? err.message : String(err);
+        this.db.run(`
+            INSERT INTO {snake_name}_last_error (tenant_id, error, recorded_at)
+            VALUES (?, ?, datetime('now'))
+            ON CONFLICT(tenant_id) DO UPDATE SET
+                error = excluded.error,
+                recorded_at = excluded.recorded_at
+        `, [this.tenant, message]);
+    }}
+
+    /**
+     * Drop this tenant's rows and replay the log from the start. Requested by
+     * setting the checkpoint below zero (the `spitestack` projection panel).
+     */
+    private rebuild(): void {{
+        this.db.transaction(() => {{
+            this.db.run('DELETE FROM {snake_name} WHERE tenant_id = ?', [this.tenant]);
+            this.db.run('DELETE FROM {snake_name}_last_error WHERE tenant_id = ?', [this.tenant]);{poison_reset}
+            this.updatePosition(0);
+        }})();
+        this.projection = new {name}();
+        console.log(`[{name}] Rebuilding projection for tenant: ${{this.tenant}}`);
+    }}
+
     async start(): Promise<void> {{
+        // Connect to event store
+        this.eventDb = await SpiteDbNapi.open(this.eventDbPath);
+        this.tenants = new TenantResolver(this.eventDb, [this.tenant]);
         this.running = true;
         console.log(`[{name}] Starting projection worker for tenant: ${{this.tenant}}`);
 
@@ -273,6 +376,7 @@ class {pascal_name}Worker {{
                 await this.processBatch();
             }} catch (err) {{
                 console.error(`[{name}] Error processing batch:`, err);
+                this.recordError(err);
             }}
             await Bun.sleep(POLL_INTERVAL_MS);
         }}
@@ -284,35 +388,39 @@ class {pascal_name}Worker {{
     }}
 
     private async processBatch(): Promise<void> {{
+        if (this.getLastPosition() < 0) {{
+            this.rebuild();
+        }}
         const lastPosition = this.getLastPosition();
 
-        // Read events from all subscribed streams
-        const events = await this.eventDb.readGlobalEvents(
-            lastPosition,
-            BATCH_SIZE,
-            this.tenant
-        );
+        // The global log spans all tenants; resume after the checkpoint
+        const events = await this.eventDb.readGlobal(lastPosition + 1, BATCH_SIZE);
 
         if (events.length === 0) {{
             return;
         }}
 
+        // Only this tenant's events are applied; the checkpoint moves past all of them
+        const owned = new Set<number>();
+        for (const event of events) {{
+            if ((await this.tenants.tenantOf(event)) === this.tenant) owned.add(Number(event.globalPos));
+        }}
+
         // Filter to subscribed events and process
         let maxEventId = lastPosition;
         const transaction = this.db.transaction(() => {{
-            for (const event of events) {{
+            {batch_start}for (const event of events) {{
+                const position = Number(event.globalPos);
+                maxEventId = Math.max(maxEventId, position);
+                if (!owned.has(position)) continue;
+
                 const eventData = JSON.parse(event.data.toString());
 
                 // Check if we're subscribed to this event type
                 if (SUBSCRIBED_EVENTS.length === 0 || SUBSCRIBED_EVENTS.includes(eventData.type)) {{
-                    // Apply event to projection
-                    this.projection.build(eventData);
-
-                    // Persist state changes
-                    this.persistState();
+                    // Apply event to projection and persist state changes
+                    {apply_event}
                 }}
-
-                maxEventId = Math.max(maxEventId, event.id);
             }}
 
             // Update position
@@ -332,7 +440,7 @@ class {pascal_name}Worker {{
 
         {persist_logic}
     }}
-
+{persist_checked}
     // Query methods (called by handlers)
     {query_methods}
 }}
@@ -367,6 +475,114 @@ export {{ {pascal_name}Worker }};
         index_creation = index_creation,
         persist_logic = persist_logic,
         query_methods = query_methods,
+        unique_violation = unique_violation,
+        batch_start = batch_start,
+        apply_event = apply_event,
+        poison_table = poison_table,
+        persist_checked = persist_checked,
+        poison_reset = poison_reset,
+        json_check_drop = json_check_drop,
+        json_check_rebuild = json_check_rebuild,
+    )
+}
+
+/// Typed error for unique column violations, emitted into workers of
+/// projections with unique columns.
+const UNIQUE_VIOLATION_TS: &str = r#"
+/** A persisted row broke one of the projection's unique columns. */
+export class UniqueViolationError extends Error {
+    readonly code = 'UNIQUE_VIOLATION';
+
+    constructor(
+        readonly column: string,
+        readonly eventId: number,
+        readonly eventType: string,
+        cause: unknown
+    ) {
+        super(`UNIQUE_VIOLATION on ${column} (event ${eventId}, ${eventType})`, { cause });
+    }
+}
+
+function toUniqueViolation(err: unknown, event: any, eventId: number): UniqueViolationError | null {
+    if ((err as { code?: unknown })?.code !== 'SQLITE_CONSTRAINT_UNIQUE') return null;
+    return new UniqueViolationError(uniqueColumns(err as Error), eventId, event?.type ?? 'unknown', err);
+}
+
+/** Columns named in "UNIQUE constraint failed: table.tenant_id, table.column", minus tenant_id. */
+function uniqueColumns(err: Error): string {
+    const list = err.message.split('UNIQUE constraint failed:')[1] ?? '';
+    const columns = list
+        .split(',')
+        .map((column) => column.trim().split('.').pop()!)
+        .filter((column) => column && column !== 'tenant_id');
+    return columns.length > 0 ? columns.join(', ') : 'unknown';
+}
+
+/** Events applied in the current batch, to rebuild state after a poison event. */
+interface AppliedBatch {
+    before: unknown;
+    applied: { event: any; violation?: UniqueViolationError }[];
+}
+"#;
+
+/// Worker methods persisting state with unique violation handling.
+fn generate_persist_state_checked(snake_name: &str, state_property: &str) -> String {
+    format!(
+        r#"
+    /**
+     * Persist, surfacing unique column failures as UniqueViolationError.
+     * A projection may define onUniqueViolation(event, violation) to fix its
+     * state (drop or rewrite the conflicting row); persisting is then retried
+     * once. Without a hook, when the hook returns 'poison' or when the retry
+     * fails too, the event is poison: its state change is undone, it is
+     * recorded in {snake_name}_poison and the checkpoint moves past it.
+     *
+     * Undoing restores the batch's snapshot and replays the events applied
+     * since, so state is only cloned once per batch.
+     */
+    private persistStateChecked(event: any, eventId: number, batch: AppliedBatch): void {{
+        const violation = this.tryPersist(event, eventId);
+        if (!violation) {{
+            batch.applied.push({{ event }});
+            return;
+        }}
+
+        const onUniqueViolation = (this.projection as any).onUniqueViolation;
+        if (typeof onUniqueViolation === 'function' && onUniqueViolation.call(this.projection, event, violation) !== 'poison') {{
+            if (!this.tryPersist(event, eventId)) {{
+                batch.applied.push({{ event, violation }});
+                return;
+            }}
+        }}
+
+        (this.projection as any).{state_property} = structuredClone(batch.before);
+        for (const applied of batch.applied) {{
+            this.projection.build(applied.event);
+            if (applied.violation) onUniqueViolation.call(this.projection, applied.event, applied.violation);
+        }}
+        this.db.run(
+            'INSERT OR REPLACE INTO {snake_name}_poison (tenant_id, event_pos, event_type, error) VALUES (?, ?, ?, ?)',
+            [this.tenant, eventId, violation.eventType, violation.message]
+        );
+        console.warn(`[{snake_name}] Skipped poison event ${{eventId}}: ${{violation.message}}`);
+    }}
+
+    /** Persist inside a savepoint; a unique violation rolls the attempt back. */
+    private tryPersist(event: any, eventId: number): UniqueViolationError | null {{
+        this.db.run('SAVEPOINT persist_event');
+        try {{
+            this.persistState();
+            this.db.run('RELEASE persist_event');
+            return null;
+        }} catch (err) {{
+            this.db.run('ROLLBACK TO persist_event');
+            this.db.run('RELEASE persist_event');
+            const violation = toUniqueViolation(err, event, eventId);
+            if (!violation) throw err;
+            return violation;
+        }}
+    }}
+"#
     )
 }
 
@@ -387,7 +603,11 @@ fn generate_persist_logic(projection: &ProjectionIR) -> String {
 
             let col_values: Vec<_> = projection.schema.columns
                 .iter()
-                .map(|c| format!("row.{}", c.name))
+                .map(|c| match c.sql_type {
+                    // Stored as JSON text; absent values stay NULL
+                    SqlType::Json => format!("row.{0} == null ? null : JSON.stringify(row.{0})", c.name),
+                    _ => format!("row.{}", c.name),
+                })
                 .collect();
 
             format!(
@@ -525,6 +745,7 @@ fn generate_projection_handlers(projection: &ProjectionIR) -> String {
  */
 
 import {{ Database }} from 'bun:sqlite';
+import {{ existsSync }} from 'node:fs';
 import type {{ TelemetryDbNapi }} from '@spitestack/db';
 
 const DATA_DIR = process.env.PROJECTION_DATA_DIR ?? './data/projections';
@@ -532,18 +753,68 @@ const DATA_DIR = process.env.PROJECTION_DATA_DIR ?? './data/projections';
 export type ProjectionHandlerContext = {{
     tenant: string;
     telemetry: TelemetryDbNapi;
+    /** Query string, for filters on JSON columns (`?column.path=value`). */
+    filters?: URLSearchParams;
 }};
 
+/** The worker for this tenant hasn't created its database yet. */
+class ProjectionNotReadyError extends Error {{}}
+
 function getProjectionDb(tenant: string): Database {{
     const dbPath = `${{DATA_DIR}}/{}_${{tenant}}.db`;
+    if (!existsSync(dbPath)) {{
+        throw new ProjectionNotReadyError('Projection is still building');
+    }}
     const db = new Database(dbPath, {{ readonly: true }});
     return db;
 }}
+
+function errorResponse(err: unknown): Response {{
+    if (err instanceof ProjectionNotReadyError) {{
+        return new Response(JSON.stringify({{ error: err.message }}), {{
+            status: 503,
+            headers: {{ 'Content-Type': 'application/json', 'Retry-After': '1' }},
+        }});
+    }}
+    return new Response(JSON.stringify({{ error: (err as Error).message }}), {{
+        status: 500,
+        headers: {{ 'Content-Type': 'application/json' }},
+    }});
+}}
 "#,
         name,
         snake_name
     ));
 
+    let json_columns = json_columns(projection);
+    if !json_columns.is_empty() {
+        code.push_str(&generate_json_column_helpers(&json_columns));
+    }
+
+    // Checkpoint for live queries: they re-run when it moves
+    code.push_str(&format!(
+        r#"
+/** Last event applied for this tenant, or 0 while the projection is building. */
+export function get{name}Position(tenant: string): number {{
+    const dbPath = `${{DATA_DIR}}/{snake}_${{tenant}}.db`;
+    if (!existsSync(dbPath)) return 0;
+    const db = new Database(dbPath, {{ readonly: true }});
+    try {{
+        const row = db
+            .prepare('SELECT last_event_id FROM {snake}_position WHERE tenant_id = ?')
+            .get(tenant) as {{ last_event_id: number }} | null;
+        return row?.last_event_id ?? 0;
+    }} catch {{
+        return 0;
+    }} finally {{
+        db.close();
+    }}
+}}
+"#,
+        name = name,
+        snake = snake_name,
+    ));
+
     // Generate handler for each query method
     for query in &projection.queries {
         code.push_str(&generate_query_handler(projection, query));
@@ -552,6 +823,65 @@ function getProjectionDb(tenant: string): Database {{
     code
 }
 
+/// Names of the projection's JSON columns.
+fn json_columns(projection: &ProjectionIR) -> Vec<&str> {
+    projection.schema.columns
+        .iter()
+        .filter(|c| c.sql_type == SqlType::Json)
+        .map(|c| c.name.as_str())
+        .collect()
+}
+
+/// Generates the helpers that parse JSON columns and turn
+/// `?column.path=value` query parameters into `json_extract` filters.
+fn generate_json_column_helpers(json_columns: &[&str]) -> String {
+    let columns = json_columns
+        .iter()
+        .map(|c| format!("'{}'", c))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!(
+        r#"
+// JSON columns: returned parsed, filterable with ?<column>.<path>=<value>
+const JSON_COLUMNS = [{columns}];
+const JSON_PATH = /^[A-Za-z_][A-Za-z0-9_]*(\.[A-Za-z_][A-Za-z0-9_]*|\[\d+\])*$/;
+
+function decodeRow(row: any): any {{
+    for (const column of JSON_COLUMNS) {{
+        if (typeof row[column] === 'string') row[column] = JSON.parse(row[column]);
+    }}
+    return row;
+}}
+
+/** Query string values compare as numbers or booleans when they look like one. */
+function filterValue(raw: string): string | number {{
+    if (raw === 'true') return 1;
+    if (raw === 'false') return 0;
+    const n = Number(raw);
+    return raw.trim() !== '' && Number.isFinite(n) ? n : raw;
+}}
+
+function jsonFilters(filters?: URLSearchParams): {{ sql: string; values: (string | number)[] }} {{
+    let sql = '';
+    const values: (string | number)[] = [];
+    for (const [key, raw] of filters ?? []) {{
+        const dot = key.indexOf('.');
+        if (dot < 0) continue;
+        const column = key.slice(0, dot);
+        const path = key.slice(dot + 1);
+        // Only known columns and plain paths reach the SQL text
+        if (!JSON_COLUMNS.includes(column) || !JSON_PATH.test(path)) continue;
+        sql += ` AND json_extract(${{column}}, '$.${{path}}') = ?`;
+        values.push(filterValue(raw));
+    }}
+    return {{ sql, values }};
+}}
+"#,
+        columns = columns,
+    )
+}
+
 /// Generates a single query handler.
 fn generate_query_handler(projection: &ProjectionIR, query: &crate::ir::QueryMethodIR) -> String {
     let proj_name = &projection.name;
@@ -595,10 +925,7 @@ export async function handle{proj_name}{query_pascal}(
             headers: {{ 'Content-Type': 'application/json' }},
         }});
     }} catch (err) {{
-        return new Response(JSON.stringify({{ error: (err as Error).message }}), {{
-            status: 500,
-            headers: {{ 'Content-Type': 'application/json' }},
-        }});
+        return errorResponse(err);
     }}
 }}
 "#,
@@ -631,15 +958,28 @@ export async function handle{proj_name}{query_pascal}(
             format!("ctx.tenant, {}", param_values.join(", "))
         };
 
+        // JSON columns are filterable by path and returned parsed
+        let has_json = !json_columns(projection).is_empty();
+        let (filter_decl, filter_sql, param_list, row_value) = if has_json {
+            (
+                "\n        const filter = jsonFilters(ctx.filters);",
+                " + filter.sql",
+                format!("{}, ...filter.values", param_list),
+                "decodeRow(row)",
+            )
+        } else {
+            ("", "", param_list, "row")
+        };
+
         format!(
             r#"
 export async function handle{proj_name}{query_pascal}(
     {param_args}
 ): Promise<Response> {{
     try {{
-        const db = getProjectionDb(ctx.tenant);
+        const db = getProjectionDb(ctx.tenant);{filter_decl}
         const stmt = db.prepare(
-            'SELECT * FROM {table} WHERE tenant_id = ?{where_clause}'
+            'SELECT * FROM {table} WHERE tenant_id = ?{where_clause}'{filter_sql}
         );
         const row = stmt.get({param_list});
         db.close();
@@ -651,15 +991,12 @@ export async function handle{proj_name}{query_pascal}(
             }});
         }}
 
-        return new Response(JSON.stringify(row), {{
+        return new Response(JSON.stringify({row_value}), {{
             status: 200,
             headers: {{ 'Content-Type': 'application/json' }},
         }});
     }} catch (err) {{
-        return new Response(JSON.stringify({{ error: (err as Error).message }}), {{
-            status: 500,
-            headers: {{ 'Content-Type': 'application/json' }},
-        }});
+        return errorResponse(err);
     }}
 }}
 "#,
@@ -668,34 +1005,36 @@ export async function handle{proj_name}{query_pascal}(
             param_args = param_args,
             table = snake_name,
             where_clause = where_clause,
+            filter_decl = filter_decl,
+            filter_sql = filter_sql,
             param_list = param_list,
+            row_value = row_value,
         )
     }
 }
 
 /// Generates the projection manager that starts all workers.
+///
+/// Workers are per projection and tenant. Public and internal projections
+/// have a single tenant and start with the server; private projections start
+/// for the tenants in `PROJECTION_TENANTS` and otherwise on their first query,
+/// up to `MAX_PROJECTION_WORKERS` processes in total. At the limit the least
+/// recently queried on-demand worker is stopped to make room; workers resume
+/// from their saved position, so this only costs catch-up time.
 fn generate_projection_manager(domain: &DomainIR) -> String {
-    let worker_spawns: Vec<String> = domain.projections
+    let entries: Vec<String> = domain.projections
         .iter()
         .map(|p| {
-            let snake_name = to_snake_case(&p.name);
+            let tenants = match p.access {
+                AccessLevel::Public => "['public']",
+                AccessLevel::Internal => "[SYSTEM_TENANT_ID]",
+                AccessLevel::Private => "PRIVATE_TENANTS",
+            };
             format!(
-                r#"    // Start {} worker
-    const {snake_name}Worker = Bun.spawn({{
-        cmd: ['bun', 'run', './_generated/projections/{snake_name}.worker.ts'],
-        env: {{
-            ...process.env,
-            TENANT: tenant,
-            EVENT_DB_PATH: eventDbPath,
-            PROJECTION_DATA_DIR: dataDir,
-        }},
-        stdout: 'inherit',
-        stderr: 'inherit',
-    }});
-    workers.push({{ name: '{name}', proc: {snake_name}Worker }});"#,
+                "    {}: {{ worker: '{}.worker.ts', tenants: {} }},",
                 p.name,
-                snake_name = snake_name,
-                name = p.name
+                to_snake_case(&p.name),
+                tenants
             )
         })
         .collect();
@@ -704,44 +1043,140 @@ fn generate_projection_manager(domain: &DomainIR) -> String {
         r#"/**
  * Projection Manager
  *
- * Starts and manages all projection workers as separate Bun processes.
- * Each projection runs in isolation for parallel processing.
+ * Starts and manages projection workers as separate Bun processes, one per
+ * projection and tenant. Each projection runs in isolation for parallel processing.
  *
  * @generated by spitestack compiler
  */
 
+import {{ SYSTEM_TENANT_ID }} from '../runtime/tenant';
+
 export interface ProjectionManagerConfig {{
-    tenant: string;
     eventDbPath: string;
     dataDir?: string;
 }}
 
 interface WorkerHandle {{
     name: string;
+    tenant: string;
     proc: ReturnType<typeof Bun.spawn>;
+    /** Started with the server rather than by a query; never evicted. */
+    pinned: boolean;
+    lastUsed: number;
 }}
 
-const workers: WorkerHandle[] = [];
+// Private projections are warmed up for these tenants; others start on first query
+const PRIVATE_TENANTS = (process.env.PROJECTION_TENANTS ?? '')
+    .split(',')
+    .map((t) => t.trim())
+    .filter(Boolean);
+
+// Upper bound on worker processes; further tenants evict the least recently used
+const MAX_WORKERS = parseInt(process.env.MAX_PROJECTION_WORKERS ?? '64');
 
-export function startProjections(config: ProjectionManagerConfig): void {{
-    const {{ tenant, eventDbPath, dataDir = './data/projections' }} = config;
+// Workers queried more recently than this are not evicted, so tenants can't thrash
+const MIN_IDLE_MS = 60_000;
+
+const PROJECTIONS: Record<string, {{ worker: string; tenants: string[] }}> = {{
+{entries}
+}};
+
+const workers = new Map<string, WorkerHandle>();
+let config: Required<ProjectionManagerConfig> | null = null;
+
+export function startProjections(options: ProjectionManagerConfig): void {{
+    config = {{ dataDir: './data/projections', ...options }};
 
     console.log('[ProjectionManager] Starting projection workers...');
 
-{worker_spawns}
+    for (const [name, projection] of Object.entries(PROJECTIONS)) {{
+        for (const tenant of projection.tenants) {{
+            ensureProjection(name, tenant, true);
+        }}
+    }}
+
+    console.log(`[ProjectionManager] Started ${{workers.size}} projection workers`);
+}}
+
+/**
+ * Starts the worker for a projection and tenant unless it is already running.
+ * No-op before startProjections() (e.g. in tests that only use the router).
+ *
+ * Returns false when MAX_PROJECTION_WORKERS are running and none has been idle
+ * long enough to evict, so the caller can report that instead of waiting for a
+ * worker that will not start.
+ */
+export function ensureProjection(name: string, tenant: string, pinned = false): boolean {{
+    const projection = PROJECTIONS[name];
+    const key = `${{name}}:${{tenant}}`;
+    if (!config || !projection) return true;
+
+    const running = workers.get(key);
+    if (running) {{
+        running.lastUsed = Date.now();
+        return true;
+    }}
+    if (workers.size >= MAX_WORKERS && !evictIdleWorker()) {{
+        console.warn(`[ProjectionManager] Not starting ${{key}}: MAX_PROJECTION_WORKERS (${{MAX_WORKERS}}) reached`);
+        return false;
+    }}
+
+    const proc = Bun.spawn({{
+        cmd: ['bun', 'run', `${{import.meta.dir}}/${{projection.worker}}`],
+        env: {{
+            ...process.env,
+            TENANT: tenant,
+            EVENT_DB_PATH: config.eventDbPath,
+            PROJECTION_DATA_DIR: config.dataDir,
+        }},
+        stdout: 'inherit',
+        stderr: 'inherit',
+    }});
+    workers.set(key, {{ name, tenant, proc, pinned, lastUsed: Date.now() }});
+
+    // Let a crashed worker be restarted by the next query
+    void proc.exited.then(() => {{
+        if (workers.get(key)?.proc === proc) workers.delete(key);
+    }});
+    return true;
+}}
+
+/** The response for a query whose worker could not start (ensureProjection returned false). */
+export function projectionCapacityResponse(): Response {{
+    return new Response(
+        JSON.stringify({{ error: 'Projection worker capacity reached', code: 'projection_capacity' }}),
+        {{
+            status: 503,
+            headers: {{ 'Content-Type': 'application/json', 'Retry-After': String(MIN_IDLE_MS / 1000) }},
+        }}
+    );
+}}
+
+/** Stops the least recently queried on-demand worker, if one is idle. */
+function evictIdleWorker(): boolean {{
+    let oldest: [string, WorkerHandle] | null = null;
+    for (const entry of workers) {{
+        if (entry[1].pinned) continue;
+        if (!oldest || entry[1].lastUsed < oldest[1].lastUsed) oldest = entry;
+    }}
+    if (!oldest || Date.now() - oldest[1].lastUsed < MIN_IDLE_MS) return false;
 
-    console.log(`[ProjectionManager] Started ${{workers.length}} projection workers`);
+    const [key, worker] = oldest;
+    console.log(`[ProjectionManager] Evicting idle worker ${{key}}`);
+    workers.delete(key);
+    worker.proc.kill();
+    return true;
 }}
 
 export function stopProjections(): void {{
     console.log('[ProjectionManager] Stopping projection workers...');
 
-    for (const worker of workers) {{
-        console.log(`[ProjectionManager] Stopping ${{worker.name}}...`);
+    for (const worker of workers.values()) {{
+        console.log(`[ProjectionManager] Stopping ${{worker.name}} (${{worker.tenant}})...`);
         worker.proc.kill();
     }}
 
-    workers.length = 0;
+    workers.clear();
     console.log('[ProjectionManager] All workers stopped');
 }}
 
@@ -749,6 +1184,197 @@ export function stopProjections(): void {{
 process.on('SIGTERM', stopProjections);
 process.on('SIGINT', stopProjections);
 "#,
-        worker_spawns = worker_spawns.join("\n\n")
+        entries = entries.join("\n")
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ApiStyle;
+    use crate::ir::{ColumnDef, DomainType, IndexDef, ParameterIR, ProjectionSchema, QueryMethodIR, SqlType};
+    use std::path::PathBuf;
+
+    fn make_domain(access: AccessLevel) -> DomainIR {
+        let mut domain = DomainIR::new(PathBuf::new());
+        domain.projections.push(ProjectionIR {
+            name: "TodoStats".to_string(),
+            source_path: PathBuf::new(),
+            kind: ProjectionKind::DenormalizedView,
+            subscribed_events: vec![],
+            schema: ProjectionSchema {
+                state_property_name: "stats".to_string(),
+                primary_keys: vec![ColumnDef {
+                    name: "id".to_string(),
+                    sql_type: SqlType::Text,
+                    nullable: false,
+                    default: None,
+                }],
+                columns: vec![],
+                indexes: vec![],
+            },
+            queries: vec![QueryMethodIR {
+                name: "getById".to_string(),
+                parameters: vec![ParameterIR { name: "id".to_string(), typ: DomainType::String }],
+                return_type: None,
+                indexed_columns: vec![],
+                is_range_query: false,
+                raw_body: None,
+            }],
+            raw_build_body: None,
+            access,
+            roles: vec![],
+        });
+        domain
+    }
+
+    #[test]
+    fn workers_page_the_global_log_for_their_tenant() {
+        let files = generate_projections(&make_domain(AccessLevel::Private), "../../../domain");
+        let file = |name: &str| &files.iter().find(|(n, _)| n == name).unwrap().1;
+
+        let worker = file("projections/todo_stats.worker.ts");
+        assert!(worker.contains("import { TenantResolver } from '../runtime/event-log';"));
+        assert!(worker.contains("await this.eventDb.readGlobal(lastPosition + 1, BATCH_SIZE);"));
+        assert!(worker.contains("if ((await this.tenants.tenantOf(event)) === this.tenant) owned.add(Number(event.globalPos));"));
+        assert!(!worker.contains("readGlobalEvents"));
+        assert!(!worker.contains("event.id"));
+
+        let manager = file("projections/manager.ts");
+        assert!(manager.contains("const MAX_WORKERS = parseInt(process.env.MAX_PROJECTION_WORKERS ?? '64');"));
+        assert!(manager.contains("if (workers.size >= MAX_WORKERS && !evictIdleWorker()) {"));
+        assert!(manager.contains("ensureProjection(name, tenant, true);"));
+        assert!(manager.contains("if (entry[1].pinned) continue;"));
+    }
+
+    #[test]
+    fn workers_record_errors_and_rebuild_on_request() {
+        let files = generate_projections(&make_domain(AccessLevel::Private), "../../../domain");
+        let worker = &files.iter().find(|(n, _)| n == "projections/todo_stats.worker.ts").unwrap().1;
+
+        assert!(worker.contains("CREATE TABLE IF NOT EXISTS todo_stats_last_error ("));
+        assert!(worker.contains("this.recordError(err);"));
+        assert!(worker.contains("if (this.getLastPosition() < 0) {\n            this.rebuild();"));
+        assert!(worker.contains("this.db.run('DELETE FROM todo_stats WHERE tenant_id = ?', [this.tenant]);"));
+        assert!(worker.contains("this.projection = new TodoStats();"));
+    }
+
+    #[test]
+    fn wires_workers_into_server_and_routes() {
+        let domain = make_domain(AccessLevel::Private);
+        let files = generate_projections(&domain, "../../../domain");
+        let manager = &files.iter().find(|(name, _)| name == "projections/manager.ts").unwrap().1;
+        assert!(manager.contains("TodoStats: { worker: 'todo_stats.worker.ts', tenants: PRIVATE_TENANTS },"));
+        assert!(manager.contains("`${import.meta.dir}/${projection.worker}`"));
+        assert!(manager.contains("export function ensureProjection(name: string, tenant: string, pinned = false): boolean {"));
+
+        let router = super::super::router::generate_router(&domain, ApiStyle::Rest);
+        assert!(router.contains("import { ensureProjection, projectionCapacityResponse } from './projections/manager';"));
+        assert!(router.contains("if (!ensureProjection('TodoStats', projCtx.tenant)) return projFinalize(projectionCapacityResponse());"));
+
+        let index = super::super::project::generate_index_ts(3000, "app", &["TodoStats".to_string()]);
+        assert!(index.contains("import { startProjections } from './generated/projections/manager';"));
+        assert!(index.contains("startProjections({ eventDbPath: `${eventsDir}/app.db`, dataDir: projectionsDir });"));
+        assert!(!super::super::project::generate_index_ts(3000, "app", &[]).contains("startProjections"));
+    }
+
+    #[test]
+    fn public_projection_starts_with_server_and_reports_not_ready() {
+        let domain = make_domain(AccessLevel::Public);
+        let files = generate_projections(&domain, "../../../domain");
+        let manager = &files.iter().find(|(name, _)| name == "projections/manager.ts").unwrap().1;
+        assert!(manager.contains("tenants: ['public'] },"));
+
+        let handlers = &files.iter().find(|(name, _)| name == "handlers/todo_stats.projection.ts").unwrap().1;
+        assert!(handlers.contains("throw new ProjectionNotReadyError('Projection is still building');"));
+        assert!(handlers.contains("return errorResponse(err);"));
+    }
+
+    #[test]
+    fn queries_stream_as_sse_when_requested() {
+        let domain = make_domain(AccessLevel::Private);
+        let files = generate_projections(&domain, "../../../domain");
+        let handlers = &files.iter().find(|(name, _)| name == "handlers/todo_stats.projection.ts").unwrap().1;
+        assert!(handlers.contains("export function getTodoStatsPosition(tenant: string): number {"));
+        assert!(handlers.contains("SELECT last_event_id FROM todo_stats_position WHERE tenant_id = ?"));
+
+        let router = super::super::router::generate_router(&domain, ApiStyle::Rest);
+        assert!(router.contains("import { handleTodoStatsGetById, getTodoStatsPosition } from './handlers/todo_stats.projection';"));
+        assert!(router.contains("const run = () => handleTodoStatsGetById(projCtx, { id: paramValue });"));
+        assert!(router.contains(
+            "if (wantsLiveQuery(req)) return projFinalize(liveQuery(req, run, () => getTodoStatsPosition(projCtx.tenant)));"
+        ));
+    }
+
+    #[test]
+    fn json_columns_are_validated_parsed_and_filterable() {
+        let mut domain = make_domain(AccessLevel::Private);
+        domain.projections[0].schema.columns.push(ColumnDef {
+            name: "tags".to_string(),
+            sql_type: SqlType::from_domain_type(&DomainType::Array(Box::new(DomainType::String))),
+            nullable: true,
+            default: None,
+        });
+        let files = generate_projections(&domain, "../../../domain");
+        let file = |name: &str| &files.iter().find(|(n, _)| n == name).unwrap().1;
+
+        assert!(file("schemas/todo_stats.sql").contains("    tags TEXT CHECK (json_valid(tags)),"));
+        let worker = file("projections/todo_stats.worker.ts");
+        assert!(worker.contains("tags TEXT CHECK (json_valid(tags)),"));
+        // Existing tables without the check are dropped and rebuilt
+        assert!(worker.contains("const missingChecks = existing !== null && !['tags'].every((column) => existing.sql.includes(`json_valid(${column})`));"));
+        assert!(worker.contains("this.db.run('DROP TABLE todo_stats');"));
+        assert!(worker.contains("if (missingChecks) this.updatePosition(-1);"));
+        assert!(worker.contains("row.tags == null ? null : JSON.stringify(row.tags)"));
+
+        let handlers = file("handlers/todo_stats.projection.ts");
+        assert!(handlers.contains("const JSON_COLUMNS = ['tags'];"));
+        assert!(handlers.contains("const filter = jsonFilters(ctx.filters);"));
+        assert!(handlers.contains("'SELECT * FROM todo_stats WHERE tenant_id = ? AND id = ?' + filter.sql"));
+        assert!(handlers.contains("stmt.get(ctx.tenant, params.id, ...filter.values);"));
+        assert!(handlers.contains("JSON.stringify(decodeRow(row))"));
+
+        // Projections without JSON columns keep the plain handlers
+        let plain = generate_projections(&make_domain(AccessLevel::Private), "../../../domain");
+        let plain_handlers = &plain.iter().find(|(n, _)| n == "handlers/todo_stats.projection.ts").unwrap().1;
+        assert!(!plain_handlers.contains("jsonFilters"));
+    }
+
+    #[test]
+    fn unique_columns_get_unique_indexes_and_typed_violations() {
+        let mut domain = make_domain(AccessLevel::Private);
+        let schema = &mut domain.projections[0].schema;
+        schema.columns.push(ColumnDef {
+            name: "email".to_string(),
+            sql_type: SqlType::Text,
+            nullable: false,
+            default: None,
+        });
+        schema.indexes.push(IndexDef { name: "uq_email".to_string(), columns: vec!["email".to_string()], unique: true });
+        let files = generate_projections(&domain, "../../../domain");
+        let file = |name: &str| &files.iter().find(|(n, _)| n == name).unwrap().1;
+
+        assert!(file("schemas/todo_stats.sql")
+            .contains("CREATE UNIQUE INDEX IF NOT EXISTS todo_stats_uq_email ON todo_stats (tenant_id, email);"));
+        let worker = file("projections/todo_stats.worker.ts");
+        assert!(worker.contains("this.db.run('CREATE UNIQUE INDEX IF NOT EXISTS todo_stats_uq_email ON todo_stats (tenant_id, email)');"));
+        assert!(worker.contains("readonly code = 'UNIQUE_VIOLATION';"));
+        // Detected by SQLite's error code, not the message
+        assert!(worker.contains("if ((err as { code?: unknown })?.code !== 'SQLITE_CONSTRAINT_UNIQUE') return null;"));
+        // State is cloned once per batch, not per event
+        assert_eq!(worker.matches("structuredClone(this.projection.stats)").count(), 1);
+        assert!(worker.contains("const batch: AppliedBatch = { before: structuredClone(this.projection.stats), applied: [] };"));
+        assert!(worker.contains("this.persistStateChecked(eventData, position, batch);"));
+        // Unresolved violations skip the event instead of blocking the projection
+        assert!(worker.contains("CREATE TABLE IF NOT EXISTS todo_stats_poison ("));
+        assert!(worker.contains("this.db.run('ROLLBACK TO persist_event');"));
+        assert!(worker.contains("(this.projection as any).stats = structuredClone(batch.before);"));
+        assert!(worker.contains("for (const applied of batch.applied) {"));
+        assert!(worker.contains("INSERT OR REPLACE INTO todo_stats_poison"));
+
+        let plain = generate_projections(&make_domain(AccessLevel::Private), "../../../domain");
+        let plain_worker = &plain.iter().find(|(n, _)| n == "projections/todo_stats.worker.ts").unwrap().1;
+        assert!(plain_worker.contains("this.persistState();"));
+        assert!(!plain_worker.contains("UniqueViolationError"));
+    }
+}
@@ -1,16 +1,24 @@
 //! Bun.serve router code generation for TypeScript.
 
-use crate::ir::{AccessLevel, DomainIR};
-use super::ts_types::{to_snake_case, to_pascal_case};
+use crate::config::ApiStyle;
+use crate::ir::{AccessLevel, AggregateIR, CommandIR, DomainIR, DomainType, StreamIdTemplate, TemplatePart};
+use super::ts_types::{to_camel_case, to_snake_case, to_pascal_case};
 
 /// Generates the main router that wires up all handlers.
-pub fn generate_router(domain: &DomainIR) -> String {
+///
+/// With `ApiStyle::GraphQl` the per-command and per-query routes are replaced
+/// by a single `/graphql` endpoint that shares the router's access helpers.
+pub fn generate_router(domain: &DomainIR, api_style: ApiStyle) -> String {
+    let rest = api_style == ApiStyle::Rest;
+    // Commands can wait for projections they can be read back from
+    let read_your_writes = rest && domain.projections.iter().any(|p| !p.queries.is_empty());
     let mut output = String::new();
 
     // Imports
     output.push_str("import type { SpiteDbNapi, TelemetryDbNapi } from '@spitestack/db';\n");
     output.push_str("import type { AuthConfig } from './runtime/auth';\n");
     output.push_str("import { createAuth, resolveTenant } from './runtime/auth';\n");
+    output.push_str("import { createSessionAuth, handleSessionInfo, handleSessionLogin, handleSessionLogout } from './runtime/session';\n");
     output.push_str("import { createEmailProvider } from './runtime/email';\n");
     output.push_str("import { createSmsProvider } from './runtime/sms';\n");
     output.push_str("import { handleAuthRegister, handleAuthLogin, handleAuthRefresh, handleAuthVerifyEmail, handleAuthRequestRecovery, handleAuthResetPassword, handleAuthChangePassword, handleAuthMfaChallenge, handleAuthMfaVerify, handleAuthMfaEnroll, handleAuthMfaEnrollChallenge, handleAuthSocialLogin, handleAuthSocialCallback, handleAuthSocialLink, handleAuthSocialMergeVerify } from './runtime/identity';\n");
@@ -22,46 +30,46 @@ pub fn generate_router(domain: &DomainIR) -> String {
     output.push_str("import { getSecurityHeaders } from './runtime/security-headers';\n");
     output.push_str("import { handleAdminStatus, handleAdminMetrics, handleAdminProjections, handleAdminLogs, handleAdminEvents, handleAdminStream } from './runtime/admin';\n");
     output.push_str("import type { AdminContext } from './runtime/admin';\n");
+    if rest && domain.aggregates.iter().any(|a| a.commands.iter().any(|c| c.accepts_uploads())) {
+        output.push_str("import { isMultipart } from './runtime/attachments';\n");
+    }
+    if !rest {
+        output.push_str("import { executeGraphQL } from './graphql';\n");
+    }
 
-    // Import handlers for each aggregate
-    for aggregate in &domain.aggregates {
-        let snake_name = to_snake_case(&aggregate.name);
-
-        let handler_names: Vec<String> = aggregate
-            .commands
-            .iter()
-            .map(|cmd| format!("handle{}{}", aggregate.name, to_pascal_case(&cmd.name)))
-            .chain(std::iter::once(format!("handle{}Get", aggregate.name)))
-            .collect();
-
-        output.push_str(&format!(
-            "import {{ {} }} from './handlers/{}.handlers';\n",
-            handler_names.join(", "),
-            snake_name
-        ));
+    if rest {
+        output.push_str(&generate_handler_imports(domain, read_your_writes));
     }
 
-    // Import handlers for each projection
-    for projection in &domain.projections {
-        let snake_name = to_snake_case(&projection.name);
+    output.push('\n');
 
-        let handler_names: Vec<String> = projection
-            .queries
-            .iter()
-            .map(|q| format!("handle{}{}", projection.name, to_pascal_case(&q.name)))
-            .collect();
+    if rest && domain.aggregates.iter().any(|a| !a.commands.is_empty()) {
+        output.push_str("// If-Match carries the stream revision the client last read; null if it is not one\n");
+        output.push_str("function expectedRevision(req: Request): number | undefined | null {\n");
+        output.push_str("  const header = req.headers.get('If-Match');\n");
+        output.push_str("  if (!header || header === '*') return undefined;\n");
+        output.push_str("  const revision = header.trim().replace(/^W\\//, '').replace(/\"/g, '');\n");
+        output.push_str("  return /^\\d+$/.test(revision) ? Number(revision) : null;\n");
+        output.push_str("}\n\n");
+    }
 
-        if !handler_names.is_empty() {
+    if read_your_writes {
+        output.push_str("// Checkpoint of each queryable projection, for X-Wait-For-Projections\n");
+        output.push_str("const projectionPositions = {\n");
+        for projection in domain.projections.iter().filter(|p| !p.queries.is_empty()) {
+            let tenant = match projection.access {
+                AccessLevel::Public => "'public'",
+                AccessLevel::Internal => "SYSTEM_TENANT_ID",
+                AccessLevel::Private => "tenant",
+            };
             output.push_str(&format!(
-                "import {{ {} }} from './handlers/{}.projection';\n",
-                handler_names.join(", "),
-                snake_name
+                "  {0}: (tenant: string) => get{0}Position({1}),\n",
+                projection.name, tenant
             ));
         }
+        output.push_str("};\n\n");
     }
 
-    output.push('\n');
-
     // Router context type
     output.push_str("export type RouterContext = {\n");
     output.push_str("  db: SpiteDbNapi;\n");
@@ -74,6 +82,7 @@ pub fn generate_router(domain: &DomainIR) -> String {
     // Router function
     output.push_str("export function createRouter(ctx: RouterContext) {\n");
     output.push_str("  const auth = createAuth(ctx.authConfig);\n");
+    output.push_str("  const sessions = ctx.authConfig.session ? createSessionAuth(ctx.db, ctx.authConfig.session) : null;\n");
     output.push_str("  const emailProvider = createEmailProvider();\n");
     output.push_str("  const smsProvider = createSmsProvider();\n");
     output.push_str("  const securityHeaders = getSecurityHeaders();\n");
@@ -91,10 +100,19 @@ pub fn generate_router(domain: &DomainIR) -> String {
     output.push_str("    const path = url.pathname;\n");
     output.push_str("    const method = req.method;\n");
     output.push_str("    const isProd = process.env.NODE_ENV === 'production';\n\n");
+
+    // Health check for container orchestrators and load balancers
+    output.push_str("    // Liveness probe - no auth, no telemetry\n");
+    output.push_str("    if (method === 'GET' && path === '/healthz') {\n");
+    output.push_str("      return finalize(new Response(JSON.stringify({ status: 'ok', uptimeMs: Date.now() - ctx.startTime }), {\n");
+    output.push_str("        status: 200,\n");
+    output.push_str("        headers: { 'Content-Type': 'application/json' }\n");
+    output.push_str("      }));\n");
+    output.push_str("    }\n\n");
     
     // Auth check
     output.push_str("    // Authenticate request\n");
-    output.push_str("    const authResult = await auth.verifyRequest(req);\n\n");
+    output.push_str("    const authResult = sessions ? await sessions.verifyRequest(req) : await auth.verifyRequest(req);\n\n");
 
     // Public Auth Routes (Login, Register, MFA Challenge/Verify)
     output.push_str("    const isAuthRoute = path.startsWith('/auth/');\n");
@@ -108,8 +126,16 @@ pub fn generate_router(domain: &DomainIR) -> String {
     output.push_str("       }\n");
     output.push_str("       if (method === 'POST' && path === '/auth/login') {\n");
     output.push_str("         const response = await handleAuthLogin(ctx.db, ctx.telemetry, ctx.authConfig, emailProvider, smsProvider, body, req);\n");
+    output.push_str("         if (sessions) return finalize(await handleSessionLogin(sessions, response));\n");
     output.push_str("         return finalize(response);\n");
     output.push_str("       }\n");
+    output.push_str("       // Cookie session endpoints (only when authConfig.session is set)\n");
+    output.push_str("       if (sessions && method === 'GET' && path === '/auth/session') {\n");
+    output.push_str("         return finalize(await handleSessionInfo(sessions, req));\n");
+    output.push_str("       }\n");
+    output.push_str("       if (sessions && method === 'POST' && path === '/auth/logout') {\n");
+    output.push_str("         return finalize(await handleSessionLogout(sessions, req));\n");
+    output.push_str("       }\n");
     output.push_str("       if (method === 'POST' && path === '/auth/register') {\n");
     output.push_str("         const response = await handleAuthRegister(ctx.db, ctx.telemetry, ctx.authConfig, emailProvider, smsProvider, body, req);\n");
     output.push_str("         return finalize(response);\n");
@@ -136,6 +162,7 @@ pub fn generate_router(domain: &DomainIR) -> String {
     output.push_str("       }\n");
     output.push_str("       if (method === 'POST' && path === '/auth/mfa/verify') {\n");
     output.push_str("         const response = await handleAuthMfaVerify(ctx.db, ctx.telemetry, ctx.authConfig, emailProvider, smsProvider, body, req);\n");
+    output.push_str("         if (sessions) return finalize(await handleSessionLogin(sessions, response));\n");
     output.push_str("         return finalize(response);\n");
     output.push_str("       }\n");
     
@@ -171,6 +198,7 @@ pub fn generate_router(domain: &DomainIR) -> String {
     output.push_str("         // OAuth callback (public)\n");
     output.push_str("         if (method === 'GET' && action === '/callback') {\n");
     output.push_str("           const response = await handleAuthSocialCallback(ctx.db, ctx.telemetry, ctx.authConfig, emailProvider, req, provider);\n");
+    output.push_str("           if (sessions) return finalize(await handleSessionLogin(sessions, response));\n");
     output.push_str("           return response;\n");
     output.push_str("         }\n\n");
     output.push_str("         // Link social account (protected)\n");
@@ -313,198 +341,29 @@ pub fn generate_router(domain: &DomainIR) -> String {
 
     // Generate route matching for each aggregate
     output.push_str("    try {\n");
-    for aggregate in &domain.aggregates {
-        let snake_name = to_snake_case(&aggregate.name);
-
-        output.push_str(&format!(
-            "      // {} routes\n",
-            aggregate.name
-        ));
-        output.push_str(&format!(
-            "      const {}Match = path.match(/^\\/{}\\/([^/]+)(?:\\/([^/]+))?$/);\n",
-            snake_name, snake_name
-        ));
-        output.push_str(&format!("      if ({}Match) {{\n", snake_name));
-        output.push_str(&format!("        const streamId = {}Match[1];\n", snake_name));
-        output.push_str(&format!("        const action = {}Match[2];\n\n", snake_name));
-
-        // GET handler - default to Internal access
-        output.push_str("        if (method === 'GET' && !action) {\n");
-        output.push_str("          // GET is Internal by default\n");
-        output.push_str("          const accessErr = checkInternal();\n");
-        output.push_str("          if (accessErr) return accessErr;\n");
-        output.push_str("          const { traceId, spanId, finalize } = createFinalize('system', authResult.user);\n");
-        output.push_str("          const handlerCtx = { ...ctx, tenant: 'system' };\n");
-        output.push_str(&format!(
-            "          const response = await handle{}Get(handlerCtx, streamId, traceId, spanId);\n",
-            aggregate.name
-        ));
-        output.push_str("          return finalize(response);\n");
-        output.push_str("        }\n");
-
-        // Command handlers with access control
-        for cmd in &aggregate.commands {
-            output.push_str(&format!(
-                "        if (method === 'POST' && action === '{}') {{\n",
-                cmd.name
-            ));
-
-            // Generate access check based on access level
-            match cmd.access {
-                AccessLevel::Public => {
-                    output.push_str("          // Public endpoint - no auth required\n");
-                    output.push_str("          const { traceId, spanId, finalize } = createFinalize('public', authResult.ok ? authResult.user : undefined);\n");
-                    output.push_str("          const handlerCtx = { ...ctx, tenant: 'public' };\n");
-                }
-                AccessLevel::Internal => {
-                    output.push_str("          // Internal endpoint - requires system tenant membership\n");
-                    output.push_str("          const accessErr = checkInternal();\n");
-                    output.push_str("          if (accessErr) return accessErr;\n");
-                    if !cmd.roles.is_empty() {
-                        let roles_str = cmd.roles.iter().map(|r| format!("'{}'", r)).collect::<Vec<_>>().join(", ");
-                        output.push_str(&format!("          const roleErr = checkRoles(authResult.user, SYSTEM_TENANT_ID, [{}]);\n", roles_str));
-                        output.push_str("          if (roleErr) return roleErr;\n");
-                    }
-                    output.push_str("          const { traceId, spanId, finalize } = createFinalize(SYSTEM_TENANT_ID, authResult.user);\n");
-                    output.push_str("          const handlerCtx = { ...ctx, tenant: SYSTEM_TENANT_ID };\n");
-                }
-                AccessLevel::Private => {
-                    output.push_str("          // Private endpoint - requires auth + tenant\n");
-                    output.push_str("          const privateResult = checkPrivate();\n");
-                    output.push_str("          if ('error' in privateResult) return privateResult.error;\n");
-                    output.push_str("          const user = privateResult.user;\n");
-                    output.push_str("          const tenant = privateResult.tenant;\n");
-                    if !cmd.roles.is_empty() {
-                        let roles_str = cmd.roles.iter().map(|r| format!("'{}'", r)).collect::<Vec<_>>().join(", ");
-                        output.push_str(&format!("          const roleErr = checkRoles(user, tenant, [{}]);\n", roles_str));
-                        output.push_str("          if (roleErr) return roleErr;\n");
-                    }
-                    output.push_str("          const { traceId, spanId, finalize } = createFinalize(tenant, user);\n");
-                    output.push_str("          const handlerCtx = { ...ctx, tenant };\n");
-                }
-            }
-
-            output.push_str("          const body = await req.json();\n");
-            output.push_str(&format!(
-                "          const response = await handle{}{}(handlerCtx, streamId, body, traceId, spanId);\n",
-                aggregate.name,
-                to_pascal_case(&cmd.name)
-            ));
-            output.push_str("          return finalize(response);\n");
-            output.push_str("        }\n");
-        }
-
+    if !rest {
+        output.push_str("      // GraphQL endpoint - access is checked per field\n");
+        output.push_str("      if (path === '/graphql' && method === 'POST') {\n");
+        output.push_str("        const { query, variables, operationName } = await req.json();\n");
+        output.push_str("        const result = await executeGraphQL({ query, variables, operationName }, {\n");
+        output.push_str("          db: ctx.db,\n");
+        output.push_str("          telemetry: ctx.telemetry,\n");
+        output.push_str("          user: authResult.ok ? authResult.user : undefined,\n");
+        output.push_str("          checkInternal,\n");
+        output.push_str("          checkPrivate,\n");
+        output.push_str("          checkRoles,\n");
+        output.push_str("          createFinalize,\n");
+        output.push_str("        });\n");
+        output.push_str("        return finalize(new Response(JSON.stringify(result), { status: 200, headers: { 'Content-Type': 'application/json' } }));\n");
         output.push_str("      }\n\n");
     }
+    if rest {
+        output.push_str(&generate_aggregate_routes(domain, read_your_writes));
+    }
 
     // Generate route matching for projections (read models)
-    if !domain.projections.is_empty() {
-        output.push_str("      // Projection routes (read models)\n");
-        output.push_str("      if (method === 'GET' && path.startsWith('/projections/')) {\n");
-        output.push_str("        const projParts = path.slice('/projections/'.length).split('/');\n");
-        output.push_str("        const projName = projParts[0];\n");
-        output.push_str("        const queryName = projParts[1];\n\n");
-
-        for projection in &domain.projections {
-            let snake_name = to_snake_case(&projection.name);
-
-            output.push_str(&format!("        if (projName === '{}') {{\n", snake_name));
-
-            // Generate access control for the projection
-            match projection.access {
-                AccessLevel::Public => {
-                    output.push_str("          // Public projection - no auth required\n");
-                    output.push_str("          const { traceId, spanId, finalize: projFinalize } = createFinalize('public', authResult.ok ? authResult.user : undefined);\n");
-                    output.push_str("          const projCtx = { tenant: 'public', telemetry: ctx.telemetry };\n");
-                }
-                AccessLevel::Internal => {
-                    output.push_str("          // Internal projection - requires system tenant membership\n");
-                    output.push_str("          const accessErr = checkInternal();\n");
-                    output.push_str("          if (accessErr) return accessErr;\n");
-                    if !projection.roles.is_empty() {
-                        let roles_str = projection.roles.iter().map(|r| format!("'{}'", r)).collect::<Vec<_>>().join(", ");
-                        output.push_str(&format!("          const roleErr = checkRoles(authResult.user, SYSTEM_TENANT_ID, [{}]);\n", roles_str));
-                        output.push_str("          if (roleErr) return roleErr;\n");
-                    }
-                    output.push_str("          const { traceId, spanId, finalize: projFinalize } = createFinalize(SYSTEM_TENANT_ID, authResult.user);\n");
-                    output.push_str("          const projCtx = { tenant: SYSTEM_TENANT_ID, telemetry: ctx.telemetry };\n");
-                }
-                AccessLevel::Private => {
-                    output.push_str("          // Private projection - requires auth + tenant\n");
-                    output.push_str("          const privateResult = checkPrivate();\n");
-                    output.push_str("          if ('error' in privateResult) return privateResult.error;\n");
-                    output.push_str("          const projUser = privateResult.user;\n");
-                    output.push_str("          const projTenant = privateResult.tenant;\n");
-                    if !projection.roles.is_empty() {
-                        let roles_str = projection.roles.iter().map(|r| format!("'{}'", r)).collect::<Vec<_>>().join(", ");
-                        output.push_str(&format!("          const roleErr = checkRoles(projUser, projTenant, [{}]);\n", roles_str));
-                        output.push_str("          if (roleErr) return roleErr;\n");
-                    }
-                    output.push_str("          const { traceId, spanId, finalize: projFinalize } = createFinalize(projTenant, projUser);\n");
-                    output.push_str("          const projCtx = { tenant: projTenant, telemetry: ctx.telemetry };\n");
-                }
-            }
-
-            // Generate route matching for each query method
-            for query in &projection.queries {
-                let query_snake = to_snake_case(&query.name);
-
-                if query.is_range_query {
-                    // Range query - parameters come from query string
-                    output.push_str(&format!("          if (queryName === '{}') {{\n", query_snake));
-                    output.push_str("            const params = Object.fromEntries(url.searchParams);\n");
-                    output.push_str(&format!(
-                        "            const response = await handle{}{}(projCtx, {{ ...params }});\n",
-                        projection.name,
-                        to_pascal_case(&query.name)
-                    ));
-                    output.push_str("            return projFinalize(response);\n");
-                    output.push_str("          }\n");
-                } else if query.parameters.is_empty() {
-                    // No parameters - simple GET
-                    output.push_str(&format!("          if (queryName === '{}') {{\n", query_snake));
-                    output.push_str(&format!(
-                        "            const response = await handle{}{}(projCtx);\n",
-                        projection.name,
-                        to_pascal_case(&query.name)
-                    ));
-                    output.push_str("            return projFinalize(response);\n");
-                    output.push_str("          }\n");
-                } else {
-                    // Point query - parameters from URL path or query string
-                    output.push_str(&format!("          if (queryName === '{}') {{\n", query_snake));
-
-                    // Extract parameters - support both path params and query params
-                    let param_names: Vec<_> = query.parameters.iter().map(|p| p.name.clone()).collect();
-                    if param_names.len() == 1 {
-                        // Single param - take from path (e.g., /projections/user_profile/get_by_id/user123)
-                        output.push_str("            const paramValue = projParts[2] ?? url.searchParams.get('");
-                        output.push_str(&param_names[0]);
-                        output.push_str("');\n");
-                        output.push_str(&format!(
-                            "            const response = await handle{}{}(projCtx, {{ {}: paramValue }});\n",
-                            projection.name,
-                            to_pascal_case(&query.name),
-                            param_names[0]
-                        ));
-                    } else {
-                        // Multiple params - from query string
-                        output.push_str("            const params = Object.fromEntries(url.searchParams);\n");
-                        output.push_str(&format!(
-                            "            const response = await handle{}{}(projCtx, params);\n",
-                            projection.name,
-                            to_pascal_case(&query.name)
-                        ));
-                    }
-                    output.push_str("            return projFinalize(response);\n");
-                    output.push_str("          }\n");
-                }
-            }
-
-            output.push_str("        }\n\n");
-        }
-
-        output.push_str("      }\n\n");
+    if rest && !domain.projections.is_empty() {
+        output.push_str(&generate_projection_routes(domain));
     }
 
     // Admin dashboard routes
@@ -579,16 +438,399 @@ pub fn generate_router(domain: &DomainIR) -> String {
     output
 }
 
+/// Generates the imports of the REST handlers and their runtime helpers.
+fn generate_handler_imports(domain: &DomainIR, read_your_writes: bool) -> String {
+    let mut output = String::new();
+
+    // Import handlers for each aggregate
+    for aggregate in &domain.aggregates {
+        let snake_name = to_snake_case(&aggregate.name);
+
+        let handler_names: Vec<String> = aggregate
+            .commands
+            .iter()
+            .map(|cmd| format!("handle{}{}", aggregate.name, to_pascal_case(&cmd.name)))
+            .chain(std::iter::once(format!("handle{}Get", aggregate.name)))
+            .collect();
+
+        output.push_str(&format!(
+            "import {{ {} }} from './handlers/{}.handlers';\n",
+            handler_names.join(", "),
+            snake_name
+        ));
+    }
+
+    // Import handlers for each projection
+    for projection in &domain.projections {
+        let snake_name = to_snake_case(&projection.name);
+
+        let handler_names: Vec<String> = projection
+            .queries
+            .iter()
+            .map(|q| format!("handle{}{}", projection.name, to_pascal_case(&q.name)))
+            .collect();
+
+        if !handler_names.is_empty() {
+            output.push_str(&format!(
+                "import {{ {}, get{}Position }} from './handlers/{}.projection';\n",
+                handler_names.join(", "),
+                projection.name,
+                snake_name
+            ));
+        }
+    }
+    if !domain.projections.is_empty() {
+        output.push_str("import { ensureProjection, projectionCapacityResponse } from './projections/manager';\n");
+        output.push_str("import { liveQuery, wantsLiveQuery } from './runtime/live';\n");
+    }
+    if read_your_writes {
+        output.push_str("import { readYourWrites } from './runtime/freshness';\n");
+    }
+
+    output
+}
+
+/// Generates the REST routes of each aggregate: templated command routes,
+/// `GET /{aggregate}/:id` and `POST /{aggregate}/:id/{command}`.
+fn generate_aggregate_routes(domain: &DomainIR, read_your_writes: bool) -> String {
+    let mut output = String::new();
+
+    for aggregate in &domain.aggregates {
+        let snake_name = to_snake_case(&aggregate.name);
+
+        output.push_str(&format!(
+            "      // {} routes\n",
+            aggregate.name
+        ));
+        // Commands with a stream id template get RESTful routes built from its parameters
+        for cmd in &aggregate.commands {
+            if let Some(template) = cmd.stream_id_template() {
+                output.push_str(&generate_templated_route(aggregate, cmd, &template, read_your_writes));
+            }
+        }
+
+        output.push_str(&format!(
+            "      const {}Match = path.match(/^\\/{}\\/([^/]+)(?:\\/([^/]+))?$/);\n",
+            snake_name, snake_name
+        ));
+        output.push_str(&format!("      if ({}Match) {{\n", snake_name));
+        output.push_str(&format!("        const streamId = {}Match[1];\n", snake_name));
+        output.push_str(&format!("        const action = {}Match[2];\n\n", snake_name));
+
+        // GET handler - default to Internal access
+        output.push_str("        if (method === 'GET' && !action) {\n");
+        output.push_str("          // GET is Internal by default\n");
+        output.push_str("          const accessErr = checkInternal();\n");
+        output.push_str("          if (accessErr) return accessErr;\n");
+        output.push_str("          const { traceId, spanId, finalize } = createFinalize('system', authResult.user);\n");
+        output.push_str("          const handlerCtx = { ...ctx, tenant: 'system' };\n");
+        output.push_str(&format!(
+            "          const response = await handle{}Get(handlerCtx, streamId, traceId, spanId);\n",
+            aggregate.name
+        ));
+        output.push_str("          return finalize(response);\n");
+        output.push_str("        }\n");
+
+        // Command handlers with access control
+        for cmd in aggregate.commands.iter().filter(|c| c.stream_id.is_none()) {
+            output.push_str(&format!(
+                "        if (method === 'POST' && action === '{}') {{\n",
+                cmd.name
+            ));
+            output.push_str(&generate_command_dispatch(aggregate, cmd, None, read_your_writes));
+            output.push_str("        }\n");
+        }
+
+        output.push_str("      }\n\n");
+    }
+
+    output
+}
+
+/// Generates the REST routes of projection queries under `/projections/`.
+fn generate_projection_routes(domain: &DomainIR) -> String {
+    let mut output = String::new();
+
+    output.push_str("      // Projection routes (read models)\n");
+    output.push_str("      if (method === 'GET' && path.startsWith('/projections/')) {\n");
+    output.push_str("        const projParts = path.slice('/projections/'.length).split('/');\n");
+    output.push_str("        const projName = projParts[0];\n");
+    output.push_str("        const queryName = projParts[1];\n\n");
+
+    for projection in &domain.projections {
+        let snake_name = to_snake_case(&projection.name);
+
+        output.push_str(&format!("        if (projName === '{}') {{\n", snake_name));
+
+        // Generate access control for the projection
+        match projection.access {
+            AccessLevel::Public => {
+                output.push_str("          // Public projection - no auth required\n");
+                output.push_str("          const { traceId, spanId, finalize: projFinalize } = createFinalize('public', authResult.ok ? authResult.user : undefined);\n");
+                output.push_str("          const projCtx = { tenant: 'public', telemetry: ctx.telemetry, filters: url.searchParams };\n");
+            }
+            AccessLevel::Internal => {
+                output.push_str("          // Internal projection - requires system tenant membership\n");
+                output.push_str("          const accessErr = checkInternal();\n");
+                output.push_str("          if (accessErr) return accessErr;\n");
+                if !projection.roles.is_empty() {
+                    let roles_str = projection.roles.iter().map(|r| format!("'{}'", r)).collect::<Vec<_>>().join(", ");
+                    output.push_str(&format!("          const roleErr = checkRoles(authResult.user, SYSTEM_TENANT_ID, [{}]);\n", roles_str));
+                    output.push_str("          if (roleErr) return roleErr;\n");
+                }
+                output.push_str("          const { traceId, spanId, finalize: projFinalize } = createFinalize(SYSTEM_TENANT_ID, authResult.user);\n");
+                output.push_str("          const projCtx = { tenant: SYSTEM_TENANT_ID, telemetry: ctx.telemetry, filters: url.searchParams };\n");
+            }
+            AccessLevel::Private => {
+                output.push_str("          // Private projection - requires auth + tenant\n");
+                output.push_str("          const privateResult = checkPrivate();\n");
+                output.push_str("          if ('error' in privateResult) return privateResult.error;\n");
+                output.push_str("          const projUser = privateResult.user;\n");
+                output.push_str("          const projTenant = privateResult.tenant;\n");
+                if !projection.roles.is_empty() {
+                    let roles_str = projection.roles.iter().map(|r| format!("'{}'", r)).collect::<Vec<_>>().join(", ");
+                    output.push_str(&format!("          const roleErr = checkRoles(projUser, projTenant, [{}]);\n", roles_str));
+                    output.push_str("          if (roleErr) return roleErr;\n");
+                }
+                output.push_str("          const { traceId, spanId, finalize: projFinalize } = createFinalize(projTenant, projUser);\n");
+                output.push_str("          const projCtx = { tenant: projTenant, telemetry: ctx.telemetry, filters: url.searchParams };\n");
+            }
+        }
+
+        // Start this tenant's worker if it isn't running yet
+        output.push_str(&format!(
+            "          if (!ensureProjection('{}', projCtx.tenant)) return projFinalize(projectionCapacityResponse());\n",
+            projection.name
+        ));
+
+        // Generate route matching for each query method. Each query also
+        // streams as SSE when the client accepts text/event-stream.
+        for query in &projection.queries {
+            let query_snake = to_snake_case(&query.name);
+            let handler = format!("handle{}{}", projection.name, to_pascal_case(&query.name));
+
+            output.push_str(&format!("          if (queryName === '{}') {{\n", query_snake));
+            let call = if query.is_range_query {
+                // Range query - parameters come from query string
+                output.push_str("            const params = Object.fromEntries(url.searchParams);\n");
+                format!("{}(projCtx, {{ ...params }})", handler)
+            } else if query.parameters.is_empty() {
+                // No parameters - simple GET
+                format!("{}(projCtx)", handler)
+            } else if query.parameters.len() == 1 {
+                // Single param - take from path (e.g., /projections/user_profile/get_by_id/user123)
+                let param = &query.parameters[0].name;
+                output.push_str(&format!(
+                    "            const paramValue = projParts[2] ?? url.searchParams.get('{}');\n",
+                    param
+                ));
+                format!("{}(projCtx, {{ {}: paramValue }})", handler, param)
+            } else {
+                // Multiple params - from query string
+                output.push_str("            const params = Object.fromEntries(url.searchParams);\n");
+                format!("{}(projCtx, params)", handler)
+            };
+            output.push_str(&format!("            const run = () => {};\n", call));
+            output.push_str(&format!(
+                "            if (wantsLiveQuery(req)) return projFinalize(liveQuery(req, run, () => get{}Position(projCtx.tenant)));\n",
+                projection.name
+            ));
+            output.push_str("            return projFinalize(await run());\n");
+            output.push_str("          }\n");
+        }
+
+        output.push_str("        }\n\n");
+    }
+
+    output.push_str("      }\n\n");
+
+    output
+}
+
+/// Generates the route for a command whose stream id comes from a template:
+/// `streamId: 'order-{orderId}'` on `Order.cancel` serves `POST /order/:orderId/cancel`.
+fn generate_templated_route(
+    aggregate: &AggregateIR,
+    cmd: &CommandIR,
+    template: &StreamIdTemplate,
+    read_your_writes: bool,
+) -> String {
+    let snake_name = to_snake_case(&aggregate.name);
+    let match_name = format!("{}{}Match", to_camel_case(&aggregate.name), to_pascal_case(&cmd.name));
+    let params = template.params();
+    let mut output = String::new();
+
+    output.push_str(&format!(
+        "      // {}.{} - stream id '{}'\n",
+        aggregate.name,
+        cmd.name,
+        cmd.stream_id.as_deref().unwrap_or_default()
+    ));
+    output.push_str(&format!(
+        "      const {} = path.match(/^\\/{}{}\\/{}$/);\n",
+        match_name,
+        snake_name,
+        "\\/([^/]+)".repeat(params.len()),
+        cmd.name
+    ));
+    output.push_str(&format!("      if ({}) {{\n", match_name));
+    // A malformed escape (`%E0%A4%A`) makes decodeURIComponent throw
+    output.push_str("        let decoded: string[];\n");
+    output.push_str("        try {\n");
+    output.push_str(&format!("          decoded = {}.slice(1).map((part) => decodeURIComponent(part));\n", match_name));
+    output.push_str("        } catch {\n");
+    output.push_str("          return finalize(new Response(JSON.stringify({ error: 'Malformed route parameter' }), { status: 400, headers: { 'Content-Type': 'application/json' } }));\n");
+    output.push_str("        }\n");
+    // Route parameters are strings; those feeding a number or boolean
+    // command parameter are converted, and rejected if they don't parse
+    let param_type = |name: &str| cmd.parameters.iter().find(|p| p.name == name).map(|p| &p.typ);
+    let values: Vec<String> = params
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let raw = format!("decoded[{}]", i);
+            match param_type(p) {
+                Some(DomainType::Number) => format!("{}: /^-?\\d+(\\.\\d+)?$/.test({}) ? Number({}) : NaN", p, raw, raw),
+                Some(DomainType::Boolean) => format!("{}: {} === 'true' ? true : {} === 'false' ? false : undefined", p, raw, raw),
+                _ => format!("{}: {}", p, raw),
+            }
+        })
+        .collect();
+    output.push_str(&format!("        const params = {{ {} }};\n", values.join(", ")));
+    for param in &params {
+        let check = match param_type(param) {
+            Some(DomainType::Number) => format!("Number.isNaN(params.{})", param),
+            Some(DomainType::Boolean) => format!("params.{} === undefined", param),
+            _ => continue,
+        };
+        output.push_str(&format!(
+            "        if ({}) return finalize(new Response(JSON.stringify({{ error: 'Invalid {}' }}), {{ status: 400, headers: {{ 'Content-Type': 'application/json' }} }}));\n",
+            check, param
+        ));
+    }
+    output.push_str(&format!("        const streamId = {};\n\n", stream_id_expr(template)));
+    output.push_str("        if (method === 'POST') {\n");
+    output.push_str(&generate_command_dispatch(aggregate, cmd, Some(&params), read_your_writes));
+    output.push_str("        }\n");
+    output.push_str("      }\n\n");
+
+    output
+}
+
+/// Renders a stream id template as a TypeScript template literal over `params`.
+pub(super) fn stream_id_expr(template: &StreamIdTemplate) -> String {
+    let body: String = template
+        .parts
+        .iter()
+        .map(|part| match part {
+            TemplatePart::Literal(text) => text.replace('\\', "\\\\").replace('`', "\\`").replace("${", "\\${"),
+            TemplatePart::Param(name) => format!("${{params.{}}}", name),
+        })
+        .collect();
+    format!("`{}`", body)
+}
+
+/// Generates the body of a command route: access check, request body and handler call.
+///
+/// `params` are the route parameters of a stream id template; those matching
+/// a command parameter are merged into the request body. With
+/// `read_your_writes`, the response waits for the projections named in
+/// `X-Wait-For-Projections` to catch up with the write.
+fn generate_command_dispatch(
+    aggregate: &AggregateIR,
+    cmd: &CommandIR,
+    params: Option<&[&str]>,
+    read_your_writes: bool,
+) -> String {
+    let mut output = String::new();
+
+    // Generate access check based on access level
+    match cmd.access {
+        AccessLevel::Public => {
+            output.push_str("          // Public endpoint - no auth required\n");
+            output.push_str("          const { traceId, spanId, finalize } = createFinalize('public', authResult.ok ? authResult.user : undefined);\n");
+            output.push_str("          const handlerCtx = { ...ctx, tenant: 'public' };\n");
+        }
+        AccessLevel::Internal => {
+            output.push_str("          // Internal endpoint - requires system tenant membership\n");
+            output.push_str("          const accessErr = checkInternal();\n");
+            output.push_str("          if (accessErr) return accessErr;\n");
+            if !cmd.roles.is_empty() {
+                let roles_str = cmd.roles.iter().map(|r| format!("'{}'", r)).collect::<Vec<_>>().join(", ");
+                output.push_str(&format!("          const roleErr = checkRoles(authResult.user, SYSTEM_TENANT_ID, [{}]);\n", roles_str));
+                output.push_str("          if (roleErr) return roleErr;\n");
+            }
+            output.push_str("          const { traceId, spanId, finalize } = createFinalize(SYSTEM_TENANT_ID, authResult.user);\n");
+            output.push_str("          const handlerCtx = { ...ctx, tenant: SYSTEM_TENANT_ID };\n");
+        }
+        AccessLevel::Private => {
+            output.push_str("          // Private endpoint - requires auth + tenant\n");
+            output.push_str("          const privateResult = checkPrivate();\n");
+            output.push_str("          if ('error' in privateResult) return privateResult.error;\n");
+            output.push_str("          const user = privateResult.user;\n");
+            output.push_str("          const tenant = privateResult.tenant;\n");
+            if !cmd.roles.is_empty() {
+                let roles_str = cmd.roles.iter().map(|r| format!("'{}'", r)).collect::<Vec<_>>().join(", ");
+                output.push_str(&format!("          const roleErr = checkRoles(user, tenant, [{}]);\n", roles_str));
+                output.push_str("          if (roleErr) return roleErr;\n");
+            }
+            output.push_str("          const { traceId, spanId, finalize } = createFinalize(tenant, user);\n");
+            output.push_str("          const handlerCtx = { ...ctx, tenant };\n");
+        }
+    }
+
+    output.push_str("          const expectedRev = expectedRevision(req);\n");
+    output.push_str("          if (expectedRev === null) return finalize(new Response(JSON.stringify({ error: 'If-Match must be a stream revision' }), { status: 400, headers: { 'Content-Type': 'application/json' } }));\n");
+
+    let merged: Vec<&str> = params
+        .unwrap_or_default()
+        .iter()
+        .copied()
+        .filter(|param| cmd.parameters.iter().any(|p| p.name == *param))
+        .collect();
+    if cmd.accepts_uploads() {
+        // Upload commands take multipart bodies; the handler stores the files
+        output.push_str("          const body = isMultipart(req) ? await req.formData() : await req.json();\n");
+        for param in &merged {
+            output.push_str(&format!(
+                "          if (body instanceof FormData) body.set('{0}', params.{0}); else body.{0} = params.{0};\n",
+                param
+            ));
+        }
+    } else if merged.is_empty() {
+        output.push_str("          const body = await req.json();\n");
+    } else {
+        let fields: Vec<String> = merged.iter().map(|p| format!("{0}: params.{0}", p)).collect();
+        output.push_str(&format!(
+            "          const body = {{ ...(await req.json()), {} }};\n",
+            fields.join(", ")
+        ));
+    }
+    output.push_str(&format!(
+        "          const response = await handle{}{}(handlerCtx, streamId, body, traceId, spanId, expectedRev);\n",
+        aggregate.name,
+        to_pascal_case(&cmd.name)
+    ));
+    if read_your_writes {
+        output.push_str("          return finalize(await readYourWrites(req, response, { tenant: handlerCtx.tenant, positions: projectionPositions }));\n");
+    } else {
+        output.push_str("          return finalize(response);\n");
+    }
+
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ir::{AggregateIR, CommandIR, DomainIR, EventTypeIR, ObjectType};
+    use crate::ir::{AggregateIR, CommandIR, DomainIR, DomainType, EventTypeIR, ObjectType, ParameterIR};
     use std::path::PathBuf;
 
     fn make_test_aggregate(name: &str, commands: Vec<CommandIR>) -> AggregateIR {
         AggregateIR {
             name: name.to_string(),
             source_path: PathBuf::new(),
+            span: None,
             state: ObjectType { fields: vec![] },
             initial_state: vec![],
             events: EventTypeIR {
@@ -605,11 +847,133 @@ mod tests {
         let mut domain = DomainIR::new(PathBuf::new());
         domain.aggregates.push(make_test_aggregate("Todo", vec![]));
 
-        let code = generate_router(&domain);
+        let code = generate_router(&domain, ApiStyle::Rest);
 
         assert!(code.contains("emitTelemetry(ctx.telemetry, records);"));
         assert!(code.contains("const createFinalize = (tenant: string, user?: { sub?: string }) => {"));
         assert!(code.contains("finalize: (response: Response, err?: unknown): Response => {"));
         assert!(!code.contains("flushTelemetry"));
+        assert!(code.contains("if (method === 'GET' && path === '/healthz') {"));
+    }
+
+    #[test]
+    fn wires_optional_session_auth() {
+        let mut domain = DomainIR::new(PathBuf::new());
+        domain.aggregates.push(make_test_aggregate("Todo", vec![]));
+
+        let code = generate_router(&domain, ApiStyle::Rest);
+
+        assert!(code.contains("const sessions = ctx.authConfig.session ? createSessionAuth(ctx.db, ctx.authConfig.session) : null;"));
+        assert!(code.contains("const authResult = sessions ? await sessions.verifyRequest(req) : await auth.verifyRequest(req);"));
+        // Every way of logging in ends in a session
+        assert_eq!(code.matches("if (sessions) return finalize(await handleSessionLogin(sessions, response));").count(), 3);
+    }
+
+    #[test]
+    fn graphql_style_replaces_rest_routes() {
+        let mut domain = DomainIR::new(PathBuf::new());
+        domain.aggregates.push(make_test_aggregate("Todo", vec![]));
+
+        let code = generate_router(&domain, ApiStyle::GraphQl);
+
+        assert!(code.contains("import { executeGraphQL } from './graphql';"));
+        assert!(code.contains("if (path === '/graphql' && method === 'POST') {"));
+        assert!(!code.contains("./handlers/todo.handlers"));
+        assert!(!code.contains("const todoMatch"));
+    }
+
+    #[test]
+    fn stream_id_template_routes_by_its_parameters() {
+        let cancel = CommandIR {
+            name: "cancel".to_string(),
+            span: None,
+            parameters: vec![ParameterIR { name: "orderId".to_string(), typ: DomainType::String }],
+            body: vec![],
+            access: AccessLevel::Private,
+            roles: vec![],
+            stream_id: Some("order-{orderId}".to_string()),
+        };
+        let mut domain = DomainIR::new(PathBuf::new());
+        domain.aggregates.push(make_test_aggregate("Order", vec![cancel]));
+
+        let code = generate_router(&domain, ApiStyle::Rest);
+
+        assert!(code.contains("const orderCancelMatch = path.match(/^\\/order\\/([^/]+)\\/cancel$/);"));
+        assert!(code.contains("decoded = orderCancelMatch.slice(1).map((part) => decodeURIComponent(part));"));
+        assert!(code.contains("return finalize(new Response(JSON.stringify({ error: 'Malformed route parameter' }), { status: 400"));
+        assert!(code.contains("const params = { orderId: decoded[0] };"));
+        assert!(code.contains("const streamId = `order-${params.orderId}`;"));
+        assert!(code.contains("const body = { ...(await req.json()), orderId: params.orderId };"));
+        assert!(code.contains("if (expectedRev === null) return finalize(new Response(JSON.stringify({ error: 'If-Match must be a stream revision' }), { status: 400"));
+        assert!(code.contains("handleOrderCancel(handlerCtx, streamId, body, traceId, spanId, expectedRev);"));
+        assert!(code.contains("return /^\\d+$/.test(revision) ? Number(revision) : null;"));
+        // Route parameters of number commands are converted
+        let refund = CommandIR {
+            name: "refund".to_string(),
+            span: None,
+            parameters: vec![ParameterIR { name: "amount".to_string(), typ: DomainType::Number }],
+            body: vec![],
+            access: AccessLevel::Private,
+            roles: vec![],
+            stream_id: Some("refund-{amount}".to_string()),
+        };
+        domain.aggregates[0].commands.push(refund);
+        let code = generate_router(&domain, ApiStyle::Rest);
+        assert!(code.contains("const params = { amount: /^-?\\d+(\\.\\d+)?$/.test(decoded[0]) ? Number(decoded[0]) : NaN };"));
+        assert!(code.contains("if (Number.isNaN(params.amount)) return finalize(new Response(JSON.stringify({ error: 'Invalid amount' }), { status: 400"));
+        assert!(code.contains("const body = { ...(await req.json()), amount: params.amount };"));
+        assert!(!code.contains("Number.isNaN(params.orderId)"));
+        // The generic `/order/:streamId/cancel` route is not generated
+        assert!(!code.contains("action === 'cancel'"));
+    }
+
+    #[test]
+    fn commands_can_wait_for_projections() {
+        let complete = CommandIR {
+            name: "complete".to_string(),
+            span: None,
+            parameters: vec![],
+            body: vec![],
+            access: AccessLevel::Private,
+            roles: vec![],
+            stream_id: None,
+        };
+        let mut domain = DomainIR::new(PathBuf::new());
+        domain.aggregates.push(make_test_aggregate("Todo", vec![complete]));
+
+        // Without queryable projections the response is returned as is
+        let code = generate_router(&domain, ApiStyle::Rest);
+        assert!(!code.contains("readYourWrites"));
+
+        domain.projections.push(crate::ir::ProjectionIR {
+            name: "TodoStats".to_string(),
+            source_path: PathBuf::new(),
+            kind: crate::ir::ProjectionKind::Aggregator,
+            subscribed_events: vec![],
+            schema: crate::ir::ProjectionSchema {
+                state_property_name: "stats".to_string(),
+                primary_keys: vec![],
+                columns: vec![],
+                indexes: vec![],
+            },
+            queries: vec![crate::ir::QueryMethodIR {
+                name: "getTotal".to_string(),
+                parameters: vec![],
+                return_type: None,
+                indexed_columns: vec![],
+                is_range_query: false,
+                raw_body: None,
+            }],
+            raw_build_body: None,
+            access: AccessLevel::Public,
+            roles: vec![],
+        });
+
+        let code = generate_router(&domain, ApiStyle::Rest);
+        assert!(code.contains("import { readYourWrites } from './runtime/freshness';"));
+        assert!(code.contains("  TodoStats: (tenant: string) => getTodoStatsPosition('public'),"));
+        assert!(code.contains(
+            "return finalize(await readYourWrites(req, response, { tenant: handlerCtx.tenant, positions: projectionPositions }));"
+        ));
     }
 }
@@ -33,6 +33,20 @@ pub const SECURITY_HEADERS: &str = include_str!("../../runtime/security-headers.
 pub const RATE_LIMIT: &str = include_str!("../../runtime/rate-limit.ts");
 /// Password policy module.
 pub const PASSWORD_POLICY: &str = include_str!("../../runtime/password-policy.ts");
+/// Cookie session module (alternative to JWT auth).
+pub const SESSION: &str = include_str!("../../runtime/session.ts");
+/// Attachments module (content-addressed blob storage for uploads).
+pub const ATTACHMENTS: &str = include_str!("../../runtime/attachments.ts");
+/// Deterministic orchestrator simulator (in-memory store, virtual clock).
+pub const SIMULATOR: &str = include_str!("../../runtime/simulator.ts");
+/// Live (SSE) projection queries.
+pub const LIVE: &str = include_str!("../../runtime/live.ts");
+/// Read-your-writes waits for projection checkpoints after a command.
+pub const FRESHNESS: &str = include_str!("../../runtime/freshness.ts");
+/// Paging the global event log and attributing events to tenants.
+pub const EVENT_LOG: &str = include_str!("../../runtime/event-log.ts");
+/// NDJSON fixture import for seed data.
+pub const FIXTURES: &str = include_str!("../../runtime/fixtures.ts");
 
 /// Returns all runtime modules as (filename, content) pairs.
 pub fn get_runtime_modules() -> Vec<(&'static str, &'static str)> {
@@ -52,6 +66,13 @@ pub fn get_runtime_modules() -> Vec<(&'static str, &'static str)> {
         ("runtime/security-headers.ts", SECURITY_HEADERS),
         ("runtime/rate-limit.ts", RATE_LIMIT),
         ("runtime/password-policy.ts", PASSWORD_POLICY),
+        ("runtime/session.ts", SESSION),
+        ("runtime/attachments.ts", ATTACHMENTS),
+        ("runtime/simulator.ts", SIMULATOR),
+        ("runtime/fixtures.ts", FIXTURES),
+        ("runtime/event-log.ts", EVENT_LOG),
+        ("runtime/live.ts", LIVE),
+        ("runtime/freshness.ts", FRESHNESS),
     ]
 }
 
@@ -65,4 +86,12 @@ mod tests {
         assert!(TELEMETRY.contains("writeBatch"));
         assert!(!TELEMETRY.contains("flushTelemetry"));
     }
+
+    #[test]
+    fn auth_runtime_verifies_through_key_source() {
+        assert!(AUTH.contains("export interface KeySource"));
+        assert!(AUTH.contains("export function jwksKeySource("));
+        assert!(AUTH.contains("config.keySource ?? secretKeySource(config.secret)"));
+        assert!(AUTH.contains("if (header.alg !== 'HS256') return null;"));
+    }
 }
@@ -9,6 +9,10 @@ use super::ts_types::{to_ts_type, to_pascal_case};
 pub fn generate_validators(aggregate: &AggregateIR, _domain_import_path: &str) -> String {
     let mut output = String::new();
 
+    if aggregate.commands.iter().any(|cmd| cmd.accepts_uploads()) {
+        output.push_str("import type { Attachment } from '../runtime/attachments';\n\n");
+    }
+
     // Common types
     output.push_str("export type ValidationError = { field: string; message: string };\n\n");
     output.push_str("export type ValidationResult<T> =\n");
@@ -204,6 +208,18 @@ fn generate_type_validation(field: &str, path: &str, typ: &DomainType, indent: u
 
             output.push_str(&format!("{}}}\n", spaces));
         }
+        DomainType::Reference(_) if typ.is_attachment() => {
+            // Attachments arrive as references produced by the upload handler
+            output.push_str(&format!(
+                "{}if (typeof {} !== 'object' || {} === null || typeof ({} as Record<string, unknown>).hash !== 'string') {{\n",
+                spaces, path, path, path
+            ));
+            output.push_str(&format!(
+                "{}  errors.push({{ field: '{}', message: 'Expected uploaded file' }});\n",
+                spaces, field
+            ));
+            output.push_str(&format!("{}}}\n", spaces));
+        }
         DomainType::Reference(_) => {
             // For references, we just check it's an object (can't validate deeper without context)
             output.push_str(&format!(
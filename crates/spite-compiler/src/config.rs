@@ -16,6 +16,37 @@ pub struct CompilerConfig {
 
     /// Source language (default: "typescript").
     pub language: String,
+
+    /// API surface of the generated project (default: REST).
+    pub api_style: ApiStyle,
+
+    /// Also generate a typed client package in `client/` (REST only).
+    pub emit_client: bool,
+
+    /// Also generate a Dockerfile and production start script.
+    pub emit_docker: bool,
+}
+
+/// API surface generated on top of the command and projection handlers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ApiStyle {
+    /// One HTTP route per command, aggregate read and projection query.
+    #[default]
+    Rest,
+    /// A single `/graphql` endpoint: mutations per command, queries per
+    /// aggregate read and projection query.
+    GraphQl,
+}
+
+impl ApiStyle {
+    /// Parses `"rest"` or `"graphql"`.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "rest" => Some(Self::Rest),
+            "graphql" => Some(Self::GraphQl),
+            _ => None,
+        }
+    }
 }
 
 impl Default for CompilerConfig {
@@ -25,6 +56,9 @@ impl Default for CompilerConfig {
             out_dir: PathBuf::from("src/generated"),
             skip_purity_check: false,
             language: "typescript".to_string(),
+            api_style: ApiStyle::Rest,
+            emit_client: false,
+            emit_docker: false,
         }
     }
 }
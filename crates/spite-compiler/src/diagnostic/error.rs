@@ -2,9 +2,12 @@
 #![allow(unused_assignments)]
 
 use std::path::PathBuf;
-use miette::Diagnostic;
+use miette::{Diagnostic, NamedSource, SourceSpan};
+use serde_json::{json, Value};
 use thiserror::Error;
 
+use super::Span;
+
 /// Errors that can occur during compilation.
 #[allow(unused_assignments)]
 #[derive(Error, Diagnostic, Debug)]
@@ -79,6 +82,17 @@ pub enum CompilerError {
         reason: String,
     },
 
+    #[error("Invalid streamId for '{aggregate}.{command}': {reason}")]
+    #[diagnostic(
+        code(spitestack::structure::invalid_stream_id),
+        help("Stream id templates look like 'order-{{orderId}}'; each {{parameter}} becomes a route segment")
+    )]
+    InvalidStreamId {
+        aggregate: String,
+        command: String,
+        reason: String,
+    },
+
     // =========================================================================
     // Purity Errors
     // =========================================================================
@@ -126,6 +140,15 @@ pub enum CompilerError {
         type_desc: String,
     },
 
+    #[error("Unsupported union type '{type_desc}'")]
+    #[diagnostic(
+        code(spitestack::types::unsupported_union),
+        help("Only `T | None` unions are supported; use separate fields or a string literal type instead")
+    )]
+    UnsupportedUnion {
+        type_desc: String,
+    },
+
     #[error("Unknown type reference: {name}")]
     #[diagnostic(code(spitestack::types::unknown_reference))]
     UnknownTypeReference {
@@ -225,6 +248,43 @@ pub enum CompilerError {
     UnsupportedLanguage {
         language: String,
     },
+
+    #[error("Unsupported {kind} '{name}': {language} domains only support aggregates")]
+    #[diagnostic(
+        code(spitestack::frontend::unsupported_declaration),
+        help("Write projections and orchestrators in TypeScript")
+    )]
+    UnsupportedDeclaration {
+        name: String,
+        kind: String,
+        language: String,
+    },
+
+    // =========================================================================
+    // Source Locations
+    // =========================================================================
+    /// Another error pinned to a location in the domain source.
+    ///
+    /// Created with [`CompilerError::at`].
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Located(Box<LocatedError>),
+}
+
+/// An error with a labeled snippet of the source it refers to.
+///
+/// Code and help come from the wrapped error; the label points at `span`.
+#[derive(Error, Diagnostic, Debug)]
+#[error("{error}")]
+#[diagnostic(forward(error))]
+pub struct LocatedError {
+    pub error: CompilerError,
+    pub span: Span,
+    pub label: String,
+    #[source_code]
+    source_code: NamedSource<String>,
+    #[label("{label}")]
+    location: SourceSpan,
 }
 
 impl CompilerError {
@@ -235,4 +295,87 @@ impl CompilerError {
             message: message.into(),
         }
     }
+
+    /// Attaches a source location, so the error renders with a labeled
+    /// snippet of the offending code.
+    ///
+    /// Errors that already carry a location keep it; the innermost one is the
+    /// most precise. If the source file can't be read the error is returned
+    /// unchanged.
+    pub fn at(self, span: &Span, label: impl Into<String>) -> Self {
+        if matches!(self, Self::Located(_)) {
+            return self;
+        }
+        let Ok(source) = std::fs::read_to_string(&span.file) else {
+            return self;
+        };
+        let location = span.source_span(&source);
+        Self::Located(Box::new(LocatedError {
+            error: self,
+            span: span.clone(),
+            label: label.into(),
+            source_code: NamedSource::new(span.file.display().to_string(), source),
+            location,
+        }))
+    }
+
+    /// Like [`CompilerError::at`], for IR nodes whose span is optional.
+    pub fn at_opt(self, span: Option<&Span>, label: impl Into<String>) -> Self {
+        match span {
+            Some(span) => self.at(span, label),
+            None => self,
+        }
+    }
+
+    /// The source location of this error, if known.
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            Self::Located(located) => Some(&located.span),
+            _ => None,
+        }
+    }
+
+    /// Renders the error as a JSON diagnostic for editor integration.
+    ///
+    /// Positions are zero-based lines and columns, as in LSP. Errors with a
+    /// file but no precise span (e.g. `SyntaxError`) point at their line.
+    pub fn to_json(&self) -> Value {
+        let (file, range, label) = match self {
+            Self::Located(located) => {
+                let span = &located.span;
+                (
+                    Some(span.file.clone()),
+                    Some(json!({
+                        "start": { "line": span.start_line, "character": span.start_col },
+                        "end": { "line": span.end_line, "character": span.end_col },
+                    })),
+                    Some(located.label.clone()),
+                )
+            }
+            Self::SyntaxError { file, line, column, .. } => (
+                Some(file.clone()),
+                Some(json!({
+                    "start": { "line": line, "character": column },
+                    "end": { "line": line, "character": column },
+                })),
+                None,
+            ),
+            Self::IoError { path: file, .. }
+            | Self::ParseFailed { path: file }
+            | Self::ForbiddenCall { file, .. }
+            | Self::ForbiddenAwait { file, .. }
+            | Self::ForbiddenImport { file, .. } => (Some(file.clone()), None, None),
+            _ => (None, None, None),
+        };
+
+        json!({
+            "severity": "error",
+            "code": self.code().map(|c| c.to_string()),
+            "message": self.to_string(),
+            "help": self.help().map(|h| h.to_string()),
+            "file": file.map(|f| f.display().to_string()),
+            "range": range,
+            "label": label,
+        })
+    }
 }
@@ -3,5 +3,5 @@
 mod error;
 mod span;
 
-pub use error::CompilerError;
+pub use error::{CompilerError, LocatedError};
 pub use span::Span;
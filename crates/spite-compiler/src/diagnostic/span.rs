@@ -2,7 +2,12 @@
 
 use std::path::PathBuf;
 
+use miette::SourceSpan;
+
 /// A span in the source code.
+///
+/// Lines and columns are zero-based; columns count bytes, as reported by
+/// tree-sitter.
 #[derive(Debug, Clone)]
 pub struct Span {
     pub file: PathBuf,
@@ -22,4 +27,38 @@ impl Span {
             end_col,
         }
     }
+
+    /// Converts the span to a byte range within `source`, for miette labels.
+    pub fn source_span(&self, source: &str) -> SourceSpan {
+        let start = line_col_offset(source, self.start_line, self.start_col);
+        let end = line_col_offset(source, self.end_line, self.end_col).max(start);
+        SourceSpan::new(start.into(), end - start)
+    }
+}
+
+/// Byte offset of a line and column, clamped to the end of the source.
+fn line_col_offset(source: &str, line: usize, col: usize) -> usize {
+    let line_start = source
+        .split_inclusive('\n')
+        .take(line)
+        .map(str::len)
+        .sum::<usize>();
+    (line_start + col).min(source.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_line_and_column_to_offsets() {
+        let source = "type A = {};\nclass Todo {\n}\n";
+        let span = Span::new(PathBuf::from("a.ts"), 1, 6, 1, 10);
+        let range = span.source_span(source);
+        assert_eq!(range.offset(), 19);
+        assert_eq!(&source[range.offset()..range.offset() + range.len()], "Todo");
+
+        let past_end = Span::new(PathBuf::from("a.ts"), 9, 0, 9, 4);
+        assert_eq!(past_end.source_span(source).offset(), source.len());
+    }
 }
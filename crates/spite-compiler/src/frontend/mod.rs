@@ -7,6 +7,7 @@
 //! This allows the compiler to support multiple source languages
 //! while sharing the validation, code generation, and tooling.
 
+pub mod python;
 pub mod typescript;
 
 use std::path::Path;
@@ -23,12 +24,19 @@ pub trait Frontend {
 
     /// Parses all source files in the given directory and returns IR.
     fn parse_directory(&mut self, dir: &Path) -> Result<DomainIR, CompilerError>;
+
+    /// Whether generated handlers import the domain's own source files.
+    /// Otherwise TypeScript domain classes are generated from the IR.
+    fn imports_domain_sources(&self) -> bool {
+        true
+    }
 }
 
 /// Creates a frontend for the given language.
 pub fn create_frontend(language: &str) -> Result<Box<dyn Frontend>, CompilerError> {
     match language {
         "typescript" | "ts" => Ok(Box::new(typescript::TypeScriptFrontend::new()?)),
+        "python" | "py" => Ok(Box::new(python::PythonFrontend::new()?)),
         _ => Err(CompilerError::UnsupportedLanguage {
             language: language.to_string(),
         }),
@@ -0,0 +1,167 @@
+//! Python-specific AST types.
+//!
+//! Only declarations are kept as AST; method bodies are lowered to IR
+//! statements while the tree-sitter nodes are still at hand.
+
+use std::path::PathBuf;
+use crate::diagnostic::Span;
+use crate::ir::{InitialValue, StatementIR};
+
+/// A parsed Python module.
+#[derive(Debug)]
+pub struct ParsedModule {
+    pub path: PathBuf,
+    pub type_aliases: Vec<TypeAlias>,
+    pub classes: Vec<ClassDecl>,
+}
+
+/// A module-level type alias (`TodoEvent = Union[...]` or `type TodoEvent = ...`).
+#[derive(Debug, Clone)]
+pub struct TypeAlias {
+    pub name: String,
+    pub type_expr: TypeExpr,
+}
+
+/// A class definition.
+#[derive(Debug, Clone)]
+pub struct ClassDecl {
+    pub name: String,
+    /// Base class names (`typing.TypedDict` is recorded as `TypedDict`).
+    pub bases: Vec<String>,
+    /// False when declared with `total=False`.
+    pub total: bool,
+    pub attributes: Vec<ClassAttribute>,
+    pub methods: Vec<MethodDecl>,
+    /// Attributes assigned as `self.<name> = ...` in `__init__`.
+    pub instance_attributes: Vec<String>,
+    pub span: Span,
+}
+
+impl ClassDecl {
+    pub fn is_typed_dict(&self) -> bool {
+        self.bases.iter().any(|b| b == "TypedDict")
+    }
+}
+
+/// A class-level attribute (`name: T`, `name = value` or `name: T = value`).
+#[derive(Debug, Clone)]
+pub struct ClassAttribute {
+    pub name: String,
+    pub annotation: Option<TypeExpr>,
+    /// Entries when the value is a dict literal.
+    pub dict_entries: Option<Vec<(String, InitialValue)>>,
+}
+
+/// A method definition.
+#[derive(Debug, Clone)]
+pub struct MethodDecl {
+    pub name: String,
+    pub parameters: Vec<Parameter>,
+    pub body: Vec<StatementIR>,
+    pub span: Span,
+}
+
+/// A method parameter (`self`/`cls` are dropped).
+#[derive(Debug, Clone)]
+pub struct Parameter {
+    pub name: String,
+    pub annotation: Option<TypeExpr>,
+    pub has_default: bool,
+}
+
+/// A type annotation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeExpr {
+    /// `str`, `TodoState`, `typing.Any`
+    Name(String),
+    /// `list[str]`, `Optional[int]`, `Literal["Created"]`
+    Generic(String, Vec<TypeExpr>),
+    /// `A | B`
+    Union(Vec<TypeExpr>),
+    /// A quoted string: a `Literal` value or a forward reference.
+    Str(String),
+    /// `None`
+    None,
+}
+
+impl TypeExpr {
+    /// Parses annotation source text.
+    ///
+    /// Annotations are parsed from text rather than from tree-sitter nodes
+    /// because the grammar gives the same annotation different shapes
+    /// (`subscript`, `generic_type`, `binary_operator`) depending on context.
+    pub fn parse(text: &str) -> TypeExpr {
+        let text = text.trim();
+
+        let members = split_top_level(text, '|');
+        if members.len() > 1 {
+            return TypeExpr::Union(members.iter().map(|m| TypeExpr::parse(m)).collect());
+        }
+
+        if text.len() >= 2 && (text.starts_with('"') || text.starts_with('\'')) {
+            return TypeExpr::Str(text[1..text.len() - 1].to_string());
+        }
+
+        if let (Some(open), true) = (text.find('['), text.ends_with(']')) {
+            let name = simple_name(&text[..open]);
+            let args = split_top_level(&text[open + 1..text.len() - 1], ',')
+                .iter()
+                .filter(|a| !a.trim().is_empty())
+                .map(|a| TypeExpr::parse(a))
+                .collect();
+            return TypeExpr::Generic(name, args);
+        }
+
+        match text {
+            "None" => TypeExpr::None,
+            _ => TypeExpr::Name(simple_name(text)),
+        }
+    }
+}
+
+impl std::fmt::Display for TypeExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |exprs: &[TypeExpr], sep: &str| {
+            exprs.iter().map(|e| e.to_string()).collect::<Vec<_>>().join(sep)
+        };
+        match self {
+            TypeExpr::Name(name) => write!(f, "{}", name),
+            TypeExpr::Generic(name, args) => write!(f, "{}[{}]", name, join(args, ", ")),
+            TypeExpr::Union(members) => write!(f, "{}", join(members, " | ")),
+            TypeExpr::Str(text) => write!(f, "\"{}\"", text),
+            TypeExpr::None => write!(f, "None"),
+        }
+    }
+}
+
+/// Strips a module prefix (`typing.Optional` -> `Optional`).
+fn simple_name(text: &str) -> String {
+    text.trim().rsplit('.').next().unwrap_or(text).to_string()
+}
+
+/// Splits on `sep` outside brackets and quotes.
+fn split_top_level(text: &str, sep: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut quote: Option<char> = None;
+    let mut start = 0;
+
+    for (i, c) in text.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '"' | '\'' => quote = Some(c),
+                '[' | '(' => depth += 1,
+                ']' | ')' => depth -= 1,
+                _ if c == sep && depth == 0 => {
+                    parts.push(&text[start..i]);
+                    start = i + c.len_utf8();
+                }
+                _ => {}
+            },
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
@@ -0,0 +1,247 @@
+//! Python frontend for the SpiteStack compiler.
+//!
+//! Aggregates are classes with an `initial_state` dict, an `events` list and
+//! `emit`/`apply` methods. `{Name}State` must be a `TypedDict`, and
+//! `{Name}Event` a union of `TypedDict`s discriminated by
+//! `type: Literal["..."]`:
+//!
+//! ```python
+//! class TodoCreated(TypedDict):
+//!     type: Literal["Created"]
+//!     title: str
+//!
+//! TodoEvent = Union[TodoCreated, TodoCompleted]
+//!
+//! class TodoAggregate:
+//!     initial_state: TodoState = {"title": "", "completed": False}
+//!
+//!     def create(self, title: str) -> None:
+//!         if not title:
+//!             raise ValueError("Title is required")
+//!         self.emit({"type": "Created", "title": title})
+//! ```
+//!
+//! Projections and orchestrators are TypeScript-only for now and are rejected.
+//! The generated project cannot import Python, so compiling emits TypeScript
+//! aggregate classes from the IR. Command bodies are translated; `apply` is
+//! not, and each event copies its same-named fields into state instead.
+
+pub mod ast;
+pub mod parser;
+pub mod to_ir;
+
+use std::path::Path;
+use walkdir::WalkDir;
+
+use crate::diagnostic::CompilerError;
+use crate::ir::DomainIR;
+use super::Frontend;
+use parser::PythonParser;
+
+/// Python frontend implementation.
+pub struct PythonFrontend {
+    parser: PythonParser,
+}
+
+impl PythonFrontend {
+    /// Creates a new Python frontend.
+    pub fn new() -> Result<Self, CompilerError> {
+        Ok(Self {
+            parser: PythonParser::new()?,
+        })
+    }
+}
+
+impl Frontend for PythonFrontend {
+    fn language(&self) -> &str {
+        "python"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["py"]
+    }
+
+    fn parse_directory(&mut self, dir: &Path) -> Result<DomainIR, CompilerError> {
+        let mut modules = Vec::new();
+
+        for entry in WalkDir::new(dir)
+            .follow_links(true)
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            let path = entry.path();
+            let is_source = path
+                .extension()
+                .is_some_and(|ext| self.extensions().contains(&ext.to_string_lossy().as_ref()));
+            if path.is_file() && is_source {
+                let source = std::fs::read_to_string(path).map_err(|e| CompilerError::IoError {
+                    path: path.to_path_buf(),
+                    message: e.to_string(),
+                })?;
+                modules.push(self.parser.parse(&source, path)?);
+            }
+        }
+
+        to_ir::to_ir(&modules, dir.to_path_buf())
+    }
+
+    fn imports_domain_sources(&self) -> bool {
+        // Handlers import generated TypeScript classes instead
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{DomainType, ExpressionIR, InitialValue, StatementIR};
+    use std::path::PathBuf;
+
+    const TODO: &str = r#"
+from typing import Literal, NotRequired, Optional, TypedDict, Union
+
+
+class TodoState(TypedDict):
+    title: str
+    completed: bool
+    tags: list[str]
+    due: NotRequired[Optional[int]]
+
+
+class TodoCreated(TypedDict):
+    type: Literal["Created"]
+    title: str
+
+
+class TodoCompleted(TypedDict):
+    type: Literal["Completed"]
+
+
+TodoEvent = Union[TodoCreated, TodoCompleted]
+
+
+class TodoAggregate:
+    initial_state: TodoState = {"title": "", "completed": False, "tags": []}
+
+    def __init__(self) -> None:
+        self.state = dict(self.initial_state)
+        self.events: list[TodoEvent] = []
+
+    def emit(self, event: TodoEvent) -> None:
+        self.apply(event)
+        self.events.append(event)
+
+    def apply(self, event: TodoEvent) -> None:
+        pass
+
+    def create(self, title: str, due: int | None = None) -> None:
+        """Create the todo."""
+        if not title:
+            raise ValueError("Title is required")
+        self.emit({"type": "Created", "title": title})
+
+    def complete(self) -> None:
+        if self.state["completed"]:
+            raise ValueError("Already completed")
+        elif self.state.title == "":
+            raise ValueError("Untitled")
+        self.emit({"type": "Completed"})
+
+    def _helper(self) -> None:
+        pass
+"#;
+
+    fn parse(source: &str) -> Result<DomainIR, CompilerError> {
+        let mut parser = PythonParser::new().unwrap();
+        let module = parser.parse(source, Path::new("todo.py")).unwrap();
+        to_ir::to_ir(&[module], PathBuf::from("domain"))
+    }
+
+    #[test]
+    fn converts_aggregate_to_ir() {
+        let domain = parse(TODO).unwrap();
+        assert_eq!(domain.aggregates.len(), 1);
+        let todo = &domain.aggregates[0];
+        assert_eq!(todo.name, "Todo");
+
+        let names: Vec<_> = todo.events.variants.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, ["Created", "Completed"]);
+        assert_eq!(todo.events.variants[0].fields[0].typ, DomainType::String);
+
+        let due = todo.state.fields.iter().find(|f| f.name == "due").unwrap();
+        assert!(due.optional);
+        assert_eq!(due.typ, DomainType::Option(Box::new(DomainType::Number)));
+        assert!(matches!(todo.initial_state[1], (ref k, InitialValue::Boolean(false)) if k == "completed"));
+
+        let commands: Vec<_> = todo.commands.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(commands, ["create", "complete"]);
+        assert_eq!(todo.commands[0].parameters.len(), 2);
+
+        let create = &todo.commands[0].body;
+        assert_eq!(create.len(), 2);
+        assert!(matches!(&create[1], StatementIR::Emit { event_type, fields }
+            if event_type == "Created" && fields.len() == 1));
+
+        let StatementIR::If { condition, else_branch, .. } = &todo.commands[1].body[0] else {
+            panic!("expected if statement");
+        };
+        assert!(matches!(condition, ExpressionIR::StateAccess(f) if f == "completed"));
+        assert!(matches!(else_branch.as_deref(), Some([StatementIR::If { .. }])));
+    }
+
+    #[test]
+    fn requires_event_type() {
+        let source = TODO.replace("TodoEvent = Union[TodoCreated, TodoCompleted]", "");
+        assert!(matches!(
+            parse(&source),
+            Err(CompilerError::MissingMember { member, .. }) if member == "TodoEvent"
+        ));
+    }
+
+    #[test]
+    fn labels_errors_with_source_location() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let source = TODO.replace("TodoEvent = Union[TodoCreated, TodoCompleted]", "");
+        std::fs::write(dir.path().join("todo.py"), &source).unwrap();
+
+        let err = PythonFrontend::new().unwrap().parse_directory(dir.path()).unwrap_err();
+        let span = err.span().expect("error should carry a span");
+        let class_line = source.lines().position(|l| l.starts_with("class TodoAggregate")).unwrap();
+        assert_eq!(span.start_line, class_line);
+        assert!(matches!(
+            err,
+            CompilerError::Located(ref located)
+                if matches!(located.error, CompilerError::MissingMember { .. })
+        ));
+
+        let json = err.to_json();
+        assert_eq!(json["code"], "spitestack::structure::missing_member");
+        assert_eq!(json["range"]["start"]["line"], class_line);
+    }
+
+    #[test]
+    fn rejects_projections_and_orchestrators() {
+        let projection = format!(
+            "{}\n\nclass TodoStats:\n    def build(self, event: TodoEvent) -> None:\n        pass\n",
+            TODO
+        );
+        let err = parse(&projection).unwrap_err();
+        assert_eq!(err.to_json()["code"], "spitestack::frontend::unsupported_declaration");
+        assert!(err.to_string().contains("Unsupported projection 'TodoStats'"), "{}", err);
+
+        let orchestrator = format!("{}\n\nclass SignupOrchestrator:\n    pass\n", TODO);
+        let err = parse(&orchestrator).unwrap_err().to_string();
+        assert!(err.contains("Unsupported orchestrator 'SignupOrchestrator'"), "{}", err);
+    }
+
+    #[test]
+    fn rejects_unions_of_different_types() {
+        let source = TODO.replace("due: int | None = None", "due: int | str | None = None");
+        let err = parse(&source).unwrap_err();
+        assert_eq!(err.to_json()["code"], "spitestack::types::unsupported_union");
+        assert!(err.to_string().contains("'int | str | None'"), "{}", err);
+
+        let literals = TODO.replace("title: str\n    completed", "title: Literal[\"a\"] | Literal[\"b\"]\n    completed");
+        assert!(parse(&literals).is_ok());
+    }
+}
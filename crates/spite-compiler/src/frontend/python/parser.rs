@@ -0,0 +1,651 @@
+//! Python parser using tree-sitter.
+
+use std::path::Path;
+use tree_sitter::{Node, Parser};
+
+use crate::diagnostic::{CompilerError, Span};
+use crate::ir::{BinaryOp, ExpressionIR, InitialValue, StatementIR, UnaryOp};
+use super::ast::*;
+
+/// Python parser.
+pub struct PythonParser {
+    parser: Parser,
+}
+
+impl PythonParser {
+    /// Creates a new Python parser.
+    pub fn new() -> Result<Self, CompilerError> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_python::LANGUAGE.into())
+            .map_err(|_| CompilerError::ParserInitFailed)?;
+        Ok(Self { parser })
+    }
+
+    /// Parses a Python source file.
+    pub fn parse(&mut self, source: &str, path: &Path) -> Result<ParsedModule, CompilerError> {
+        let tree = self
+            .parser
+            .parse(source, None)
+            .ok_or_else(|| CompilerError::ParseFailed { path: path.to_path_buf() })?;
+
+        let visitor = Visitor { source, path };
+        let mut module = ParsedModule {
+            path: path.to_path_buf(),
+            type_aliases: Vec::new(),
+            classes: Vec::new(),
+        };
+
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        for child in root.named_children(&mut cursor) {
+            match child.kind() {
+                "class_definition" => module.classes.push(visitor.visit_class(child)),
+                "decorated_definition" => {
+                    if let Some(def) = child.child_by_field_name("definition") {
+                        if def.kind() == "class_definition" {
+                            module.classes.push(visitor.visit_class(def));
+                        }
+                    }
+                }
+                "expression_statement" => {
+                    if let Some(alias) = visitor.visit_alias_assignment(child) {
+                        module.type_aliases.push(alias);
+                    }
+                }
+                "type_alias_statement" => {
+                    if let (Some(left), Some(right)) =
+                        (child.child_by_field_name("left"), child.child_by_field_name("right"))
+                    {
+                        module.type_aliases.push(TypeAlias {
+                            name: visitor.text(left).to_string(),
+                            type_expr: TypeExpr::parse(visitor.text(right)),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(module)
+    }
+}
+
+/// Extracts declarations from tree-sitter nodes.
+struct Visitor<'a> {
+    source: &'a str,
+    path: &'a Path,
+}
+
+impl<'a> Visitor<'a> {
+    fn text(&self, node: Node) -> &'a str {
+        &self.source[node.byte_range()]
+    }
+
+    fn span(&self, node: Node) -> Span {
+        Span::new(
+            self.path.to_path_buf(),
+            node.start_position().row,
+            node.start_position().column,
+            node.end_position().row,
+            node.end_position().column,
+        )
+    }
+
+    /// `TodoEvent = Union[...]` or `TodoEvent: TypeAlias = ...` at module level.
+    fn visit_alias_assignment(&self, node: Node) -> Option<TypeAlias> {
+        let assignment = node.named_child(0).filter(|n| n.kind() == "assignment")?;
+        let left = assignment.child_by_field_name("left").filter(|n| n.kind() == "identifier")?;
+        let right = assignment.child_by_field_name("right")?;
+        Some(TypeAlias {
+            name: self.text(left).to_string(),
+            type_expr: TypeExpr::parse(self.text(right)),
+        })
+    }
+
+    fn visit_class(&self, node: Node) -> ClassDecl {
+        let mut class = ClassDecl {
+            name: node
+                .child_by_field_name("name")
+                .map(|n| self.text(n).to_string())
+                .unwrap_or_default(),
+            bases: Vec::new(),
+            total: true,
+            attributes: Vec::new(),
+            methods: Vec::new(),
+            instance_attributes: Vec::new(),
+            span: self.span(node),
+        };
+
+        if let Some(superclasses) = node.child_by_field_name("superclasses") {
+            let mut cursor = superclasses.walk();
+            for arg in superclasses.named_children(&mut cursor) {
+                match arg.kind() {
+                    "keyword_argument" => {
+                        let name = arg.child_by_field_name("name").map(|n| self.text(n));
+                        let value = arg.child_by_field_name("value").map(|n| self.text(n));
+                        if name == Some("total") && value == Some("False") {
+                            class.total = false;
+                        }
+                    }
+                    _ => {
+                        let base = self.text(arg);
+                        class.bases.push(base.rsplit('.').next().unwrap_or(base).to_string());
+                    }
+                }
+            }
+        }
+
+        let Some(body) = node.child_by_field_name("body") else {
+            return class;
+        };
+        let mut cursor = body.walk();
+        for child in body.named_children(&mut cursor) {
+            match child.kind() {
+                "expression_statement" => {
+                    if let Some(attribute) = self.visit_class_attribute(child) {
+                        class.attributes.push(attribute);
+                    }
+                }
+                "function_definition" => self.visit_method(child, &mut class),
+                "decorated_definition" => {
+                    if let Some(def) = child.child_by_field_name("definition") {
+                        if def.kind() == "function_definition" {
+                            self.visit_method(def, &mut class);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        class
+    }
+
+    fn visit_class_attribute(&self, node: Node) -> Option<ClassAttribute> {
+        let assignment = node.named_child(0).filter(|n| n.kind() == "assignment")?;
+        let left = assignment.child_by_field_name("left").filter(|n| n.kind() == "identifier")?;
+        let dict_entries = assignment
+            .child_by_field_name("right")
+            .filter(|n| n.kind() == "dictionary")
+            .map(|n| self.dict_initial_values(n));
+
+        Some(ClassAttribute {
+            name: self.text(left).to_string(),
+            annotation: assignment
+                .child_by_field_name("type")
+                .map(|t| TypeExpr::parse(self.text(t))),
+            dict_entries,
+        })
+    }
+
+    fn visit_method(&self, node: Node, class: &mut ClassDecl) {
+        let name = node
+            .child_by_field_name("name")
+            .map(|n| self.text(n).to_string())
+            .unwrap_or_default();
+
+        let mut parameters = Vec::new();
+        if let Some(params) = node.child_by_field_name("parameters") {
+            let mut cursor = params.walk();
+            for param in params.named_children(&mut cursor) {
+                if let Some(parameter) = self.visit_parameter(param) {
+                    if parameter.name != "self" && parameter.name != "cls" {
+                        parameters.push(parameter);
+                    }
+                }
+            }
+        }
+
+        let body_node = node.child_by_field_name("body");
+        if name == "__init__" {
+            if let Some(body) = body_node {
+                class.instance_attributes.extend(self.self_assignments(body));
+            }
+        }
+
+        class.methods.push(MethodDecl {
+            name,
+            parameters,
+            body: body_node.map(|b| self.convert_block(b)).unwrap_or_default(),
+            span: self.span(node),
+        });
+    }
+
+    fn visit_parameter(&self, node: Node) -> Option<Parameter> {
+        let annotation = node
+            .child_by_field_name("type")
+            .map(|t| TypeExpr::parse(self.text(t)));
+
+        let (name, has_default) = match node.kind() {
+            "identifier" => (self.text(node), false),
+            // typed_parameter has no name field; the identifier is its first child
+            "typed_parameter" => (self.text(node.named_child(0)?), false),
+            "default_parameter" | "typed_default_parameter" => {
+                (self.text(node.child_by_field_name("name")?), true)
+            }
+            _ => return None,
+        };
+
+        Some(Parameter {
+            name: name.to_string(),
+            annotation,
+            has_default,
+        })
+    }
+
+    /// Names assigned as `self.<name> = ...` directly in a block.
+    fn self_assignments(&self, block: Node) -> Vec<String> {
+        let mut names = Vec::new();
+        let mut cursor = block.walk();
+        for stmt in block.named_children(&mut cursor) {
+            let Some(assignment) = stmt.named_child(0).filter(|n| n.kind() == "assignment") else {
+                continue;
+            };
+            if let Some(left) = assignment.child_by_field_name("left") {
+                if let Some(attr) = self.self_attribute(left) {
+                    names.push(attr.to_string());
+                }
+            }
+        }
+        names
+    }
+
+    /// Returns `name` when the node is `self.<name>`.
+    fn self_attribute(&self, node: Node) -> Option<&'a str> {
+        if node.kind() != "attribute" {
+            return None;
+        }
+        let object = node.child_by_field_name("object")?;
+        if object.kind() == "identifier" && self.text(object) == "self" {
+            node.child_by_field_name("attribute").map(|a| self.text(a))
+        } else {
+            None
+        }
+    }
+
+    /// Whether the node is `self.state`.
+    fn is_self_state(&self, node: Node) -> bool {
+        self.self_attribute(node) == Some("state")
+    }
+
+    fn string_value(&self, node: Node) -> String {
+        let mut value = String::new();
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            if child.kind() == "string_content" {
+                value.push_str(self.text(child));
+            }
+        }
+        value
+    }
+
+    fn dict_initial_values(&self, node: Node) -> Vec<(String, InitialValue)> {
+        let mut values = Vec::new();
+        let mut cursor = node.walk();
+        for pair in node.named_children(&mut cursor).filter(|n| n.kind() == "pair") {
+            let (Some(key), Some(value)) =
+                (pair.child_by_field_name("key"), pair.child_by_field_name("value"))
+            else {
+                continue;
+            };
+            let key = if key.kind() == "string" {
+                self.string_value(key)
+            } else {
+                self.text(key).to_string()
+            };
+
+            let initial = match value.kind() {
+                "string" => InitialValue::String(self.string_value(value)),
+                "integer" | "float" => self
+                    .text(value)
+                    .replace('_', "")
+                    .parse()
+                    .map(InitialValue::Number)
+                    .unwrap_or(InitialValue::Null),
+                "true" => InitialValue::Boolean(true),
+                "false" => InitialValue::Boolean(false),
+                "list" | "tuple" => InitialValue::EmptyArray,
+                "dictionary" => InitialValue::EmptyObject,
+                _ => InitialValue::Null,
+            };
+            values.push((key, initial));
+        }
+        values
+    }
+
+    fn convert_block(&self, block: Node) -> Vec<StatementIR> {
+        let mut cursor = block.walk();
+        block
+            .named_children(&mut cursor)
+            .filter_map(|stmt| self.convert_statement(stmt))
+            .collect()
+    }
+
+    fn convert_statement(&self, node: Node) -> Option<StatementIR> {
+        match node.kind() {
+            "if_statement" => self.convert_if(node),
+            "raise_statement" => Some(StatementIR::Throw {
+                message: self.raise_message(node),
+            }),
+            "return_statement" => Some(StatementIR::Return(
+                node.named_child(0).map(|e| self.convert_expression(e)),
+            )),
+            "expression_statement" => {
+                let inner = node.named_child(0)?;
+                match inner.kind() {
+                    "assignment" => {
+                        let left = inner.child_by_field_name("left").filter(|n| n.kind() == "identifier")?;
+                        let right = inner.child_by_field_name("right")?;
+                        Some(StatementIR::Let {
+                            name: self.text(left).to_string(),
+                            value: self.convert_expression(right),
+                        })
+                    }
+                    // Docstrings
+                    "string" => None,
+                    "call" => Some(
+                        self.convert_emit(inner)
+                            .unwrap_or_else(|| StatementIR::Expression(self.convert_expression(inner))),
+                    ),
+                    _ => Some(StatementIR::Expression(self.convert_expression(inner))),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn convert_if(&self, node: Node) -> Option<StatementIR> {
+        let condition = self.convert_expression(node.child_by_field_name("condition")?);
+        let then_branch = node
+            .child_by_field_name("consequence")
+            .map(|b| self.convert_block(b))
+            .unwrap_or_default();
+
+        // Fold `elif` clauses into nested ifs, innermost first
+        let mut cursor = node.walk();
+        let alternatives: Vec<Node> = node.children_by_field_name("alternative", &mut cursor).collect();
+        let mut else_branch = None;
+        for alternative in alternatives.iter().rev() {
+            match alternative.kind() {
+                "else_clause" => {
+                    else_branch = alternative.child_by_field_name("body").map(|b| self.convert_block(b));
+                }
+                "elif_clause" => {
+                    let Some(cond) = alternative.child_by_field_name("condition") else {
+                        continue;
+                    };
+                    else_branch = Some(vec![StatementIR::If {
+                        condition: self.convert_expression(cond),
+                        then_branch: alternative
+                            .child_by_field_name("consequence")
+                            .map(|b| self.convert_block(b))
+                            .unwrap_or_default(),
+                        else_branch: else_branch.take(),
+                    }]);
+                }
+                _ => {}
+            }
+        }
+
+        Some(StatementIR::If {
+            condition,
+            then_branch,
+            else_branch,
+        })
+    }
+
+    /// `raise ValueError("message")` -> "message".
+    fn raise_message(&self, node: Node) -> String {
+        let Some(exception) = node.named_child(0) else {
+            return "Error".to_string();
+        };
+        let message = match exception.kind() {
+            "call" => exception
+                .child_by_field_name("arguments")
+                .and_then(|args| args.named_child(0))
+                .filter(|arg| arg.kind() == "string")
+                .map(|arg| self.string_value(arg)),
+            "string" => Some(self.string_value(exception)),
+            _ => None,
+        };
+        message.unwrap_or_else(|| self.text(exception).to_string())
+    }
+
+    /// `self.emit({"type": "Created", ...})`
+    fn convert_emit(&self, call: Node) -> Option<StatementIR> {
+        let function = call.child_by_field_name("function")?;
+        if self.self_attribute(function) != Some("emit") {
+            return None;
+        }
+        let payload = call
+            .child_by_field_name("arguments")?
+            .named_child(0)
+            .filter(|n| n.kind() == "dictionary")?;
+
+        let mut event_type = None;
+        let mut fields = Vec::new();
+        for (key, value) in self.convert_dict(payload) {
+            match (key.as_str(), value) {
+                ("type", ExpressionIR::StringLiteral(s)) => event_type = Some(s),
+                (_, value) => fields.push((key, value)),
+            }
+        }
+
+        Some(StatementIR::Emit {
+            event_type: event_type?,
+            fields,
+        })
+    }
+
+    fn convert_dict(&self, node: Node) -> Vec<(String, ExpressionIR)> {
+        let mut entries = Vec::new();
+        let mut cursor = node.walk();
+        for pair in node.named_children(&mut cursor).filter(|n| n.kind() == "pair") {
+            let (Some(key), Some(value)) =
+                (pair.child_by_field_name("key"), pair.child_by_field_name("value"))
+            else {
+                continue;
+            };
+            let key = if key.kind() == "string" {
+                self.string_value(key)
+            } else {
+                self.text(key).to_string()
+            };
+            entries.push((key, self.convert_expression(value)));
+        }
+        entries
+    }
+
+    fn convert_arguments(&self, node: Option<Node>) -> Vec<ExpressionIR> {
+        let Some(args) = node else {
+            return Vec::new();
+        };
+        let mut cursor = args.walk();
+        args.named_children(&mut cursor)
+            .filter(|a| a.kind() != "comment")
+            .map(|a| match a.kind() {
+                "keyword_argument" => a
+                    .child_by_field_name("value")
+                    .map(|v| self.convert_expression(v))
+                    .unwrap_or_else(|| ExpressionIR::Identifier(self.text(a).to_string())),
+                _ => self.convert_expression(a),
+            })
+            .collect()
+    }
+
+    fn convert_expression(&self, node: Node) -> ExpressionIR {
+        let fallback = || ExpressionIR::Identifier(self.text(node).to_string());
+
+        match node.kind() {
+            "identifier" => ExpressionIR::Identifier(self.text(node).to_string()),
+            "string" => ExpressionIR::StringLiteral(self.string_value(node)),
+            "integer" | "float" => self
+                .text(node)
+                .replace('_', "")
+                .parse()
+                .map(ExpressionIR::NumberLiteral)
+                .unwrap_or_else(|_| fallback()),
+            "true" => ExpressionIR::BooleanLiteral(true),
+            "false" => ExpressionIR::BooleanLiteral(false),
+            "none" => ExpressionIR::Identifier("None".to_string()),
+            "parenthesized_expression" => node
+                .named_child(0)
+                .map(|inner| self.convert_expression(inner))
+                .unwrap_or_else(fallback),
+            "attribute" => {
+                let (Some(object), Some(attribute)) =
+                    (node.child_by_field_name("object"), node.child_by_field_name("attribute"))
+                else {
+                    return fallback();
+                };
+                if self.is_self_state(object) {
+                    ExpressionIR::StateAccess(self.text(attribute).to_string())
+                } else {
+                    ExpressionIR::PropertyAccess {
+                        object: Box::new(self.convert_expression(object)),
+                        property: self.text(attribute).to_string(),
+                    }
+                }
+            }
+            "subscript" => {
+                let (Some(value), Some(key)) =
+                    (node.child_by_field_name("value"), node.child_by_field_name("subscript"))
+                else {
+                    return fallback();
+                };
+                if key.kind() != "string" {
+                    return fallback();
+                }
+                let key = self.string_value(key);
+                if self.is_self_state(value) {
+                    ExpressionIR::StateAccess(key)
+                } else {
+                    ExpressionIR::PropertyAccess {
+                        object: Box::new(self.convert_expression(value)),
+                        property: key,
+                    }
+                }
+            }
+            "call" => {
+                let Some(function) = node.child_by_field_name("function") else {
+                    return fallback();
+                };
+                let arguments = self.convert_arguments(node.child_by_field_name("arguments"));
+                match function.kind() {
+                    "attribute" => match (
+                        function.child_by_field_name("object"),
+                        function.child_by_field_name("attribute"),
+                    ) {
+                        (Some(object), Some(method)) => ExpressionIR::MethodCall {
+                            object: Box::new(self.convert_expression(object)),
+                            method: self.text(method).to_string(),
+                            arguments,
+                        },
+                        _ => fallback(),
+                    },
+                    _ => ExpressionIR::Call {
+                        callee: self.text(function).to_string(),
+                        arguments,
+                    },
+                }
+            }
+            "comparison_operator" => self.convert_comparison(node).unwrap_or_else(fallback),
+            "boolean_operator" => {
+                let operator = match node.child_by_field_name("operator").map(|o| o.kind()) {
+                    Some("and") => BinaryOp::And,
+                    Some("or") => BinaryOp::Or,
+                    _ => return fallback(),
+                };
+                self.binary(node, operator).unwrap_or_else(fallback)
+            }
+            "binary_operator" => {
+                let operator = match node.child_by_field_name("operator").map(|o| o.kind()) {
+                    Some("+") => BinaryOp::Add,
+                    Some("-") => BinaryOp::Sub,
+                    Some("*") => BinaryOp::Mul,
+                    Some("/") => BinaryOp::Div,
+                    _ => return fallback(),
+                };
+                self.binary(node, operator).unwrap_or_else(fallback)
+            }
+            "not_operator" => match node.child_by_field_name("argument") {
+                Some(argument) => ExpressionIR::Unary {
+                    operator: UnaryOp::Not,
+                    operand: Box::new(self.convert_expression(argument)),
+                },
+                None => fallback(),
+            },
+            "unary_operator" => {
+                let Some(argument) = node.child_by_field_name("argument") else {
+                    return fallback();
+                };
+                match node.child_by_field_name("operator").map(|o| o.kind()) {
+                    Some("-") => ExpressionIR::Unary {
+                        operator: UnaryOp::Neg,
+                        operand: Box::new(self.convert_expression(argument)),
+                    },
+                    Some("+") => self.convert_expression(argument),
+                    _ => fallback(),
+                }
+            }
+            "dictionary" => ExpressionIR::Object(self.convert_dict(node)),
+            "list" | "tuple" => {
+                let mut cursor = node.walk();
+                ExpressionIR::Array(
+                    node.named_children(&mut cursor)
+                        .filter(|n| n.kind() != "comment")
+                        .map(|n| self.convert_expression(n))
+                        .collect(),
+                )
+            }
+            _ => fallback(),
+        }
+    }
+
+    fn binary(&self, node: Node, operator: BinaryOp) -> Option<ExpressionIR> {
+        Some(ExpressionIR::Binary {
+            left: Box::new(self.convert_expression(node.child_by_field_name("left")?)),
+            operator,
+            right: Box::new(self.convert_expression(node.child_by_field_name("right")?)),
+        })
+    }
+
+    /// Comparisons. Chained comparisons (`a < b < c`) keep only the first pair;
+    /// `x in xs` becomes `xs.includes(x)`, the same IR a TypeScript domain produces.
+    fn convert_comparison(&self, node: Node) -> Option<ExpressionIR> {
+        let left = self.convert_expression(node.named_child(0)?);
+        let right = self.convert_expression(node.named_child(1)?);
+        let mut cursor = node.walk();
+        let operator = node.children_by_field_name("operators", &mut cursor).next()?;
+
+        let includes = |left: ExpressionIR, right: ExpressionIR| ExpressionIR::MethodCall {
+            object: Box::new(right),
+            method: "includes".to_string(),
+            arguments: vec![left],
+        };
+
+        let op = match operator.kind() {
+            "==" | "is" => BinaryOp::Eq,
+            "!=" | "is not" => BinaryOp::NotEq,
+            "<" => BinaryOp::Lt,
+            "<=" => BinaryOp::LtEq,
+            ">" => BinaryOp::Gt,
+            ">=" => BinaryOp::GtEq,
+            "in" => return Some(includes(left, right)),
+            "not in" => {
+                return Some(ExpressionIR::Unary {
+                    operator: UnaryOp::Not,
+                    operand: Box::new(includes(left, right)),
+                })
+            }
+            _ => return None,
+        };
+
+        Some(ExpressionIR::Binary {
+            left: Box::new(left),
+            operator: op,
+            right: Box::new(right),
+        })
+    }
+}
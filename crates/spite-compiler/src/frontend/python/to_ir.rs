@@ -0,0 +1,329 @@
+//! Convert Python declarations to language-agnostic IR.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::diagnostic::CompilerError;
+use crate::ir::{
+    AggregateIR, CommandIR, DomainIR, DomainType, EventField, EventTypeIR, EventVariant,
+    FieldDef, ObjectType, ParameterIR,
+};
+use super::ast::*;
+
+/// Converts parsed Python modules to domain IR.
+pub fn to_ir(modules: &[ParsedModule], source_dir: PathBuf) -> Result<DomainIR, CompilerError> {
+    let mut domain = DomainIR::new(source_dir);
+
+    let classes: HashMap<&str, &ClassDecl> = modules
+        .iter()
+        .flat_map(|m| m.classes.iter())
+        .map(|c| (c.name.as_str(), c))
+        .collect();
+    let aliases: HashMap<&str, &TypeAlias> = modules
+        .iter()
+        .flat_map(|m| m.type_aliases.iter())
+        .map(|a| (a.name.as_str(), a))
+        .collect();
+
+    for class in modules.iter().flat_map(|m| m.classes.iter()) {
+        if let Some(kind) = unsupported_kind(class) {
+            return Err(CompilerError::UnsupportedDeclaration {
+                name: class.name.clone(),
+                kind: kind.to_string(),
+                language: "python".to_string(),
+            }
+            .at(&class.span, format!("{}s are TypeScript-only", kind)));
+        }
+    }
+
+    for module in modules {
+        for class in module.classes.iter().filter(|c| is_aggregate(c)) {
+            domain
+                .aggregates
+                .push(convert_aggregate(class, &classes, &aliases, &module.path)?);
+        }
+    }
+
+    if domain.aggregates.is_empty() {
+        return Err(CompilerError::NoAggregates);
+    }
+
+    Ok(domain)
+}
+
+/// Checks if a class is an aggregate (has initial_state, events, emit, apply).
+fn is_aggregate(class: &ClassDecl) -> bool {
+    let has_initial_state = class.attributes.iter().any(|a| a.name == "initial_state");
+    let has_events = class.attributes.iter().any(|a| a.name == "events")
+        || class.instance_attributes.iter().any(|a| a == "events");
+    let has_emit = class.methods.iter().any(|m| m.name == "emit");
+    let has_apply = class.methods.iter().any(|m| m.name == "apply");
+
+    has_initial_state && has_events && has_emit && has_apply
+}
+
+/// Projections (a `build` method, as in TypeScript) and orchestrators are
+/// not supported in Python; they are reported rather than skipped.
+fn unsupported_kind(class: &ClassDecl) -> Option<&'static str> {
+    if class.name.ends_with("Orchestrator") {
+        Some("orchestrator")
+    } else if class.name.ends_with("Projection") || class.methods.iter().any(|m| m.name == "build") {
+        Some("projection")
+    } else {
+        None
+    }
+}
+
+fn convert_aggregate(
+    class: &ClassDecl,
+    classes: &HashMap<&str, &ClassDecl>,
+    aliases: &HashMap<&str, &TypeAlias>,
+    source_path: &Path,
+) -> Result<AggregateIR, CompilerError> {
+    let name = class.name.trim_end_matches("Aggregate").to_string();
+
+    // Event type: a union alias of TypedDicts, or a single TypedDict
+    let event_type_name = format!("{}Event", name);
+    let members = match (aliases.get(event_type_name.as_str()), classes.get(event_type_name.as_str())) {
+        (Some(alias), _) => union_members(&alias.type_expr),
+        (None, Some(single)) if single.is_typed_dict() => vec![TypeExpr::Name(single.name.clone())],
+        _ => {
+            let label = format!("no `{}` type found for this aggregate", event_type_name);
+            return Err(CompilerError::MissingMember {
+                member: event_type_name,
+                aggregate: class.name.clone(),
+            }
+            .at(&class.span, label));
+        }
+    };
+    let variants = members
+        .iter()
+        .map(|member| convert_event_variant(member, classes))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.at(&class.span, format!("while reading `{}`", event_type_name)))?;
+
+    // State type: a TypedDict
+    let state_type_name = format!("{}State", name);
+    let state_class = classes
+        .get(state_type_name.as_str())
+        .ok_or_else(|| {
+            CompilerError::MissingMember {
+                member: state_type_name.clone(),
+                aggregate: class.name.clone(),
+            }
+            .at(&class.span, format!("no `{}` type found for this aggregate", state_type_name))
+        })?;
+    if !state_class.is_typed_dict() {
+        return Err(CompilerError::InvalidStateType {
+            type_name: state_type_name,
+        }
+        .at(&state_class.span, "expected a TypedDict"));
+    }
+
+    let initial_state = class
+        .attributes
+        .iter()
+        .find(|a| a.name == "initial_state")
+        .and_then(|a| a.dict_entries.clone())
+        .unwrap_or_default();
+
+    // Commands are public methods other than emit/apply
+    let commands = class
+        .methods
+        .iter()
+        .filter(|m| !m.name.starts_with('_') && !matches!(m.name.as_str(), "emit" | "apply"))
+        .map(convert_command)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(AggregateIR {
+        name,
+        source_path: source_path.to_path_buf(),
+        span: Some(class.span.clone()),
+        state: typed_dict_fields(state_class)?,
+        initial_state,
+        events: EventTypeIR {
+            name: event_type_name,
+            variants,
+        },
+        commands,
+        // There is no TypeScript apply body to pass through
+        raw_apply_body: None,
+    })
+}
+
+/// Flattens `Union[A, B]`, `A | B` and `Annotated[Union[...], ...]`.
+fn union_members(expr: &TypeExpr) -> Vec<TypeExpr> {
+    match expr {
+        TypeExpr::Union(members) => members.iter().flat_map(union_members).collect(),
+        TypeExpr::Generic(name, args) if name == "Union" => args.iter().flat_map(union_members).collect(),
+        TypeExpr::Generic(name, args) if name == "Annotated" => {
+            args.first().map(union_members).unwrap_or_default()
+        }
+        TypeExpr::Str(forward) => union_members(&TypeExpr::parse(forward)),
+        other => vec![other.clone()],
+    }
+}
+
+/// Converts a union member (a TypedDict with `type: Literal["..."]`) to an event variant.
+fn convert_event_variant(
+    member: &TypeExpr,
+    classes: &HashMap<&str, &ClassDecl>,
+) -> Result<EventVariant, CompilerError> {
+    let invalid = |type_name: String| CompilerError::InvalidEventType { type_name };
+
+    let TypeExpr::Name(class_name) = member else {
+        return Err(invalid("union member".to_string()));
+    };
+    let class = classes
+        .get(class_name.as_str())
+        .filter(|c| c.is_typed_dict())
+        .ok_or_else(|| invalid(class_name.clone()))?;
+
+    let discriminant = class
+        .attributes
+        .iter()
+        .find(|a| a.name == "type")
+        .and_then(|a| a.annotation.as_ref())
+        .ok_or_else(|| invalid(format!("{}: missing type discriminant", class_name)))?;
+    let variant_name = match discriminant {
+        TypeExpr::Generic(name, args) if name == "Literal" => match args.as_slice() {
+            [TypeExpr::Str(value)] => value.clone(),
+            _ => return Err(invalid(format!("{}: type must be a string literal", class_name))),
+        },
+        _ => return Err(invalid(format!("{}: type must be a string literal", class_name))),
+    };
+
+    let fields = typed_dict_fields(class)?
+        .fields
+        .into_iter()
+        .filter(|f| f.name != "type")
+        .map(|f| EventField {
+            name: f.name,
+            typ: f.typ,
+        })
+        .collect();
+
+    Ok(EventVariant {
+        name: variant_name,
+        span: Some(class.span.clone()),
+        fields,
+    })
+}
+
+/// Annotated class attributes of a TypedDict as an object type.
+fn typed_dict_fields(class: &ClassDecl) -> Result<ObjectType, CompilerError> {
+    let mut fields = Vec::new();
+
+    for attribute in &class.attributes {
+        let Some(annotation) = attribute.annotation.as_ref() else {
+            continue;
+        };
+        let (annotation, not_required) = match annotation {
+            TypeExpr::Generic(name, args) if name == "NotRequired" || name == "Required" => {
+                match args.first() {
+                    Some(inner) => (inner, name == "NotRequired"),
+                    None => continue,
+                }
+            }
+            other => (other, !class.total),
+        };
+        fields.push(FieldDef {
+            name: attribute.name.clone(),
+            typ: convert_type(annotation)
+                .map_err(|e| e.at(&class.span, format!("in field `{}`", attribute.name)))?,
+            optional: not_required,
+        });
+    }
+
+    Ok(ObjectType { fields })
+}
+
+fn convert_command(method: &MethodDecl) -> Result<CommandIR, CompilerError> {
+    let mut parameters = Vec::new();
+
+    for p in &method.parameters {
+        let typ = match &p.annotation {
+            Some(annotation) => convert_type(annotation)
+                .map_err(|e| e.at(&method.span, format!("in parameter `{}`", p.name)))?,
+            None => DomainType::String,
+        };
+        parameters.push(ParameterIR {
+            name: p.name.clone(),
+            // Parameters with defaults may be omitted by callers
+            typ: match typ {
+                DomainType::Option(_) => typ,
+                _ if p.has_default => DomainType::Option(Box::new(typ)),
+                _ => typ,
+            },
+        });
+    }
+
+    Ok(CommandIR {
+        name: method.name.clone(),
+        span: Some(method.span.clone()),
+        parameters,
+        body: method.body.clone(),
+        // Default to Internal - access config will be applied later
+        access: crate::ir::AccessLevel::Internal,
+        roles: Vec::new(),
+        stream_id: None,
+    })
+}
+
+/// Converts a type annotation to a DomainType.
+fn convert_type(expr: &TypeExpr) -> Result<DomainType, CompilerError> {
+    Ok(match expr {
+        TypeExpr::Name(name) => match name.as_str() {
+            "str" => DomainType::String,
+            "int" | "float" => DomainType::Number,
+            "bool" => DomainType::Boolean,
+            _ => DomainType::Reference(name.clone()),
+        },
+        TypeExpr::Str(forward) => convert_type(&TypeExpr::parse(forward))?,
+        TypeExpr::None => DomainType::Reference("None".to_string()),
+        TypeExpr::Union(members) => convert_union(expr, members)?,
+        TypeExpr::Generic(name, args) => match (name.as_str(), args.first()) {
+            ("list" | "List" | "Sequence" | "set" | "Set" | "frozenset" | "tuple" | "Tuple", Some(inner)) => {
+                DomainType::Array(Box::new(convert_type(inner)?))
+            }
+            ("Optional", Some(inner)) => DomainType::Option(Box::new(convert_type(inner)?)),
+            ("Union", _) => convert_union(expr, args)?,
+            ("Annotated" | "Required" | "NotRequired" | "ReadOnly" | "Final", Some(inner)) => {
+                convert_type(inner)?
+            }
+            ("Literal", Some(TypeExpr::Name(value))) if value == "True" || value == "False" => {
+                DomainType::Boolean
+            }
+            ("Literal", Some(TypeExpr::Name(value))) if value.parse::<f64>().is_ok() => DomainType::Number,
+            ("Literal", _) => DomainType::String,
+            _ => DomainType::Reference(name.clone()),
+        },
+    })
+}
+
+/// `T | None` becomes an optional `T`. Unions whose members map to different
+/// types (`int | str`) have no single `DomainType` and are rejected.
+fn convert_union(union: &TypeExpr, members: &[TypeExpr]) -> Result<DomainType, CompilerError> {
+    let has_none = members.contains(&TypeExpr::None);
+    let converted = members
+        .iter()
+        .filter(|m| **m != TypeExpr::None)
+        .map(convert_type)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let inner = match converted.split_first() {
+        None => DomainType::Reference("None".to_string()),
+        Some((first, rest)) if rest.iter().all(|t| t == first) => first.clone(),
+        Some(_) => {
+            return Err(CompilerError::UnsupportedUnion {
+                type_desc: union.to_string(),
+            })
+        }
+    };
+
+    if has_none {
+        Ok(DomainType::Option(Box::new(inner)))
+    } else {
+        Ok(inner)
+    }
+}
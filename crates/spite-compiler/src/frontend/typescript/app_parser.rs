@@ -14,7 +14,8 @@
 //!   roles: ['user'],
 //!   methods: {
 //!     create: { access: 'public' },
-//!     cancel: { access: 'internal', roles: ['admin'] }
+//!     cancel: { access: 'internal', roles: ['admin'] },
+//!     ship: { streamId: 'order-{orderId}' } // POST /order/:orderId/ship
 //!   }
 //! });
 //!
@@ -286,6 +287,15 @@ impl<'a> AppConfigExtractor<'a> {
         AccessLevel::from_str(text).unwrap_or(AccessLevel::Internal)
     }
 
+    /// Parse: 'value'
+    fn parse_string(&self, node: Node) -> Option<String> {
+        if node.kind() != "string" {
+            return None;
+        }
+        let value = self.node_text(node).trim_matches(|c| c == '"' || c == '\'');
+        (!value.is_empty()).then(|| value.to_string())
+    }
+
     /// Parse: ['role1', 'role2']
     fn parse_string_array(&self, node: Node) -> Vec<String> {
         let mut result = Vec::new();
@@ -330,7 +340,7 @@ impl<'a> AppConfigExtractor<'a> {
         methods
     }
 
-    /// Parse: { access: '...', roles: [...] }
+    /// Parse: { access: '...', roles: [...], streamId: '...' }
     fn parse_method_config(&self, node: Node) -> MethodAccessConfig {
         let mut config = MethodAccessConfig::default();
 
@@ -351,6 +361,9 @@ impl<'a> AppConfigExtractor<'a> {
                             "roles" => {
                                 config.roles = self.parse_string_array(value);
                             }
+                            "streamId" => {
+                                config.stream_id = self.parse_string(value);
+                            }
                             _ => {}
                         }
                     }
@@ -461,6 +474,25 @@ mod tests {
         assert_eq!(order_config.methods["cancel"].roles, vec!["admin"]);
     }
 
+    #[test]
+    fn test_parse_method_stream_id() {
+        let source = r#"
+            const app = new App();
+            app.register(OrderAggregate, {
+                methods: {
+                    cancel: { access: 'private', streamId: 'order-{orderId}' }
+                }
+            });
+        "#;
+
+        let dir = setup_test_dir(source);
+        let config = parse_app_config(dir.path()).unwrap().unwrap();
+
+        let resolved = config.entities["OrderAggregate"].resolve_method("cancel");
+        assert_eq!(resolved.stream_id.as_deref(), Some("order-{orderId}"));
+        assert_eq!(config.entities["OrderAggregate"].resolve_method("create").stream_id, None);
+    }
+
     #[test]
     fn test_no_index_file() {
         let dir = TempDir::new().unwrap();
@@ -74,6 +74,7 @@ pub struct ObjectProperty {
     pub name: String,
     pub type_node: TypeNode,
     pub optional: bool,
+    pub span: Span,
 }
 
 /// A class declaration.
@@ -71,6 +71,17 @@ impl<'a> Visitor<'a> {
         )
     }
 
+    /// A syntax error labeled at `node`.
+    fn syntax_error(&self, node: Node, message: &str) -> CompilerError {
+        CompilerError::SyntaxError {
+            message: message.to_string(),
+            file: self.path.to_path_buf(),
+            line: node.start_position().row,
+            column: node.start_position().column,
+        }
+        .at(&self.span(node), "here")
+    }
+
     fn node_text(&self, node: Node) -> &str {
         node.utf8_text(self.source.as_bytes()).unwrap_or("")
     }
@@ -378,6 +389,7 @@ impl<'a> Visitor<'a> {
             name,
             type_node,
             optional,
+            span: self.span(node),
         }))
     }
 
@@ -759,12 +771,7 @@ impl<'a> Visitor<'a> {
             }
         }
 
-        let condition = condition.ok_or_else(|| CompilerError::SyntaxError {
-            message: "Missing condition in if statement".to_string(),
-            file: self.path.to_path_buf(),
-            line: node.start_position().row,
-            column: node.start_position().column,
-        })?;
+        let condition = condition.ok_or_else(|| self.syntax_error(node, "Missing condition in if statement"))?;
 
         Ok(Some(Statement::If {
             condition,
@@ -791,12 +798,7 @@ impl<'a> Visitor<'a> {
             }
         }
 
-        let discriminant = discriminant.ok_or_else(|| CompilerError::SyntaxError {
-            message: "Missing discriminant in switch statement".to_string(),
-            file: self.path.to_path_buf(),
-            line: node.start_position().row,
-            column: node.start_position().column,
-        })?;
+        let discriminant = discriminant.ok_or_else(|| self.syntax_error(node, "Missing discriminant in switch statement"))?;
 
         Ok(Some(Statement::Switch {
             discriminant,
@@ -899,12 +901,7 @@ impl<'a> Visitor<'a> {
             }
         }
 
-        let argument = argument.ok_or_else(|| CompilerError::SyntaxError {
-            message: "Missing argument in throw statement".to_string(),
-            file: self.path.to_path_buf(),
-            line: node.start_position().row,
-            column: node.start_position().column,
-        })?;
+        let argument = argument.ok_or_else(|| self.syntax_error(node, "Missing argument in throw statement"))?;
 
         Ok(Some(Statement::Throw {
             argument,
@@ -1061,12 +1058,7 @@ impl<'a> Visitor<'a> {
                     }
                 }
 
-                let object = object.ok_or_else(|| CompilerError::SyntaxError {
-                    message: "Missing object in member expression".to_string(),
-                    file: self.path.to_path_buf(),
-                    line: node.start_position().row,
-                    column: node.start_position().column,
-                })?;
+                let object = object.ok_or_else(|| self.syntax_error(node, "Missing object in member expression"))?;
 
                 Ok(Expression::MemberAccess {
                     object: Box::new(object),
@@ -1099,12 +1091,7 @@ impl<'a> Visitor<'a> {
                     }
                 }
 
-                let callee = callee.ok_or_else(|| CompilerError::SyntaxError {
-                    message: "Missing callee in call expression".to_string(),
-                    file: self.path.to_path_buf(),
-                    line: node.start_position().row,
-                    column: node.start_position().column,
-                })?;
+                let callee = callee.ok_or_else(|| self.syntax_error(node, "Missing callee in call expression"))?;
 
                 Ok(Expression::Call {
                     callee: Box::new(callee),
@@ -1138,12 +1125,7 @@ impl<'a> Visitor<'a> {
                     }
                 }
 
-                let callee = callee.ok_or_else(|| CompilerError::SyntaxError {
-                    message: "Missing callee in new expression".to_string(),
-                    file: self.path.to_path_buf(),
-                    line: node.start_position().row,
-                    column: node.start_position().column,
-                })?;
+                let callee = callee.ok_or_else(|| self.syntax_error(node, "Missing callee in new expression"))?;
 
                 Ok(Expression::New {
                     callee: Box::new(callee),
@@ -1168,19 +1150,9 @@ impl<'a> Visitor<'a> {
                     }
                 }
 
-                let left = left.ok_or_else(|| CompilerError::SyntaxError {
-                    message: "Missing left operand".to_string(),
-                    file: self.path.to_path_buf(),
-                    line: node.start_position().row,
-                    column: node.start_position().column,
-                })?;
+                let left = left.ok_or_else(|| self.syntax_error(node, "Missing left operand"))?;
 
-                let right = right.ok_or_else(|| CompilerError::SyntaxError {
-                    message: "Missing right operand".to_string(),
-                    file: self.path.to_path_buf(),
-                    line: node.start_position().row,
-                    column: node.start_position().column,
-                })?;
+                let right = right.ok_or_else(|| self.syntax_error(node, "Missing right operand"))?;
 
                 Ok(Expression::Binary {
                     left: Box::new(left),
@@ -1203,12 +1175,7 @@ impl<'a> Visitor<'a> {
                     }
                 }
 
-                let argument = argument.ok_or_else(|| CompilerError::SyntaxError {
-                    message: "Missing argument in unary expression".to_string(),
-                    file: self.path.to_path_buf(),
-                    line: node.start_position().row,
-                    column: node.start_position().column,
-                })?;
+                let argument = argument.ok_or_else(|| self.syntax_error(node, "Missing argument in unary expression"))?;
 
                 Ok(Expression::Unary {
                     operator,
@@ -1227,12 +1194,7 @@ impl<'a> Visitor<'a> {
                     }
                 }
 
-                let argument = argument.ok_or_else(|| CompilerError::SyntaxError {
-                    message: "Missing argument in await expression".to_string(),
-                    file: self.path.to_path_buf(),
-                    line: node.start_position().row,
-                    column: node.start_position().column,
-                })?;
+                let argument = argument.ok_or_else(|| self.syntax_error(node, "Missing argument in await expression"))?;
 
                 Ok(Expression::Await {
                     argument: Box::new(argument),
@@ -1246,12 +1208,7 @@ impl<'a> Visitor<'a> {
                         return self.visit_expression(child);
                     }
                 }
-                Err(CompilerError::SyntaxError {
-                    message: "Empty parenthesized expression".to_string(),
-                    file: self.path.to_path_buf(),
-                    line: node.start_position().row,
-                    column: node.start_position().column,
-                })
+                Err(self.syntax_error(node, "Empty parenthesized expression"))
             }
             "spread_element" => {
                 let mut argument = None;
@@ -1263,12 +1220,7 @@ impl<'a> Visitor<'a> {
                     }
                 }
 
-                let argument = argument.ok_or_else(|| CompilerError::SyntaxError {
-                    message: "Missing argument in spread element".to_string(),
-                    file: self.path.to_path_buf(),
-                    line: node.start_position().row,
-                    column: node.start_position().column,
-                })?;
+                let argument = argument.ok_or_else(|| self.syntax_error(node, "Missing argument in spread element"))?;
 
                 Ok(Expression::Spread {
                     argument: Box::new(argument),
@@ -38,7 +38,8 @@ pub fn to_ir(files: &[ParsedFile], source_dir: PathBuf) -> Result<DomainIR, Comp
                 let aggregate = convert_aggregate(class, &all_event_types, &all_state_types, &file.path)?;
                 domain.aggregates.push(aggregate);
             } else if is_projection(class) {
-                let projection = convert_projection(class, &all_event_types, &file.path)?;
+                let projection = convert_projection(class, &all_event_types, &file.path)
+                    .map_err(|e| e.at(&class.span, "in this projection"))?;
                 domain.projections.push(projection);
             }
         }
@@ -76,9 +77,12 @@ fn convert_aggregate(
     let event_type = event_types
         .iter()
         .find(|t| t.name == event_type_name)
-        .ok_or_else(|| CompilerError::MissingMember {
-            member: event_type_name.clone(),
-            aggregate: class.name.clone(),
+        .ok_or_else(|| {
+            CompilerError::MissingMember {
+                member: event_type_name.clone(),
+                aggregate: class.name.clone(),
+            }
+            .at(&class.span, format!("no `{}` type found for this aggregate", event_type_name))
         })?;
 
     // Find matching state type
@@ -86,16 +90,21 @@ fn convert_aggregate(
     let state_type = state_types
         .iter()
         .find(|t| t.name == state_type_name)
-        .ok_or_else(|| CompilerError::MissingMember {
-            member: state_type_name.clone(),
-            aggregate: class.name.clone(),
+        .ok_or_else(|| {
+            CompilerError::MissingMember {
+                member: state_type_name.clone(),
+                aggregate: class.name.clone(),
+            }
+            .at(&class.span, format!("no `{}` type found for this aggregate", state_type_name))
         })?;
 
     // Convert event type
-    let events = convert_event_type(event_type)?;
+    let events = convert_event_type(event_type)
+        .map_err(|e| e.at(&event_type.span, "in this event type"))?;
 
     // Convert state type
-    let state = convert_state_type(state_type)?;
+    let state = convert_state_type(state_type)
+        .map_err(|e| e.at(&state_type.span, "in this state type"))?;
 
     // Extract initial state values
     let initial_state = extract_initial_state(class);
@@ -123,6 +132,7 @@ fn convert_aggregate(
     Ok(AggregateIR {
         name,
         source_path: source_path.to_path_buf(),
+        span: Some(class.span.clone()),
         state,
         initial_state,
         events,
@@ -202,6 +212,7 @@ fn convert_object_to_variant(props: &[ObjectProperty]) -> Result<EventVariant, C
 
     Ok(EventVariant {
         name: variant_name,
+        span: Some(type_prop.span.clone()),
         fields,
     })
 }
@@ -358,11 +369,13 @@ fn convert_command(method: &MethodDecl) -> Result<CommandIR, CompilerError> {
 
     Ok(CommandIR {
         name: method.name.clone(),
+        span: Some(method.span.clone()),
         parameters,
         body,
         // Default to Internal - access config will be applied later
         access: crate::ir::AccessLevel::Internal,
         roles: Vec::new(),
+        stream_id: None,
     })
 }
 
@@ -649,6 +662,7 @@ pub fn apply_access_config(domain: &mut DomainIR, app_config: &crate::ir::AppCon
             let method_config = entity_config.resolve_method(&cmd.name);
             cmd.access = method_config.access;
             cmd.roles = method_config.roles;
+            cmd.stream_id = method_config.stream_id;
         }
     }
 }
@@ -992,7 +1006,32 @@ fn extract_projection_schema(
     };
 
     // Derive indexes from query method parameters
-    let indexes = derive_indexes_from_queries(class, &columns);
+    let mut indexes = derive_indexes_from_queries(class, &columns);
+
+    // Columns declared unique get a unique index (per tenant)
+    for field in extract_unique_fields(class) {
+        let column = to_snake_case(&field);
+        if primary_keys.iter().any(|pk| pk.name == column) {
+            return Err(CompilerError::InvalidProjection {
+                name: class.name.clone(),
+                reason: format!("unique field '{}' is already the primary key", field),
+            });
+        }
+        if !columns.iter().any(|c| c.name == column) {
+            return Err(CompilerError::InvalidProjection {
+                name: class.name.clone(),
+                reason: format!("unique field '{}' is not a field of the state", field),
+            });
+        }
+        match indexes.iter_mut().find(|idx| idx.columns == [column.as_str()]) {
+            Some(idx) => idx.unique = true,
+            None => indexes.push(IndexDef {
+                name: format!("uq_{}", column),
+                columns: vec![column],
+                unique: true,
+            }),
+        }
+    }
 
     Ok(ProjectionSchema {
         state_property_name,
@@ -1002,6 +1041,26 @@ fn extract_projection_schema(
     })
 }
 
+/// Reads `static unique = ['email']`: state fields whose values must be
+/// unique within a tenant.
+fn extract_unique_fields(class: &ClassDecl) -> Vec<String> {
+    class
+        .properties
+        .iter()
+        .find(|p| p.name == "unique" && p.is_static)
+        .and_then(|p| p.initializer.as_deref())
+        .map(|init| {
+            init.trim()
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .split(',')
+                .map(|s| s.trim().trim_matches(|c| c == '"' || c == '\'').to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 /// Extracts column definitions from a type node.
 fn extract_columns_from_type(type_node: &TypeNode) -> Vec<ColumnDef> {
     match type_node {
@@ -89,6 +89,9 @@ pub struct MethodAccessConfig {
     /// Required roles to access this method.
     /// Only applicable for `Internal` and `Private` access levels.
     pub roles: Vec<String>,
+
+    /// Stream id template for the method's route (e.g. `"order-{orderId}"`).
+    pub stream_id: Option<String>,
 }
 
 /// Access configuration for an aggregate or orchestrator.
@@ -118,12 +121,14 @@ impl EntityAccessConfig {
                 } else {
                     method_config.roles.clone()
                 },
+                stream_id: method_config.stream_id.clone(),
             }
         } else {
             // Use entity defaults
             MethodAccessConfig {
                 access: self.access,
                 roles: self.roles.clone(),
+                stream_id: None,
             }
         }
     }
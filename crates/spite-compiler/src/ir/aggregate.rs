@@ -1,6 +1,7 @@
 //! Aggregate intermediate representation.
 
 use std::path::PathBuf;
+use crate::diagnostic::Span;
 use super::{AccessLevel, DomainType, ObjectType, ParameterIR, InitialValue};
 
 /// IR representation of an aggregate.
@@ -12,6 +13,9 @@ pub struct AggregateIR {
     /// Source file path.
     pub source_path: PathBuf,
 
+    /// Location of the aggregate class, for diagnostics.
+    pub span: Option<Span>,
+
     /// The state type.
     pub state: ObjectType,
 
@@ -45,6 +49,9 @@ pub struct EventVariant {
     /// The discriminant value (e.g., "Created", "Completed").
     pub name: String,
 
+    /// Location of the variant's declaration, for diagnostics and editors.
+    pub span: Option<Span>,
+
     /// Fields in this event variant.
     pub fields: Vec<EventField>,
 }
@@ -62,6 +69,9 @@ pub struct CommandIR {
     /// Name of the command (e.g., "create", "complete").
     pub name: String,
 
+    /// Location of the command method, for diagnostics.
+    pub span: Option<Span>,
+
     /// Parameters to the command.
     pub parameters: Vec<ParameterIR>,
 
@@ -74,6 +84,86 @@ pub struct CommandIR {
     /// Required roles to access this command.
     /// Only applicable for `Internal` and `Private` access levels.
     pub roles: Vec<String>,
+
+    /// Template deriving the stream id from route parameters
+    /// (e.g. `"order-{orderId}"`), if the command declares one.
+    pub stream_id: Option<String>,
+}
+
+impl CommandIR {
+    /// Whether this command takes file uploads (any `Attachment` parameter).
+    pub fn accepts_uploads(&self) -> bool {
+        self.parameters.iter().any(|p| p.typ.contains_attachment())
+    }
+
+    /// The parsed stream id template. Invalid templates are rejected during
+    /// validation, so codegen treats them as absent.
+    pub fn stream_id_template(&self) -> Option<StreamIdTemplate> {
+        self.stream_id.as_deref().and_then(|t| StreamIdTemplate::parse(t).ok())
+    }
+}
+
+/// A stream id derived from route parameters, e.g. `order-{orderId}`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamIdTemplate {
+    pub parts: Vec<TemplatePart>,
+}
+
+/// A piece of a [`StreamIdTemplate`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplatePart {
+    Literal(String),
+    Param(String),
+}
+
+impl StreamIdTemplate {
+    /// Parses a template. Parameters are `{identifier}`; at least one is required
+    /// and each may appear once.
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let mut parts = Vec::new();
+        let mut rest = template;
+        while let Some(open) = rest.find(['{', '}']) {
+            if rest[open..].starts_with('}') {
+                return Err("unmatched '}'".to_string());
+            }
+            let close = rest[open..]
+                .find('}')
+                .map(|i| open + i)
+                .ok_or_else(|| "unclosed '{'".to_string())?;
+            let name = &rest[open + 1..close];
+            let valid = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if !valid {
+                return Err(format!("'{{{}}}' is not a valid parameter name", name));
+            }
+            if parts.contains(&TemplatePart::Param(name.to_string())) {
+                return Err(format!("parameter '{}' appears more than once", name));
+            }
+            if open > 0 {
+                parts.push(TemplatePart::Literal(rest[..open].to_string()));
+            }
+            parts.push(TemplatePart::Param(name.to_string()));
+            rest = &rest[close + 1..];
+        }
+        if !rest.is_empty() {
+            parts.push(TemplatePart::Literal(rest.to_string()));
+        }
+        if !parts.iter().any(|p| matches!(p, TemplatePart::Param(_))) {
+            return Err("it must contain at least one {parameter}".to_string());
+        }
+        Ok(Self { parts })
+    }
+
+    /// Route parameter names, in order of appearance.
+    pub fn params(&self) -> Vec<&str> {
+        self.parts
+            .iter()
+            .filter_map(|p| match p {
+                TemplatePart::Param(name) => Some(name.as_str()),
+                TemplatePart::Literal(_) => None,
+            })
+            .collect()
+    }
 }
 
 /// IR representation of a statement.
@@ -196,3 +286,21 @@ pub enum UnaryOp {
     Not,     // !
     Neg,     // -
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_stream_id_templates() {
+        let template = StreamIdTemplate::parse("order-{orderId}-line-{lineId}").unwrap();
+        assert_eq!(template.params(), vec!["orderId", "lineId"]);
+        assert_eq!(template.parts[0], TemplatePart::Literal("order-".to_string()));
+        assert_eq!(template.parts[3], TemplatePart::Param("lineId".to_string()));
+
+        assert!(StreamIdTemplate::parse("order").is_err());
+        assert!(StreamIdTemplate::parse("order-{orderId").is_err());
+        assert!(StreamIdTemplate::parse("order-{order-id}").is_err());
+        assert!(StreamIdTemplate::parse("{id}-{id}").is_err());
+    }
+}
@@ -12,7 +12,7 @@ mod projection;
 pub use access::{AccessLevel, AppConfig, AppMode, EntityAccessConfig, MethodAccessConfig};
 pub use aggregate::{
     AggregateIR, CommandIR, EventTypeIR, EventVariant, EventField,
-    StatementIR, ExpressionIR, BinaryOp, UnaryOp,
+    StatementIR, ExpressionIR, BinaryOp, UnaryOp, StreamIdTemplate, TemplatePart,
 };
 pub use orchestrator::{OrchestratorDependency, OrchestratorIR};
 pub use projection::{
@@ -36,6 +36,30 @@ pub enum DomainType {
     Reference(String),
 }
 
+/// Type name that marks a command parameter as a file upload.
+///
+/// Handlers store the uploaded file as a blob and pass the command a reference
+/// of this type, so binary data never reaches an event payload.
+pub const ATTACHMENT_TYPE: &str = "Attachment";
+
+impl DomainType {
+    /// Whether this is an `Attachment` reference (a single uploaded file).
+    pub fn is_attachment(&self) -> bool {
+        matches!(self, DomainType::Reference(name) if name == ATTACHMENT_TYPE)
+    }
+
+    /// Whether this is an attachment, optionally wrapped in arrays or optionals.
+    ///
+    /// Attachments nested inside object fields are not supported; uploads map
+    /// onto top-level multipart fields.
+    pub fn contains_attachment(&self) -> bool {
+        match self {
+            DomainType::Array(inner) | DomainType::Option(inner) => inner.contains_attachment(),
+            other => other.is_attachment(),
+        }
+    }
+}
+
 /// An object type with named fields.
 #[derive(Debug, Clone, PartialEq)]
 pub struct ObjectType {
@@ -121,6 +121,8 @@ pub enum SqlType {
     Real,
     /// BLOB - for binary data
     Blob,
+    /// TEXT holding well-formed JSON - for arrays and objects
+    Json,
 }
 
 impl SqlType {
@@ -130,9 +132,9 @@ impl SqlType {
             DomainType::String => SqlType::Text,
             DomainType::Number => SqlType::Real,
             DomainType::Boolean => SqlType::Integer,
-            DomainType::Array(_) => SqlType::Text,    // JSON serialized
+            DomainType::Array(_) => SqlType::Json,
             DomainType::Option(inner) => Self::from_domain_type(inner),
-            DomainType::Object(_) => SqlType::Text,   // JSON serialized
+            DomainType::Object(_) => SqlType::Json,
             DomainType::Reference(_) => SqlType::Text, // Assume string ID
         }
     }
@@ -144,6 +146,16 @@ impl SqlType {
             SqlType::Integer => "INTEGER",
             SqlType::Real => "REAL",
             SqlType::Blob => "BLOB",
+            SqlType::Json => "TEXT",
+        }
+    }
+
+    /// Column constraint enforcing the type beyond SQLite's affinity, if any
+    /// (with a leading space, ready to append to a column definition).
+    pub fn check_constraint(&self, column: &str) -> String {
+        match self {
+            SqlType::Json => format!(" CHECK (json_valid({}))", column),
+            _ => String::new(),
         }
     }
 }
@@ -7,6 +7,7 @@
 //! ## Supported Languages
 //!
 //! - TypeScript (default)
+//! - Python (aggregates only; `check` but not compile)
 //!
 //! ## Architecture
 //!
@@ -41,19 +42,23 @@
 //! ## Usage
 //!
 //! ```rust,ignore
-//! use spite_compiler::{Compiler, CompilerConfig};
+//! use spite_compiler::{ApiStyle, Compiler, CompilerConfig};
 //!
 //! let config = CompilerConfig {
 //!     domain_dir: "domain".into(),
 //!     out_dir: "src/generated".into(),
 //!     skip_purity_check: false,
 //!     language: "typescript".to_string(),
+//!     api_style: ApiStyle::Rest,
+//!     emit_client: false,
+//!     emit_docker: false,
 //! };
 //!
 //! let compiler = Compiler::new(config);
 //! compiler.compile().await?;
 //! ```
 
+pub mod cache;
 pub mod config;
 pub mod frontend;
 pub mod ir;
@@ -62,9 +67,9 @@ pub mod codegen;
 pub mod diagnostic;
 pub mod schema;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-pub use config::CompilerConfig;
+pub use config::{ApiStyle, CompilerConfig};
 pub use diagnostic::CompilerError;
 pub use codegen::project;
 
@@ -127,22 +132,31 @@ impl Compiler {
         }
 
         // Phase 7: Generate TypeScript code
-        // Compute import path from handlers/ to domain source
-        let domain_import_path = self.compute_domain_import_path()?;
-        let generated = codegen::generate(&domain_ir, &domain_import_path)?;
+        let generated = self.generate_code(frontend.as_ref(), &domain_ir)?;
 
         // Phase 5: Write output
         self.write_output(&generated)?;
 
-        Ok(CompileResult {
-            aggregates: domain_ir.aggregates.len(),
-            orchestrators: domain_ir.orchestrators.len(),
-            events: domain_ir
-                .aggregates
-                .iter()
-                .map(|a| a.events.variants.len())
-                .sum(),
-        })
+        Ok(CompileResult::for_domain(&domain_ir))
+    }
+
+    /// Generates the wiring code, plus TypeScript domain classes for
+    /// frontends whose sources the generated project can't import (Python).
+    fn generate_code(
+        &self,
+        frontend: &dyn frontend::Frontend,
+        domain_ir: &ir::DomainIR,
+    ) -> Result<codegen::GeneratedCode, CompilerError> {
+        if frontend.imports_domain_sources() {
+            // Compute import path from handlers/ to domain source
+            let domain_import_path = self.compute_domain_import_path()?;
+            return codegen::generate(domain_ir, &domain_import_path, self.config.api_style);
+        }
+
+        // Domain classes are generated next to handlers/
+        let mut generated = codegen::generate(domain_ir, "../domain", self.config.api_style)?;
+        generated.files.extend(codegen::generate_domain_sources(domain_ir));
+        Ok(generated)
     }
 
     /// Validates source domain logic without generating code.
@@ -232,7 +246,7 @@ impl Compiler {
                     self.generate_upcasts(&diffs, &locked)?;
 
                     // Update the lock file with new versions
-                    let updated_lock = schema::SchemaLockFile::from_domain_ir(domain, env!("CARGO_PKG_VERSION"));
+                    let updated_lock = locked.next(domain, env!("CARGO_PKG_VERSION"));
                     updated_lock.save(&lock_path)?;
                     eprintln!("   Updated events.lock.json with new schema versions");
                 }
@@ -385,14 +399,20 @@ impl Compiler {
     pub async fn compile_project(&self, project_name: &str, port: u16) -> Result<CompileResult, CompilerError> {
         // First, compile the domain code
         let mut frontend = frontend::create_frontend(&self.config.language)?;
+        let sources = self.hash_domain_sources(frontend.as_ref());
         let domain_ir = frontend.parse_directory(&self.config.domain_dir)?;
 
         if !self.config.skip_purity_check {
             validate::validate_domain(&domain_ir)?;
         }
 
-        let domain_import_path = self.compute_domain_import_path()?;
-        let generated = codegen::generate(&domain_ir, &domain_import_path)?;
+        if self.config.emit_client && self.config.api_style != ApiStyle::Rest {
+            return Err(CompilerError::CodegenFailed {
+                message: "the generated client targets REST routes; use --api-style rest with --emit-client".to_string(),
+            });
+        }
+
+        let generated = self.generate_code(frontend.as_ref(), &domain_ir)?;
 
         // Create project structure
         let project_dir = &self.config.out_dir;
@@ -411,7 +431,7 @@ impl Compiler {
         let napi_path = project::detect_napi_path(project_dir);
 
         // Write package.json
-        let package_json = project::generate_package_json(project_name, napi_path.as_deref());
+        let package_json = project::generate_package_json(project_name, napi_path.as_deref(), self.config.api_style);
         std::fs::write(project_dir.join("package.json"), package_json).map_err(|e| CompilerError::IoError {
             path: project_dir.join("package.json"),
             message: e.to_string(),
@@ -436,6 +456,20 @@ impl Compiler {
             message: e.to_string(),
         })?;
 
+        // Write src/seed.ts
+        let seed_ts = project::generate_seed_ts(project_name);
+        std::fs::write(src_dir.join("seed.ts"), seed_ts).map_err(|e| CompilerError::IoError {
+            path: src_dir.join("seed.ts"),
+            message: e.to_string(),
+        })?;
+
+        // Write src/schema-sample.ts
+        let schema_sample_ts = project::generate_schema_sample_ts(project_name);
+        std::fs::write(src_dir.join("schema-sample.ts"), schema_sample_ts).map_err(|e| CompilerError::IoError {
+            path: src_dir.join("schema-sample.ts"),
+            message: e.to_string(),
+        })?;
+
         // Write .gitignore
         let gitignore = project::generate_gitignore();
         std::fs::write(project_dir.join(".gitignore"), gitignore).map_err(|e| CompilerError::IoError {
@@ -450,6 +484,27 @@ impl Compiler {
             message: e.to_string(),
         })?;
 
+        // Write the typed client package
+        if self.config.emit_client {
+            let client_dir = project_dir.join("client");
+            std::fs::create_dir_all(&client_dir).map_err(|e| CompilerError::IoError {
+                path: client_dir.clone(),
+                message: e.to_string(),
+            })?;
+            for (filename, content) in codegen::client::generate_client(&domain_ir, project_name) {
+                let path = client_dir.join(filename);
+                std::fs::write(&path, content).map_err(|e| CompilerError::IoError {
+                    path,
+                    message: e.to_string(),
+                })?;
+            }
+        }
+
+        // Write the Docker deployment bundle
+        if self.config.emit_docker {
+            self.write_docker(port)?;
+        }
+
         // Write generated domain code
         for (filename, content) in &generated.files {
             let path = generated_dir.join(filename);
@@ -466,64 +521,199 @@ impl Compiler {
             })?;
         }
 
-        Ok(CompileResult {
-            aggregates: domain_ir.aggregates.len(),
-            orchestrators: domain_ir.orchestrators.len(),
-            events: domain_ir
-                .aggregates
-                .iter()
-                .map(|a| a.events.variants.len())
-                .sum(),
-        })
+        // Seed the build cache so the first watch-mode recompile is incremental.
+        // Projection fingerprints carry over, so projections edited since the
+        // last compile are still reported as changed.
+        let mut build_cache = cache::BuildCache {
+            projections: cache::BuildCache::load(&self.config.out_dir).projections,
+            ..Default::default()
+        };
+        if !self.config.skip_purity_check {
+            for aggregate in &domain_ir.aggregates {
+                build_cache.record_aggregate(aggregate);
+            }
+        }
+        let mut result = CompileResult::for_domain(&domain_ir);
+        result.changed_projections = build_cache.record_projections(&domain_ir.projections);
+        self.save_build_cache(build_cache, sources, &result, generated.files.len())?;
+
+        Ok(result)
+    }
+
+    /// Writes the Dockerfile, its ignore file and the production start script.
+    ///
+    /// The build context is the closest directory holding both the project
+    /// and the domain source.
+    fn write_docker(&self, port: u16) -> Result<(), CompilerError> {
+        let project_dir = &self.config.out_dir;
+        let canonical = |path: &Path| {
+            path.canonicalize().map_err(|e| CompilerError::IoError {
+                path: path.to_path_buf(),
+                message: e.to_string(),
+            })
+        };
+        let project_abs = canonical(project_dir)?;
+        let domain_abs = canonical(&self.config.domain_dir)?;
+        let context = project_abs
+            .ancestors()
+            .find(|dir| domain_abs.starts_with(dir))
+            .unwrap_or(Path::new("/"));
+        let relative = |path: &Path| {
+            let rel = path.strip_prefix(context).unwrap_or(path).to_string_lossy().to_string();
+            if rel.is_empty() { ".".to_string() } else { rel }
+        };
+
+        let files = [
+            ("Dockerfile", project::generate_dockerfile(port, &relative(&project_abs), &relative(&domain_abs))),
+            ("Dockerfile.dockerignore", project::generate_dockerignore(&relative(&project_abs))),
+            ("docker-start.sh", project::generate_docker_start_script().to_string()),
+        ];
+        for (filename, content) in files {
+            let path = project_dir.join(filename);
+            std::fs::write(&path, content).map_err(|e| CompilerError::IoError {
+                path,
+                message: e.to_string(),
+            })?;
+        }
+        Ok(())
     }
 
     /// Re-compiles just the generated domain code (for watch mode).
+    ///
+    /// Uses the build cache in `out_dir`: if no domain source changed the
+    /// previous result is returned as is, aggregates whose IR is unchanged are
+    /// not re-validated, and generated files are only rewritten when their
+    /// content differs.
     pub async fn recompile_domain(&self) -> Result<CompileResult, CompilerError> {
         let mut frontend = frontend::create_frontend(&self.config.language)?;
+        let mut build_cache = cache::BuildCache::load(&self.config.out_dir);
+        let sources = self.hash_domain_sources(frontend.as_ref());
+        let settings = self.cache_settings();
+        let generated_dir = self.config.out_dir.join("src").join("generated");
+
+        if let Some(counts) = &build_cache.counts {
+            if build_cache.sources == sources && build_cache.settings == settings && generated_dir.exists() {
+                return Ok(CompileResult {
+                    aggregates: counts.aggregates,
+                    orchestrators: counts.orchestrators,
+                    events: counts.events,
+                    skipped_aggregates: counts.aggregates,
+                    skipped_files: counts.files,
+                    changed_projections: Vec::new(),
+                });
+            }
+        }
+
         let domain_ir = frontend.parse_directory(&self.config.domain_dir)?;
 
+        let mut skipped_aggregates = 0;
         if !self.config.skip_purity_check {
-            validate::validate_domain(&domain_ir)?;
+            if build_cache.settings != settings {
+                build_cache.aggregates.clear();
+            }
+            for aggregate in &domain_ir.aggregates {
+                if build_cache.aggregate_unchanged(aggregate) {
+                    skipped_aggregates += 1;
+                    continue;
+                }
+                validate::validate_aggregate(aggregate)?;
+                build_cache.record_aggregate(aggregate);
+            }
+            build_cache
+                .aggregates
+                .retain(|name, _| domain_ir.aggregates.iter().any(|a| &a.name == name));
         }
 
-        let domain_import_path = self.compute_domain_import_path()?;
-        let generated = codegen::generate(&domain_ir, &domain_import_path)?;
+        let generated = self.generate_code(frontend.as_ref(), &domain_ir)?;
 
-        // Write only the generated wiring code
-        let generated_dir = self.config.out_dir.join("src").join("generated");
-
-        // Create subdirectories (validators, handlers, orchestrators - not events/state/aggregates)
-        for subdir in &["validators", "handlers", "orchestrators"] {
-            std::fs::create_dir_all(generated_dir.join(subdir)).map_err(|e| CompilerError::IoError {
-                path: generated_dir.join(subdir),
-                message: e.to_string(),
-            })?;
+        // Write only the generated wiring code, skipping files that are already up to date
+        let mut skipped_files = 0;
+        for (filename, content) in &generated.files {
+            if !cache::write_if_changed(&generated_dir.join(filename), content)? {
+                skipped_files += 1;
+            }
         }
 
-        for (filename, content) in &generated.files {
-            let path = generated_dir.join(filename);
-            // Ensure parent directory exists
-            if let Some(parent) = path.parent() {
-                std::fs::create_dir_all(parent).map_err(|e| CompilerError::IoError {
-                    path: parent.to_path_buf(),
+        let mut result = CompileResult::for_domain(&domain_ir);
+        result.skipped_aggregates = skipped_aggregates;
+        result.skipped_files = skipped_files;
+        result.changed_projections = build_cache.record_projections(&domain_ir.projections);
+        self.save_build_cache(build_cache, sources, &result, generated.files.len())?;
+
+        Ok(result)
+    }
+
+    /// Deletes the stored read models of the given projections, so their
+    /// workers replay the event log from the start when the server restarts.
+    ///
+    /// Only call this while the server is stopped. Returns the number of
+    /// files removed.
+    pub fn reset_projections(&self, names: &[String]) -> Result<usize, CompilerError> {
+        let data_dir = self.config.out_dir.join("data").join("projections");
+        let Ok(entries) = std::fs::read_dir(&data_dir) else {
+            return Ok(0);
+        };
+
+        // Workers store `{snake_name}_{tenant}.db`; a file belongs to the
+        // projection with the longest matching prefix (`todo_` vs `todo_stats_`)
+        let known = cache::BuildCache::load(&self.config.out_dir).projections;
+        let prefixes: Vec<(String, bool)> = known
+            .keys()
+            .chain(names)
+            .map(|name| (codegen::projection_data_prefix(name), names.contains(name)))
+            .collect();
+
+        let mut removed = 0;
+        for entry in entries.filter_map(|e| e.ok()) {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if !file_name.contains(".db") {
+                continue;
+            }
+            let owner = prefixes
+                .iter()
+                .filter(|(prefix, _)| file_name.starts_with(prefix.as_str()))
+                .max_by_key(|(prefix, _)| prefix.len());
+            if let Some((_, true)) = owner {
+                std::fs::remove_file(entry.path()).map_err(|e| CompilerError::IoError {
+                    path: entry.path(),
                     message: e.to_string(),
                 })?;
+                removed += 1;
             }
-            std::fs::write(&path, content).map_err(|e| CompilerError::IoError {
-                path,
-                message: e.to_string(),
-            })?;
         }
+        Ok(removed)
+    }
 
-        Ok(CompileResult {
-            aggregates: domain_ir.aggregates.len(),
-            orchestrators: domain_ir.orchestrators.len(),
-            events: domain_ir
-                .aggregates
-                .iter()
-                .map(|a| a.events.variants.len())
-                .sum(),
-        })
+    /// Stores the hashes and counts of a successful compilation.
+    fn save_build_cache(
+        &self,
+        mut build_cache: cache::BuildCache,
+        sources: std::collections::BTreeMap<String, String>,
+        result: &CompileResult,
+        files: usize,
+    ) -> Result<(), CompilerError> {
+        build_cache.sources = sources;
+        build_cache.settings = self.cache_settings();
+        build_cache.counts = Some(cache::CachedCounts {
+            aggregates: result.aggregates,
+            orchestrators: result.orchestrators,
+            events: result.events,
+            files,
+        });
+        build_cache.save(&self.config.out_dir)
+    }
+
+    /// Hashes the domain sources the frontend would parse.
+    fn hash_domain_sources(&self, frontend: &dyn frontend::Frontend) -> std::collections::BTreeMap<String, String> {
+        cache::hash_sources(&self.config.domain_dir, frontend.extensions())
+    }
+
+    /// Config that affects generated output; a change invalidates the cache.
+    fn cache_settings(&self) -> String {
+        format!(
+            "{}:{:?}:{}",
+            self.config.language, self.config.api_style, self.config.skip_purity_check
+        )
     }
 }
 
@@ -536,4 +726,189 @@ pub struct CompileResult {
     pub orchestrators: usize,
     /// Total number of event variants across all aggregates.
     pub events: usize,
+    /// Aggregates whose validation was skipped because their IR was unchanged.
+    pub skipped_aggregates: usize,
+    /// Generated files that were already up to date and not rewritten.
+    pub skipped_files: usize,
+    /// Projections whose stored shape or build logic changed since the last
+    /// compile; their read models are stale.
+    pub changed_projections: Vec<String>,
+}
+
+impl CompileResult {
+    fn for_domain(domain_ir: &ir::DomainIR) -> Self {
+        Self {
+            aggregates: domain_ir.aggregates.len(),
+            orchestrators: domain_ir.orchestrators.len(),
+            events: domain_ir
+                .aggregates
+                .iter()
+                .map(|a| a.events.variants.len())
+                .sum(),
+            skipped_aggregates: 0,
+            skipped_files: 0,
+            changed_projections: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn python_domains_get_generated_domain_classes() {
+        let domain = TempDir::new().unwrap();
+        std::fs::write(
+            domain.path().join("todo.py"),
+            r#"
+from typing import Literal, TypedDict
+
+
+class TodoState(TypedDict):
+    title: str
+
+
+class TodoCreated(TypedDict):
+    type: Literal["Created"]
+    title: str
+
+
+TodoEvent = TodoCreated
+
+
+class TodoAggregate:
+    initial_state: TodoState = {"title": ""}
+
+    def __init__(self) -> None:
+        self.events: list[TodoEvent] = []
+
+    def emit(self, event: TodoEvent) -> None:
+        self.apply(event)
+
+    def apply(self, event: TodoEvent) -> None:
+        pass
+
+    def create(self, title: str) -> None:
+        self.emit({"type": "Created", "title": title})
+"#,
+        )
+        .unwrap();
+
+        let compiler = Compiler::new(CompilerConfig {
+            domain_dir: domain.path().to_path_buf(),
+            language: "python".to_string(),
+            ..Default::default()
+        });
+        let mut python = frontend::create_frontend("python").unwrap();
+        let domain_ir = python.parse_directory(domain.path()).unwrap();
+        let generated = compiler.generate_code(python.as_ref(), &domain_ir).unwrap();
+
+        let file = |name: &str| {
+            generated
+                .files
+                .iter()
+                .find(|(f, _)| f == name)
+                .map(|(_, content)| content.as_str())
+                .unwrap_or_else(|| panic!("missing {}", name))
+        };
+        assert!(file("domain/Todo/aggregate.ts").contains("export class TodoAggregate"));
+        assert!(file("domain/Todo/state.ts").contains("export const initialTodoState"));
+        assert!(file("handlers/todo.handlers.ts").contains("from '../domain/Todo/aggregate'"));
+        assert!(file("index.ts").contains("export * from './domain/Todo/events'"));
+    }
+
+    #[test]
+    fn reset_projections_only_removes_their_own_files() {
+        let out = TempDir::new().unwrap();
+        let data_dir = out.path().join("data").join("projections");
+        std::fs::create_dir_all(&data_dir).unwrap();
+        for file in ["todo_acme.db", "todo_acme.db-wal", "todo_stats_acme.db", "other_acme.db"] {
+            std::fs::write(data_dir.join(file), "").unwrap();
+        }
+        let mut cache = cache::BuildCache::default();
+        cache.projections.insert("Todo".to_string(), String::new());
+        cache.projections.insert("TodoStats".to_string(), String::new());
+        cache.save(out.path()).unwrap();
+
+        let compiler = Compiler::new(CompilerConfig {
+            out_dir: out.path().to_path_buf(),
+            ..Default::default()
+        });
+        assert_eq!(compiler.reset_projections(&["Todo".to_string()]).unwrap(), 2);
+        assert!(!data_dir.join("todo_acme.db").exists());
+        assert!(data_dir.join("todo_stats_acme.db").exists());
+        assert!(data_dir.join("other_acme.db").exists());
+    }
+
+    #[test]
+    fn docker_bundle_uses_common_context() {
+        let root = tempfile::TempDir::new().unwrap();
+        let domain_dir = root.path().join("src").join("domain");
+        let out_dir = root.path().join("build").join("app");
+        std::fs::create_dir_all(&domain_dir).unwrap();
+        std::fs::create_dir_all(&out_dir).unwrap();
+
+        let compiler = Compiler::new(CompilerConfig {
+            domain_dir,
+            out_dir: out_dir.clone(),
+            emit_docker: true,
+            ..Default::default()
+        });
+        compiler.write_docker(3000).unwrap();
+
+        let dockerfile = std::fs::read_to_string(out_dir.join("Dockerfile")).unwrap();
+        assert!(dockerfile.contains("docker build -f build/app/Dockerfile ."));
+        assert!(dockerfile.contains("WORKDIR /app/build/app"));
+        assert!(dockerfile.contains("COPY src/domain /app/src/domain"));
+        assert!(dockerfile.contains("VOLUME /data"));
+        assert!(dockerfile.contains("http://localhost:3000/healthz"));
+        let ignore = std::fs::read_to_string(out_dir.join("Dockerfile.dockerignore")).unwrap();
+        assert!(ignore.contains("build/app/data"));
+        let start = std::fs::read_to_string(out_dir.join("docker-start.sh")).unwrap();
+        assert!(start.contains("exec bun run src/index.ts"));
+    }
+
+    #[test]
+    fn schema_sample_pages_the_global_log() {
+        let script = codegen::project::generate_schema_sample_ts("app");
+        assert!(script.contains("import { readGlobalFrom, TenantResolver } from './generated/runtime/event-log';"));
+        assert!(script.contains("for await (const events of readGlobalFrom(db, 0)) {"));
+        assert!(!script.contains("readGlobalEvents"));
+    }
+
+    #[test]
+    fn static_unique_marks_projection_columns_unique() {
+        let domain = tempfile::TempDir::new().unwrap();
+        let source = |unique: &str| {
+            format!(
+                r#"export class UserDirectory {{
+  static unique = [{}];
+  users: {{ [userId: string]: {{ email: string; name: string }} }} = {{}};
+
+  build(event: UserEvent): void {{}}
+
+  getByEmail(email: string) {{
+    return null;
+  }}
+}}
+"#,
+                unique
+            )
+        };
+        let parse = |unique: &str| {
+            std::fs::write(domain.path().join("projection.ts"), source(unique)).unwrap();
+            frontend::create_frontend("typescript").unwrap().parse_directory(domain.path())
+        };
+
+        let ir = parse("'email'").unwrap();
+        let indexes = &ir.projections[0].schema.indexes;
+        assert_eq!(indexes.len(), 1);
+        assert_eq!(indexes[0].columns, vec!["email".to_string()]);
+        assert!(indexes[0].unique);
+
+        let err = parse("'phone'").unwrap_err().to_string();
+        assert!(err.contains("unique field 'phone' is not a field of the state"), "{}", err);
+    }
 }
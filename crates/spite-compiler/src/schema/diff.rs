@@ -250,6 +250,7 @@ pub fn diff_schemas(
                             } else {
                                 None
                             },
+                            since: None,
                         },
                     )
                 })
@@ -284,6 +285,7 @@ pub fn diff_schemas(
                             typ: "event".to_string(),
                             required: true,
                             default: None,
+                            since: None,
                         },
                     }],
                 });
@@ -307,6 +309,7 @@ mod tests {
             } else {
                 Some(serde_json::Value::Null)
             },
+            since: None,
         }
     }
 
@@ -0,0 +1,416 @@
+//! Event payload schema export.
+//!
+//! Turns the lock file into artifacts that external consumers (CDC feeds,
+//! webhooks) can use to validate and type the events they receive:
+//!
+//! - **JSON Schema** (draft 2020-12): one `$defs` entry per event version
+//! - **TypeBox**: a TypeScript module with one `Type.Object` per event version
+//!
+//! The lock file keeps the latest shape of each event and the version that
+//! added each field, so every earlier version's shape is recovered by leaving
+//! out the fields added after it. Because versions only ever add optional
+//! fields, a payload usually matches several versions.
+
+use std::collections::BTreeMap;
+
+use serde_json::{json, Map, Value};
+
+use super::lock::{EventSchema, SchemaLockFile};
+
+/// JSON Schema dialect used for exports.
+pub const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Output format for `export_schemas`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    JsonSchema,
+    TypeBox,
+}
+
+impl ExportFormat {
+    /// Parse an export format from a string.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "json-schema" | "jsonschema" | "json" => Some(ExportFormat::JsonSchema),
+            "typebox" => Some(ExportFormat::TypeBox),
+            _ => None,
+        }
+    }
+
+    /// Conventional file name for this format.
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            ExportFormat::JsonSchema => "events.schema.json",
+            ExportFormat::TypeBox => "events.schema.ts",
+        }
+    }
+}
+
+/// Export every event in the lock file in the given format.
+pub fn export_schemas(lock: &SchemaLockFile, format: ExportFormat) -> String {
+    match format {
+        ExportFormat::JsonSchema => {
+            // Serializing a Value cannot fail
+            serde_json::to_string_pretty(&export_json_schema(lock)).unwrap_or_default() + "\n"
+        }
+        ExportFormat::TypeBox => export_typebox(lock),
+    }
+}
+
+/// Build a JSON Schema document covering every event version in the lock file.
+///
+/// Each version lives under `$defs` as `Aggregate.Event.vN`; the root is an
+/// `anyOf` over all of them so the document can validate any event directly
+/// (a payload without the later optional fields matches several versions).
+pub fn export_json_schema(lock: &SchemaLockFile) -> Value {
+    let mut defs = Map::new();
+    let mut refs = Vec::new();
+
+    for (aggregate, event, schema) in sorted_events(lock) {
+        for version in 1..=schema.version {
+            let key = format!("{}.{}.v{}", aggregate, event, version);
+            refs.push(json!({ "$ref": format!("#/$defs/{}", key) }));
+            defs.insert(key, event_json_schema(aggregate, event, schema, version));
+        }
+    }
+
+    json!({
+        "$schema": JSON_SCHEMA_DIALECT,
+        "title": "SpiteStack event payloads",
+        "x-spite-lock-version": lock.version,
+        "$defs": defs,
+        "anyOf": refs,
+    })
+}
+
+/// Build the JSON Schema for one version of an event.
+pub fn event_json_schema(aggregate: &str, event: &str, schema: &EventSchema, version: u32) -> Value {
+    let mut properties = Map::new();
+    let mut required = vec![Value::String("type".to_string())];
+    properties.insert("type".to_string(), json!({ "const": event }));
+
+    let fields: BTreeMap<_, _> = schema.fields_at(version).collect();
+    for (name, field) in fields {
+        let typ = parse_lock_type(&field.typ).to_json_schema();
+        if field.required {
+            required.push(Value::String(name.clone()));
+            properties.insert(name.clone(), typ);
+        } else {
+            // Upcasts fill missing optional fields with null
+            properties.insert(name.clone(), json!({ "anyOf": [typ, { "type": "null" }] }));
+        }
+    }
+
+    json!({
+        "title": format!("{}.{} v{}", aggregate, event, version),
+        "type": "object",
+        "x-spite-aggregate": aggregate,
+        "x-spite-event": event,
+        "x-spite-version": version,
+        "properties": properties,
+        "required": required,
+    })
+}
+
+/// Build a TypeBox module covering every event version in the lock file.
+///
+/// Each version is exported as `AggregateEventVn`, and `AggregateEvent` names
+/// the latest one.
+pub fn export_typebox(lock: &SchemaLockFile) -> String {
+    let mut code = String::new();
+    code.push_str(
+        r#"/**
+ * Event payload schemas exported from events.lock.json.
+ * DO NOT EDIT - regenerate with `spitestack schema export --format typebox`
+ */
+
+import { Type, type Static } from '@sinclair/typebox';
+"#,
+    );
+
+    let mut by_aggregate: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+
+    for (aggregate, event, schema) in sorted_events(lock) {
+        let const_name = format!("{}{}", aggregate, event);
+
+        for version in 1..=schema.version {
+            let mut props = vec![format!("type: Type.Literal('{}')", event)];
+
+            let fields: BTreeMap<_, _> = schema.fields_at(version).collect();
+            for (name, field) in fields {
+                let typ = parse_lock_type(&field.typ).to_typebox();
+                if field.required {
+                    props.push(format!("{}: {}", name, typ));
+                } else {
+                    props.push(format!(
+                        "{}: Type.Optional(Type.Union([{}, Type.Null()]))",
+                        name, typ
+                    ));
+                }
+            }
+
+            code.push_str(&format!(
+                "\nexport const {const_name}V{version} = Type.Object({{\n  {}\n}}, {{ $id: '{aggregate}.{event}.v{version}', 'x-spite-version': {version} }});\n",
+                props.join(",\n  ")
+            ));
+            code.push_str(&format!(
+                "export type {const_name}V{version} = Static<typeof {const_name}V{version}>;\n"
+            ));
+        }
+
+        code.push_str(&format!(
+            "export const {const_name} = {const_name}V{};\nexport type {const_name} = {const_name}V{};\n",
+            schema.version, schema.version
+        ));
+
+        by_aggregate.entry(aggregate).or_default().push(const_name);
+    }
+
+    for (aggregate, members) in by_aggregate {
+        code.push_str(&format!(
+            "\nexport const {aggregate}Event = Type.Union([{}]);\n",
+            members.join(", ")
+        ));
+        code.push_str(&format!(
+            "export type {aggregate}Event = Static<typeof {aggregate}Event>;\n"
+        ));
+    }
+
+    code
+}
+
+/// Events in a stable (aggregate, event) order.
+fn sorted_events(lock: &SchemaLockFile) -> Vec<(&str, &str, &EventSchema)> {
+    let mut events: Vec<_> = lock
+        .aggregates
+        .iter()
+        .flat_map(|(aggregate, lock)| {
+            lock.events
+                .iter()
+                .map(move |(event, schema)| (aggregate.as_str(), event.as_str(), schema))
+        })
+        .collect();
+    events.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+    events
+}
+
+/// A field type as recorded in the lock file.
+#[derive(Debug, Clone, PartialEq)]
+enum LockType {
+    String,
+    Number,
+    Boolean,
+    Array(Box<LockType>),
+    Object(Vec<(String, bool, LockType)>),
+    Reference(String),
+}
+
+impl LockType {
+    fn to_json_schema(&self) -> Value {
+        match self {
+            LockType::String => json!({ "type": "string" }),
+            LockType::Number => json!({ "type": "number" }),
+            LockType::Boolean => json!({ "type": "boolean" }),
+            LockType::Array(inner) => json!({ "type": "array", "items": inner.to_json_schema() }),
+            LockType::Object(fields) => {
+                let mut properties = Map::new();
+                let mut required = Vec::new();
+                for (name, optional, typ) in fields {
+                    properties.insert(name.clone(), typ.to_json_schema());
+                    if !optional {
+                        required.push(Value::String(name.clone()));
+                    }
+                }
+                json!({ "type": "object", "properties": properties, "required": required })
+            }
+            // Named types are not resolved in the lock file; accept any value
+            LockType::Reference(name) => json!({ "$comment": format!("reference to {}", name) }),
+        }
+    }
+
+    fn to_typebox(&self) -> String {
+        match self {
+            LockType::String => "Type.String()".to_string(),
+            LockType::Number => "Type.Number()".to_string(),
+            LockType::Boolean => "Type.Boolean()".to_string(),
+            LockType::Array(inner) => format!("Type.Array({})", inner.to_typebox()),
+            LockType::Object(fields) => {
+                let props: Vec<String> = fields
+                    .iter()
+                    .map(|(name, optional, typ)| {
+                        if *optional {
+                            format!("{}: Type.Optional({})", name, typ.to_typebox())
+                        } else {
+                            format!("{}: {}", name, typ.to_typebox())
+                        }
+                    })
+                    .collect();
+                format!("Type.Object({{ {} }})", props.join(", "))
+            }
+            LockType::Reference(_) => "Type.Unknown()".to_string(),
+        }
+    }
+}
+
+/// Parse the TypeScript type string stored in the lock file.
+///
+/// Mirrors `domain_type_to_string`: primitives, `T[]`, `T | undefined`,
+/// inline objects `{ a: T, b?: U }` and bare type names.
+fn parse_lock_type(s: &str) -> LockType {
+    let s = s.trim();
+    let s = s.strip_suffix(" | undefined").unwrap_or(s).trim();
+
+    if let Some(inner) = s.strip_suffix("[]") {
+        return LockType::Array(Box::new(parse_lock_type(inner)));
+    }
+
+    if let Some(body) = s.strip_prefix('{').and_then(|b| b.strip_suffix('}')) {
+        let fields = split_top_level(body)
+            .into_iter()
+            .filter_map(|part| {
+                let (name, typ) = part.split_once(':')?;
+                let name = name.trim();
+                let (name, optional) = match name.strip_suffix('?') {
+                    Some(n) => (n, true),
+                    None => (name, false),
+                };
+                Some((name.to_string(), optional, parse_lock_type(typ)))
+            })
+            .collect();
+        return LockType::Object(fields);
+    }
+
+    match s {
+        "string" => LockType::String,
+        "number" => LockType::Number,
+        "boolean" => LockType::Boolean,
+        other => LockType::Reference(other.to_string()),
+    }
+}
+
+/// Split on commas that are not nested inside braces.
+fn split_top_level(s: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+
+    for (i, c) in s.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+
+    parts.into_iter().filter(|p| !p.trim().is_empty()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::schema::{AggregateLock, FieldSchema};
+
+    fn make_lock() -> SchemaLockFile {
+        let mut fields = HashMap::new();
+        fields.insert(
+            "id".to_string(),
+            FieldSchema { typ: "string".to_string(), required: true, default: None, since: None },
+        );
+        fields.insert(
+            "tags".to_string(),
+            FieldSchema {
+                typ: "{ name: string, weight?: number }[]".to_string(),
+                required: false,
+                default: Some(Value::Null),
+                since: Some(2),
+            },
+        );
+
+        let mut events = HashMap::new();
+        events.insert(
+            "Created".to_string(),
+            EventSchema {
+                version: 2,
+                previous_version: Some(1),
+                fields,
+                upcast_from: HashMap::new(),
+                hash: "test".to_string(),
+            },
+        );
+
+        let mut aggregates = HashMap::new();
+        aggregates.insert("Todo".to_string(), AggregateLock { events });
+
+        SchemaLockFile {
+            version: "1.0".to_string(),
+            generated_at: "0Z".to_string(),
+            compiler_version: "0.1.0".to_string(),
+            aggregates,
+        }
+    }
+
+    #[test]
+    fn test_parse_lock_type() {
+        assert_eq!(parse_lock_type("number | undefined"), LockType::Number);
+        assert_eq!(
+            parse_lock_type("string[][]"),
+            LockType::Array(Box::new(LockType::Array(Box::new(LockType::String))))
+        );
+        assert_eq!(
+            parse_lock_type("{ a: { b: boolean, c: string }, d?: Foo }"),
+            LockType::Object(vec![
+                (
+                    "a".to_string(),
+                    false,
+                    LockType::Object(vec![
+                        ("b".to_string(), false, LockType::Boolean),
+                        ("c".to_string(), false, LockType::String),
+                    ])
+                ),
+                ("d".to_string(), true, LockType::Reference("Foo".to_string())),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_export_json_schema() {
+        let doc = export_json_schema(&make_lock());
+        let v1 = &doc["$defs"]["Todo.Created.v1"];
+        let v2 = &doc["$defs"]["Todo.Created.v2"];
+
+        assert_eq!(doc["$schema"], JSON_SCHEMA_DIALECT);
+        assert_eq!(
+            doc["anyOf"],
+            json!([{ "$ref": "#/$defs/Todo.Created.v1" }, { "$ref": "#/$defs/Todo.Created.v2" }])
+        );
+        assert!(doc.get("oneOf").is_none());
+        assert_eq!(v2["x-spite-version"], 2);
+        assert_eq!(v2["properties"]["type"]["const"], "Created");
+        assert_eq!(v2["required"], json!(["type", "id"]));
+        assert_eq!(
+            v2["properties"]["tags"]["anyOf"][0]["items"]["required"],
+            json!(["name"])
+        );
+        // `tags` was added in version 2
+        assert_eq!(v1["x-spite-version"], 1);
+        assert!(v1["properties"].get("tags").is_none());
+        assert!(v1["properties"].get("id").is_some());
+    }
+
+    #[test]
+    fn test_export_typebox() {
+        let code = export_typebox(&make_lock());
+
+        assert!(code.contains("export const TodoCreatedV1 = Type.Object({\n  type: Type.Literal('Created'),\n  id: Type.String()\n}, { $id: 'Todo.Created.v1', 'x-spite-version': 1 });"));
+        assert!(code.contains("export const TodoCreatedV2 = Type.Object({"));
+        assert!(code.contains("tags: Type.Optional(Type.Union([Type.Array(Type.Object({ name: Type.String(), weight: Type.Optional(Type.Number()) })), Type.Null()]))"));
+        assert!(code.contains("export const TodoCreated = TodoCreatedV2;"));
+        assert!(code.contains("export const TodoEvent = Type.Union([TodoCreated]);"));
+    }
+}
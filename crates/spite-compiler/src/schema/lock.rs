@@ -74,6 +74,10 @@ pub struct FieldSchema {
     /// Default value for optional fields (JSON representation).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub default: Option<serde_json::Value>,
+
+    /// Event version that added the field; `None` for version 1.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub since: Option<u32>,
 }
 
 impl SchemaLockFile {
@@ -139,6 +143,32 @@ impl SchemaLockFile {
             aggregates,
         }
     }
+
+    /// Generate the lock file for `domain` as the successor of this one.
+    ///
+    /// Unchanged events keep their locked schema; events whose fields changed
+    /// move to the next version, with added fields recorded as introduced in it.
+    pub fn next(&self, domain: &DomainIR, compiler_version: &str) -> Self {
+        let mut next = Self::from_domain_ir(domain, compiler_version);
+
+        for (aggregate, lock) in next.aggregates.iter_mut() {
+            let Some(previous) = self.aggregates.get(aggregate) else {
+                continue;
+            };
+            for (event, schema) in lock.events.iter_mut() {
+                let Some(locked) = previous.events.get(event) else {
+                    continue;
+                };
+                *schema = if compute_hash(event, &locked.fields) == schema.hash {
+                    locked.clone()
+                } else {
+                    locked.successor(schema)
+                };
+            }
+        }
+
+        next
+    }
 }
 
 impl AggregateLock {
@@ -179,7 +209,9 @@ impl EventSchema {
     /// Create a new version from an existing schema with added fields.
     pub fn new_version(&self, added_fields: HashMap<String, FieldSchema>) -> Self {
         let mut fields = self.fields.clone();
-        fields.extend(added_fields);
+        fields.extend(added_fields.into_iter().map(|(name, field)| {
+            (name, FieldSchema { since: Some(self.version + 1), ..field })
+        }));
 
         let hash = compute_hash_from_fields(&fields);
 
@@ -194,6 +226,40 @@ impl EventSchema {
             hash,
         }
     }
+
+    /// The next version of this schema, with the fields of `current`.
+    fn successor(&self, current: &EventSchema) -> Self {
+        let version = self.version + 1;
+        let fields = current
+            .fields
+            .iter()
+            .map(|(name, field)| {
+                let since = match self.fields.get(name) {
+                    Some(locked) => locked.since,
+                    None => Some(version),
+                };
+                (name.clone(), FieldSchema { since, ..field.clone() })
+            })
+            .collect();
+
+        let mut upcast_from = self.upcast_from.clone();
+        upcast_from.insert(self.version, "auto".to_string());
+
+        Self {
+            version,
+            previous_version: Some(self.version),
+            fields,
+            upcast_from,
+            hash: current.hash.clone(),
+        }
+    }
+
+    /// The fields the event had at `version`.
+    pub fn fields_at(&self, version: u32) -> impl Iterator<Item = (&String, &FieldSchema)> {
+        self.fields
+            .iter()
+            .filter(move |(_, field)| field.since.unwrap_or(1) <= version)
+    }
 }
 
 impl FieldSchema {
@@ -215,6 +281,7 @@ impl FieldSchema {
             typ,
             required: !is_optional,
             default,
+            since: None,
         }
     }
 }
@@ -306,6 +373,7 @@ mod tests {
                             typ: "string".to_string(),
                             required: true,
                             default: None,
+                            since: None,
                         },
                     );
                     fields
@@ -342,6 +410,44 @@ mod tests {
         assert!(loaded.aggregates["Todo"].events.contains_key("Created"));
     }
 
+    fn todo_domain(fields: Vec<EventField>) -> DomainIR {
+        let mut domain = DomainIR::new(std::path::PathBuf::new());
+        domain.aggregates.push(AggregateIR {
+            name: "Todo".to_string(),
+            source_path: std::path::PathBuf::new(),
+            span: None,
+            state: crate::ir::ObjectType { fields: vec![] },
+            initial_state: vec![],
+            events: crate::ir::EventTypeIR {
+                name: "TodoEvent".to_string(),
+                variants: vec![crate::ir::EventVariant { name: "Created".to_string(), span: None, fields }],
+            },
+            commands: vec![],
+            raw_apply_body: None,
+        });
+        domain
+    }
+
+    #[test]
+    fn test_next_versions_changed_events() {
+        let id = EventField { name: "id".to_string(), typ: DomainType::String };
+        let note = EventField { name: "note".to_string(), typ: DomainType::Option(Box::new(DomainType::String)) };
+
+        let v1 = SchemaLockFile::from_domain_ir(&todo_domain(vec![id.clone()]), "0.1.0");
+        let v2 = v1.next(&todo_domain(vec![id.clone(), note.clone()]), "0.1.0");
+        let created = &v2.aggregates["Todo"].events["Created"];
+
+        assert_eq!((created.version, created.previous_version), (2, Some(1)));
+        assert_eq!(created.fields["id"].since, None);
+        assert_eq!(created.fields["note"].since, Some(2));
+        assert_eq!(created.upcast_from.get(&1).map(String::as_str), Some("auto"));
+        assert_eq!(created.fields_at(1).map(|(name, _)| name.as_str()).collect::<Vec<_>>(), vec!["id"]);
+
+        // Unchanged events keep their version
+        let again = v2.next(&todo_domain(vec![id, note]), "0.1.0");
+        assert_eq!(again.aggregates["Todo"].events["Created"].version, 2);
+    }
+
     #[test]
     fn test_load_nonexistent() {
         let dir = TempDir::new().unwrap();
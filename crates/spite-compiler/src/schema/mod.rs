@@ -7,11 +7,14 @@
 //! 2. Detecting changes between code and lock file
 //! 3. Auto-generating upcasts for non-breaking changes
 //! 4. Rejecting breaking changes with helpful errors
+//! 5. Exporting event payload schemas for external consumers
 
 pub mod lock;
 pub mod diff;
 pub mod upcast;
+pub mod export;
 
 pub use lock::{SchemaLockFile, AggregateLock, EventSchema, FieldSchema, domain_type_to_string_pub};
 pub use diff::{SchemaDiff, FieldChange, ChangeType, diff_schemas};
 pub use upcast::{UpcastGenerator, UpcastStrategy};
+pub use export::{ExportFormat, export_schemas};
@@ -254,6 +254,7 @@ mod tests {
             } else {
                 Some(serde_json::Value::Null)
             },
+            since: None,
         }
     }
 
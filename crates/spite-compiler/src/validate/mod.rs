@@ -4,7 +4,7 @@ mod purity;
 mod structure;
 
 use crate::diagnostic::CompilerError;
-use crate::ir::DomainIR;
+use crate::ir::{AggregateIR, DomainIR};
 
 /// Validates the entire domain.
 pub fn validate_domain(domain: &DomainIR) -> Result<(), CompilerError> {
@@ -18,3 +18,11 @@ pub fn validate_domain(domain: &DomainIR) -> Result<(), CompilerError> {
 
     Ok(())
 }
+
+/// Validates a single aggregate's structure and purity.
+///
+/// Used by incremental recompilation to check only aggregates that changed.
+pub fn validate_aggregate(aggregate: &AggregateIR) -> Result<(), CompilerError> {
+    structure::validate_aggregate_structure(aggregate)?;
+    purity::validate_aggregate_purity(aggregate)
+}
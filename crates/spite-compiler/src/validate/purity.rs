@@ -41,7 +41,9 @@ const FORBIDDEN_CALLS: &[&str] = &[
 pub fn validate_aggregate_purity(aggregate: &AggregateIR) -> Result<(), CompilerError> {
     for command in &aggregate.commands {
         for stmt in &command.body {
-            validate_statement(stmt, &aggregate.source_path)?;
+            validate_statement(stmt, &aggregate.source_path).map_err(|e| {
+                e.at_opt(command.span.as_ref(), format!("in command `{}`", command.name))
+            })?;
         }
     }
     Ok(())
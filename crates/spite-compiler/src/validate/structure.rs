@@ -4,7 +4,7 @@
 //! that types are correctly structured.
 
 use crate::diagnostic::CompilerError;
-use crate::ir::{AggregateIR, DomainIR};
+use crate::ir::{AggregateIR, DomainIR, StreamIdTemplate};
 
 /// Validates the structure of the domain IR.
 pub fn validate_structure(domain: &DomainIR) -> Result<(), CompilerError> {
@@ -15,7 +15,12 @@ pub fn validate_structure(domain: &DomainIR) -> Result<(), CompilerError> {
 }
 
 /// Validates an aggregate has all required components.
-fn validate_aggregate_structure(aggregate: &AggregateIR) -> Result<(), CompilerError> {
+pub(super) fn validate_aggregate_structure(aggregate: &AggregateIR) -> Result<(), CompilerError> {
+    check_aggregate_structure(aggregate)
+        .map_err(|e| e.at_opt(aggregate.span.as_ref(), format!("in aggregate `{}`", aggregate.name)))
+}
+
+fn check_aggregate_structure(aggregate: &AggregateIR) -> Result<(), CompilerError> {
     // Check that state has at least one field
     if aggregate.state.fields.is_empty() {
         return Err(CompilerError::InvalidStateType {
@@ -39,5 +44,19 @@ fn validate_aggregate_structure(aggregate: &AggregateIR) -> Result<(), CompilerE
         }
     }
 
+    // Check that stream id templates parse
+    for cmd in &aggregate.commands {
+        if let Some(template) = &cmd.stream_id {
+            StreamIdTemplate::parse(template).map_err(|reason| {
+                CompilerError::InvalidStreamId {
+                    aggregate: aggregate.name.clone(),
+                    command: cmd.name.clone(),
+                    reason,
+                }
+                .at_opt(cmd.span.as_ref(), "stream id applies to this command")
+            })?;
+        }
+    }
+
     Ok(())
 }
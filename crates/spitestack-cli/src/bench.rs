@@ -0,0 +1,131 @@
+//! `spitestack db bench` - load generator results.
+//!
+//! The store is the native `SpiteDbNapi` engine, loaded the only way it can
+//! be: through `@spitestack/db` in Bun. For the length of a run the CLI drops
+//! a script into the generated project's `src/` that appends straight to a
+//! throwaway store. No HTTP server or handler is involved. The script prints
+//! one line per writer; this module merges them into totals and latency
+//! percentiles.
+
+use serde_json::Value;
+
+/// One writer's results, as printed by the bench script.
+#[derive(Debug, Clone, Default)]
+pub struct WriterResult {
+    pub appended: u64,
+    pub failed: u64,
+    /// Scheduled appends skipped because the writer fell behind.
+    pub dropped: u64,
+    pub latencies_us: Vec<u64>,
+}
+
+/// Totals across all writers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchSummary {
+    pub appended: u64,
+    pub failed: u64,
+    pub dropped: u64,
+    /// Appended events per second over the run.
+    pub throughput: f64,
+    /// (label, microseconds) for p50, p90, p99, p99.9 and max.
+    pub percentiles: Vec<(&'static str, u64)>,
+}
+
+/// Parse the bench script's output (one JSON object per line).
+pub fn parse_results(text: &str) -> Result<Vec<WriterResult>, String> {
+    let mut results = Vec::new();
+
+    for (i, raw) in text.lines().enumerate() {
+        let raw = raw.trim();
+        if raw.is_empty() {
+            continue;
+        }
+
+        let value: Value =
+            serde_json::from_str(raw).map_err(|e| format!("Line {}: invalid JSON ({})", i + 1, e))?;
+        let count = |name: &str| value.get(name).and_then(Value::as_u64).unwrap_or(0);
+
+        results.push(WriterResult {
+            appended: count("appended"),
+            failed: count("failed"),
+            dropped: count("dropped"),
+            latencies_us: match value.get("latenciesUs") {
+                Some(Value::Array(items)) => items.iter().filter_map(Value::as_u64).collect(),
+                _ => return Err(format!("Line {}: \"latenciesUs\" must be an array", i + 1)),
+            },
+        });
+    }
+
+    Ok(results)
+}
+
+/// Merge writer results from a run of `duration_secs`.
+pub fn summarize(results: &[WriterResult], duration_secs: u64) -> BenchSummary {
+    let mut latencies: Vec<u64> = results.iter().flat_map(|r| r.latencies_us.iter().copied()).collect();
+    latencies.sort_unstable();
+
+    let appended = results.iter().map(|r| r.appended).sum();
+    BenchSummary {
+        appended,
+        failed: results.iter().map(|r| r.failed).sum(),
+        dropped: results.iter().map(|r| r.dropped).sum(),
+        throughput: appended as f64 / duration_secs.max(1) as f64,
+        percentiles: vec![
+            ("p50", percentile(&latencies, 0.50)),
+            ("p90", percentile(&latencies, 0.90)),
+            ("p99", percentile(&latencies, 0.99)),
+            ("p99.9", percentile(&latencies, 0.999)),
+            ("max", latencies.last().copied().unwrap_or(0)),
+        ],
+    }
+}
+
+/// Nearest-rank percentile of sorted values (0 when empty).
+fn percentile(sorted: &[u64], q: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (q * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_results() {
+        let text = "{\"writer\": 0, \"appended\": 2, \"failed\": 1, \"dropped\": 3, \"latenciesUs\": [120, 80]}\n\n";
+        let results = parse_results(text).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].dropped, 3);
+        assert_eq!(results[0].latencies_us, vec![120, 80]);
+        assert!(parse_results("{\"appended\": 1}").unwrap_err().contains("\"latenciesUs\" must be an array"));
+    }
+
+    #[test]
+    fn test_summarize_merges_writers() {
+        let writers = vec![
+            WriterResult { appended: 50, failed: 0, dropped: 2, latencies_us: (1..=50).collect() },
+            WriterResult { appended: 50, failed: 1, dropped: 0, latencies_us: (51..=100).collect() },
+        ];
+
+        let summary = summarize(&writers, 10);
+        assert_eq!((summary.appended, summary.failed, summary.dropped), (100, 1, 2));
+        assert_eq!(summary.throughput, 10.0);
+        assert_eq!(
+            summary.percentiles,
+            vec![("p50", 50), ("p90", 90), ("p99", 99), ("p99.9", 100), ("max", 100)]
+        );
+        assert_eq!(summarize(&[], 10).percentiles[0], ("p50", 0));
+    }
+
+    #[test]
+    fn test_script_removes_store_after_writers() {
+        let script = spite_compiler::project::generate_bench_ts();
+        assert!(!script.contains("db.close()"));
+        let results = script.find("await Promise.all(").unwrap();
+        assert!(results < script.find("rmSync(dbPath + suffix").unwrap());
+        assert!(script.contains("await db.append(stream, `${stream}-${rev}`, rev, [payload], 'bench');"));
+    }
+}
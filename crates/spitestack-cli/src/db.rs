@@ -0,0 +1,321 @@
+//! `spitestack db` - event store inspection.
+//!
+//! The store is only reachable through `@spitestack/db`, so each command runs
+//! a script the CLI drops into the generated project's `src/` for the length
+//! of the command (see `generate_db_tool_ts`). The script prints one JSON line
+//! per event; this module turns those lines into stats, stream listings and
+//! consistency checks. The TUI event browser runs the same script.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use rusqlite::{Connection, OpenFlags};
+use serde_json::Value;
+use tokio::process::{Child, Command};
+
+/// Numbers the script files of this process's runs.
+static NEXT_RUN: AtomicU64 = AtomicU64::new(0);
+
+/// A running db-tool script. The script file is removed when the run is
+/// dropped, and the process is killed with it.
+pub struct ToolRun {
+    pub child: Child,
+    script: PathBuf,
+}
+
+impl Drop for ToolRun {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.script);
+    }
+}
+
+/// Start the script with `args` in the generated project at `output`.
+///
+/// Stdout is piped. Each run gets its own script file, named after the
+/// process and a per-run counter, so overlapping runs (the TUI's tail and
+/// its periodic head reads) never remove each other's script.
+pub fn spawn_tool(output: &Path, args: &[String], stderr: Stdio) -> Result<ToolRun, String> {
+    if !output.join("package.json").exists() {
+        return Err(format!(
+            "No generated project in {}. Run `spitestack compile` first",
+            output.display()
+        ));
+    }
+
+    let name = script_name();
+    let script = output.join("src").join(&name);
+    std::fs::write(&script, spite_compiler::project::generate_db_tool_ts())
+        .map_err(|e| format!("Failed to write {}: {}", script.display(), e))?;
+
+    let child = Command::new("bun")
+        .arg("run")
+        .arg(format!("src/{}", name))
+        .args(args)
+        .current_dir(output)
+        .stdout(Stdio::piped())
+        .stderr(stderr)
+        .kill_on_drop(true)
+        .spawn();
+    match child {
+        Ok(child) => Ok(ToolRun { child, script }),
+        Err(e) => {
+            let _ = std::fs::remove_file(&script);
+            Err(format!("Failed to run bun: {}", e))
+        }
+    }
+}
+
+/// File name for the next run's script.
+fn script_name() -> String {
+    format!("db-tool-{}-{}.ts", std::process::id(), NEXT_RUN.fetch_add(1, Ordering::Relaxed))
+}
+
+/// One event as printed by the script.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredEvent {
+    pub pos: u64,
+    pub stream: String,
+    pub rev: u64,
+    /// Tenant name, or `#<hash>` when no known tenant matched.
+    pub tenant: String,
+    pub timestamp_ms: u64,
+    pub bytes: u64,
+    /// `type` of the payload; None if the payload is not a typed JSON object.
+    pub event_type: Option<String>,
+    /// Payload, for `read-stream` and `tail`.
+    pub data: Option<Value>,
+}
+
+/// Parse one line of script output.
+pub fn parse_event(line: &str) -> Result<StoredEvent, String> {
+    let value: Value = serde_json::from_str(line.trim()).map_err(|e| format!("invalid JSON ({})", e))?;
+    let number = |name: &str| value.get(name).and_then(Value::as_u64).ok_or(format!("missing \"{}\"", name));
+    let text = |name: &str| value.get(name).and_then(Value::as_str).map(String::from);
+
+    Ok(StoredEvent {
+        pos: number("pos")?,
+        stream: text("stream").ok_or("missing \"stream\"")?,
+        rev: number("rev")?,
+        tenant: text("tenant").unwrap_or_default(),
+        timestamp_ms: number("ts").unwrap_or(0),
+        bytes: number("bytes").unwrap_or(0),
+        event_type: text("type"),
+        data: value.get("data").filter(|data| !data.is_null()).cloned(),
+    })
+}
+
+/// A stream seen while scanning the log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamSummary {
+    pub tenant: String,
+    pub stream: String,
+    pub events: u64,
+    pub revision: u64,
+    pub last_timestamp_ms: u64,
+}
+
+/// Everything learnt from one pass over the global log.
+#[derive(Debug, Default)]
+pub struct StoreScan {
+    pub events: u64,
+    pub bytes: u64,
+    pub head: u64,
+    pub first_timestamp_ms: Option<u64>,
+    pub last_timestamp_ms: Option<u64>,
+    pub types: BTreeMap<String, u64>,
+    /// Event types outside the system tenant, where the runtime keeps its
+    /// identity and tenant events.
+    pub domain_types: BTreeMap<String, u64>,
+    pub tenants: BTreeSet<String>,
+    /// Consistency problems found by `add`, in log order.
+    pub problems: Vec<String>,
+    streams: BTreeMap<(String, String), StreamSummary>,
+}
+
+impl StoreScan {
+    /// Account for the next event of the log.
+    pub fn add(&mut self, event: StoredEvent) {
+        if self.events > 0 && event.pos <= self.head {
+            self.problems.push(format!("position {} follows position {}", event.pos, self.head));
+        }
+        match &event.event_type {
+            Some(event_type) => {
+                *self.types.entry(event_type.clone()).or_default() += 1;
+                if event.tenant != "system" {
+                    *self.domain_types.entry(event_type.clone()).or_default() += 1;
+                }
+            }
+            None => self.problems.push(format!("event at {} has no JSON payload with a type", event.pos)),
+        }
+
+        let key = (event.tenant.clone(), event.stream.clone());
+        match self.streams.get_mut(&key) {
+            Some(stream) => {
+                if event.rev != stream.revision + 1 {
+                    self.problems.push(format!(
+                        "{} ({}): revision {} at position {} follows revision {}",
+                        event.stream, event.tenant, event.rev, event.pos, stream.revision
+                    ));
+                }
+                stream.events += 1;
+                stream.revision = stream.revision.max(event.rev);
+                stream.last_timestamp_ms = event.timestamp_ms;
+            }
+            None => {
+                self.streams.insert(
+                    key,
+                    StreamSummary {
+                        tenant: event.tenant.clone(),
+                        stream: event.stream.clone(),
+                        events: 1,
+                        revision: event.rev,
+                        last_timestamp_ms: event.timestamp_ms,
+                    },
+                );
+            }
+        }
+
+        self.events += 1;
+        self.bytes += event.bytes;
+        self.head = self.head.max(event.pos);
+        self.first_timestamp_ms.get_or_insert(event.timestamp_ms);
+        self.last_timestamp_ms = Some(event.timestamp_ms);
+        self.tenants.insert(event.tenant);
+    }
+
+    /// Number of distinct streams.
+    pub fn stream_count(&self) -> usize {
+        self.streams.len()
+    }
+
+    /// Streams matching the filters, by tenant then stream id.
+    pub fn streams(&self, prefix: Option<&str>, tenant: Option<&str>) -> Vec<&StreamSummary> {
+        self.streams
+            .values()
+            .filter(|s| prefix.is_none_or(|prefix| s.stream.starts_with(prefix)))
+            .filter(|s| tenant.is_none_or(|tenant| s.tenant == tenant))
+            .collect()
+    }
+}
+
+/// Store files (the database and its WAL/SHM siblings) in `events_dir`.
+pub fn store_files(events_dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(events_dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            [".db", ".db-wal", ".db-shm"].iter().any(|ext| name.ends_with(ext))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Snapshot every store database in `events_dir` into `dest` with
+/// `VACUUM INTO`. Each copy is taken in one read transaction, so it is
+/// consistent even while the server appends. Returns the number of
+/// databases and the bytes written.
+pub fn backup_store(events_dir: &Path, dest: &Path) -> Result<(usize, u64), String> {
+    let databases: Vec<PathBuf> = store_files(events_dir)
+        .map_err(|e| format!("Failed to read {}: {}", events_dir.display(), e))?
+        .into_iter()
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("db"))
+        .collect();
+
+    let mut bytes = 0;
+    for database in &databases {
+        let target = dest.join(database.file_name().expect("store files have names"));
+        let conn = Connection::open_with_flags(database, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|e| format!("Failed to open {}: {}", database.display(), e))?;
+        conn.execute("VACUUM INTO ?1", [target.to_string_lossy()])
+            .map_err(|e| format!("Failed to back up {}: {}", database.display(), e))?;
+        bytes += std::fs::metadata(&target).map(|m| m.len()).unwrap_or(0);
+    }
+    Ok((databases.len(), bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(pos: u64, stream: &str, rev: u64, event_type: Option<&str>) -> StoredEvent {
+        StoredEvent {
+            pos,
+            stream: stream.to_string(),
+            rev,
+            tenant: "acme".to_string(),
+            timestamp_ms: pos * 10,
+            bytes: 100,
+            event_type: event_type.map(String::from),
+            data: None,
+        }
+    }
+
+    #[test]
+    fn test_script_name_is_unique_per_run() {
+        assert_ne!(script_name(), script_name());
+    }
+
+    #[test]
+    fn test_parse_event() {
+        let line = r##"{"pos": 7, "stream": "todo-1", "rev": 2, "tenant": "#42", "ts": 1700, "bytes": 31, "type": "Created", "data": {"type": "Created"}}"##;
+        let parsed = parse_event(line).unwrap();
+        assert_eq!((parsed.pos, parsed.rev, parsed.tenant.as_str()), (7, 2, "#42"));
+        assert_eq!(parsed.event_type.as_deref(), Some("Created"));
+        assert!(parsed.data.is_some());
+        assert!(parse_event(r#"{"stream": "todo-1"}"#).unwrap_err().contains("missing \"pos\""));
+    }
+
+    #[test]
+    fn test_scan_collects_stats_and_problems() {
+        let mut scan = StoreScan::default();
+        scan.add(event(1, "todo-1", 1, Some("Created")));
+        scan.add(event(2, "todo-2", 1, Some("Created")));
+        scan.add(event(3, "todo-1", 3, Some("Completed")));
+        scan.add(event(3, "todo-2", 2, None));
+
+        assert_eq!((scan.events, scan.bytes, scan.head), (4, 400, 3));
+        assert_eq!(scan.types.get("Created"), Some(&2));
+        assert_eq!(scan.stream_count(), 2);
+        assert_eq!(scan.streams(Some("todo-1"), None)[0].revision, 3);
+        assert!(scan.streams(None, Some("other")).is_empty());
+        assert_eq!(
+            scan.problems,
+            vec![
+                "todo-1 (acme): revision 3 at position 3 follows revision 1".to_string(),
+                "position 3 follows position 3".to_string(),
+                "event at 3 has no JSON payload with a type".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_backup_store_includes_wal_contents() {
+        let dir = std::env::temp_dir().join(format!("spitestack-backup-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let (events_dir, dest) = (dir.join("events"), dir.join("backup"));
+        std::fs::create_dir_all(&events_dir).unwrap();
+        std::fs::create_dir_all(&dest).unwrap();
+
+        // Keep the writer open so the rows stay in the WAL, as with a running server
+        let writer = Connection::open(events_dir.join("app.db")).unwrap();
+        writer
+            .execute_batch(
+                "PRAGMA journal_mode = WAL; PRAGMA wal_autocheckpoint = 0;
+                 CREATE TABLE events (pos INTEGER PRIMARY KEY); INSERT INTO events VALUES (1), (2);",
+            )
+            .unwrap();
+
+        let (databases, bytes) = backup_store(&events_dir, &dest).unwrap();
+        assert_eq!(databases, 1);
+        assert!(bytes > 0);
+        let copy = Connection::open(dest.join("app.db")).unwrap();
+        let count: i64 = copy.query_row("SELECT COUNT(*) FROM events", [], |row| row.get(0)).unwrap();
+        assert_eq!(count, 2);
+
+        drop(writer);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
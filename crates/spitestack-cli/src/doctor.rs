@@ -0,0 +1,462 @@
+//! `spitestack doctor` - health checks for a project's data directory.
+//!
+//! Checks run against the generated project's `data/` directory:
+//!
+//! - SQLite integrity of every SQLite file (projections, sessions)
+//! - Projection checkpoints against the event log head
+//! - Lock file against the current domain code and the stored event types
+//! - Telemetry disk usage
+//!
+//! The event log is read by the caller with the same scan as `spitestack db
+//! stats`. Without it, checkpoints are compared across projections of the
+//! same tenant instead.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use spite_compiler::ir::DomainIR;
+use spite_compiler::schema::{diff_schemas, SchemaLockFile};
+
+use crate::db::StoreScan;
+use crate::projections;
+
+/// Telemetry size above which doctor suggests pruning.
+pub const TELEMETRY_WARN_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Outcome of a single check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Ok,
+    Warn,
+    Error,
+}
+
+/// A single doctor finding.
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub check: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    /// Suggested fix, if any.
+    pub fix: Option<String>,
+}
+
+impl Finding {
+    fn ok(check: &'static str, message: impl Into<String>) -> Self {
+        Self { check, severity: Severity::Ok, message: message.into(), fix: None }
+    }
+
+    fn warn(check: &'static str, message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { check, severity: Severity::Warn, message: message.into(), fix: Some(fix.into()) }
+    }
+
+    fn error(check: &'static str, message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { check, severity: Severity::Error, message: message.into(), fix: Some(fix.into()) }
+    }
+}
+
+/// Run all checks.
+///
+/// `domain` and `lock` are optional so doctor still reports on the data
+/// directory when the domain fails to parse. `log` is the scanned event log:
+/// None without a store, an error if the store could not be read.
+pub fn run_checks(
+    data_dir: &Path,
+    domain: Option<&DomainIR>,
+    lock: Option<&SchemaLockFile>,
+    log: Option<Result<&StoreScan, String>>,
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let scan = match log {
+        Some(Ok(scan)) => {
+            findings.push(Finding::ok(
+                "events",
+                format!("{} event(s) in {} stream(s), head position {}", scan.events, scan.stream_count(), scan.head),
+            ));
+            Some(scan)
+        }
+        Some(Err(problem)) => {
+            findings.push(Finding::warn(
+                "events",
+                format!("Cannot read the event log ({})", problem),
+                "Install Bun and run `spitestack compile` so the store can be opened",
+            ));
+            None
+        }
+        None => None,
+    };
+
+    if !data_dir.is_dir() {
+        findings.push(Finding::warn(
+            "data",
+            format!("No data directory at {}", data_dir.display()),
+            "Start the app once with `spitestack dev` to create it",
+        ));
+    } else {
+        findings.extend(check_sqlite_integrity(data_dir));
+        findings.extend(check_projection_checkpoints(data_dir, domain, scan.map(|s| s.head)));
+        findings.push(check_telemetry_usage(data_dir, TELEMETRY_WARN_BYTES));
+    }
+
+    if let Some(domain) = domain {
+        findings.push(check_lock_file(domain, lock));
+    }
+    if let (Some(lock), Some(scan)) = (lock, scan) {
+        findings.push(check_stored_event_types(lock, scan));
+    }
+
+    findings
+}
+
+/// Run `PRAGMA quick_check` on every SQLite file under the data directory.
+fn check_sqlite_integrity(data_dir: &Path) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let files: Vec<PathBuf> = walk_files(data_dir).into_iter().filter(|p| is_sqlite_file(p)).collect();
+
+    let mut healthy = 0;
+    for path in &files {
+        match quick_check(path) {
+            Ok(()) => healthy += 1,
+            Err(problem) => findings.push(Finding::error(
+                "sqlite",
+                format!("{}: {}", display_relative(path, data_dir), problem),
+                if is_projection_file(path, data_dir) {
+                    "Delete the file; the projection rebuilds from the event log on next start"
+                } else {
+                    "Restore the file from a backup"
+                },
+            )),
+        }
+    }
+
+    if findings.is_empty() {
+        findings.push(Finding::ok("sqlite", format!("{} SQLite file(s) passed integrity check", healthy)));
+    }
+
+    findings
+}
+
+fn quick_check(path: &Path) -> Result<(), String> {
+    let conn = rusqlite::Connection::open_with_flags(path, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| e.to_string())?;
+    let result: String = conn
+        .query_row("PRAGMA quick_check", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+
+    if result == "ok" {
+        Ok(())
+    } else {
+        Err(result)
+    }
+}
+
+/// Read each projection's checkpoint and compare it with the log head, or
+/// without one, with the furthest projection of the same tenant.
+fn check_projection_checkpoints(data_dir: &Path, domain: Option<&DomainIR>, head: Option<u64>) -> Vec<Finding> {
+    let projections_dir = data_dir.join("projections");
+    if !projections_dir.is_dir() {
+        return vec![Finding::ok("projections", "No projection databases yet")];
+    }
+
+    let known: Vec<String> = domain
+        .map(|d| d.projections.iter().map(|p| to_snake_case(&p.name)).collect())
+        .unwrap_or_default();
+
+    let mut findings = Vec::new();
+    // tenant -> [(projection, checkpoint)]
+    let mut by_tenant: BTreeMap<String, Vec<(String, i64)>> = BTreeMap::new();
+
+    for (path, owner) in projections::projection_files(&projections_dir, &known) {
+        let Some((projection, tenant)) = owner else {
+            if domain.is_some() {
+                findings.push(Finding::warn(
+                    "projections",
+                    format!("{} does not belong to any projection in the domain", display_relative(&path, data_dir)),
+                    "Delete it if the projection was removed or renamed",
+                ));
+            }
+            continue;
+        };
+
+        match projections::read_status(&path, &projection, &tenant) {
+            Ok(status) => {
+                if let Some(error) = &status.last_error {
+                    findings.push(Finding::warn(
+                        "projections",
+                        format!("{} ({}) last failed with: {}", projection, tenant, error),
+                        "Fix the projection, then rebuild it from `/projections` in the TUI",
+                    ));
+                }
+                by_tenant.entry(tenant).or_default().push((projection, status.checkpoint));
+            }
+            Err(problem) => findings.push(Finding::error(
+                "projections",
+                format!("{}: cannot read checkpoint ({})", display_relative(&path, data_dir), problem),
+                "Delete the file to rebuild the projection from the event log",
+            )),
+        }
+    }
+
+    for (tenant, checkpoints) in &by_tenant {
+        let furthest = checkpoints.iter().map(|(_, c)| *c).max().unwrap_or(0);
+        for (projection, checkpoint) in checkpoints {
+            match head {
+                Some(head) if *checkpoint > head as i64 => findings.push(Finding::error(
+                    "projections",
+                    format!("{} ({}) is at position {}, past the log head {}", projection, tenant, checkpoint, head),
+                    "The event store was replaced; delete the file to rebuild the projection",
+                )),
+                Some(head) if *checkpoint < head as i64 => findings.push(Finding::warn(
+                    "projections",
+                    format!("{} ({}) is {} event(s) behind the log head", projection, tenant, head as i64 - checkpoint),
+                    "Start the app so the worker catches up, or check its last error",
+                )),
+                Some(_) => {}
+                None if *checkpoint < furthest => findings.push(Finding::warn(
+                    "projections",
+                    format!("{} ({}) is {} event(s) behind other projections", projection, tenant, furthest - checkpoint),
+                    "Check the dev server output for projection worker errors",
+                )),
+                None => {}
+            }
+        }
+    }
+
+    if findings.is_empty() {
+        let count: usize = by_tenant.values().map(|c| c.len()).sum();
+        let against = if head.is_some() { "the log head" } else { "each other" };
+        findings.push(Finding::ok("projections", format!("{} projection checkpoint(s) in sync with {}", count, against)));
+    }
+
+    findings
+}
+
+/// Compare the lock file with the current domain code.
+fn check_lock_file(domain: &DomainIR, lock: Option<&SchemaLockFile>) -> Finding {
+    let Some(lock) = lock else {
+        return Finding::warn(
+            "schema",
+            "No events.lock.json found",
+            "Run `spitestack schema sync` before storing production events",
+        );
+    };
+
+    let diffs = diff_schemas(&lock.aggregates, domain);
+    let breaking: Vec<_> = diffs.iter().filter(|d| d.is_breaking()).collect();
+
+    if !breaking.is_empty() {
+        let events: Vec<String> = breaking.iter().map(|d| format!("{}.{}", d.aggregate, d.event)).collect();
+        Finding::error(
+            "schema",
+            format!("Breaking changes against the lock file: {}", events.join(", ")),
+            "Add a new event type instead, or see `spitestack schema diff`",
+        )
+    } else if !diffs.is_empty() {
+        Finding::warn(
+            "schema",
+            format!("{} event(s) changed since the lock file was written", diffs.len()),
+            "Run `spitestack schema sync` to record the new versions",
+        )
+    } else {
+        Finding::ok("schema", "Lock file matches the domain")
+    }
+}
+
+/// Check that every event type in the log is recorded in the lock file.
+///
+/// Events of the system tenant are written by the runtime (identity,
+/// tenants) and are not part of the domain.
+fn check_stored_event_types(lock: &SchemaLockFile, scan: &StoreScan) -> Finding {
+    let unknown: Vec<&String> = scan
+        .domain_types
+        .keys()
+        .filter(|event_type| !lock.aggregates.values().any(|aggregate| aggregate.events.contains_key(*event_type)))
+        .collect();
+
+    if unknown.is_empty() {
+        Finding::ok(
+            "schema",
+            format!("All {} stored event type(s) are in the lock file", scan.domain_types.len()),
+        )
+    } else {
+        let names: Vec<&str> = unknown.iter().map(|t| t.as_str()).collect();
+        Finding::error(
+            "schema",
+            format!("Stored events have types missing from the lock file: {}", names.join(", ")),
+            "Restore the removed events in the domain and run `spitestack schema sync`",
+        )
+    }
+}
+
+/// Report telemetry disk usage.
+fn check_telemetry_usage(data_dir: &Path, warn_bytes: u64) -> Finding {
+    let telemetry_dir = data_dir.join("telemetry");
+    let bytes: u64 = walk_files(&telemetry_dir)
+        .iter()
+        .filter_map(|p| std::fs::metadata(p).ok())
+        .map(|m| m.len())
+        .sum();
+
+    if bytes > warn_bytes {
+        Finding::warn(
+            "telemetry",
+            format!("Telemetry uses {}", format_bytes(bytes)),
+            "Lower the telemetry retention or delete old slices from data/telemetry",
+        )
+    } else {
+        Finding::ok("telemetry", format!("Telemetry uses {}", format_bytes(bytes)))
+    }
+}
+
+fn walk_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return files;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            files.extend(walk_files(&path));
+        } else {
+            files.push(path);
+        }
+    }
+
+    files.sort();
+    files
+}
+
+fn is_sqlite_file(path: &Path) -> bool {
+    use std::io::Read;
+
+    let mut header = [0u8; 16];
+    std::fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .map(|_| &header == b"SQLite format 3\0")
+        .unwrap_or(false)
+}
+
+/// Files rebuilt from the event log: everything under `projections/`,
+/// including the session table (`projections/sessions.db`).
+fn is_projection_file(path: &Path, data_dir: &Path) -> bool {
+    path.starts_with(data_dir.join("projections"))
+}
+
+fn display_relative(path: &Path, base: &Path) -> String {
+    path.strip_prefix(base).unwrap_or(path).display().to_string()
+}
+
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+fn to_snake_case(s: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in s.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                result.push('_');
+            }
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_data_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("spitestack-doctor-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("projections")).unwrap();
+        dir
+    }
+
+    fn write_projection(dir: &Path, projection: &str, tenant: &str, checkpoint: i64) {
+        let conn = rusqlite::Connection::open(dir.join("projections").join(format!("{}_{}.db", projection, tenant))).unwrap();
+        conn.execute_batch(&format!(
+            "CREATE TABLE {p}_position (tenant_id TEXT PRIMARY KEY, last_event_id INTEGER NOT NULL DEFAULT 0);
+             INSERT INTO {p}_position VALUES ('{t}', {c});",
+            p = projection,
+            t = tenant,
+            c = checkpoint
+        ))
+        .unwrap();
+    }
+
+    #[test]
+    fn test_reports_lagging_projection() {
+        let dir = temp_data_dir("lag");
+        write_projection(&dir, "totals", "acme", 10);
+        write_projection(&dir, "counts", "acme", 7);
+
+        let findings = run_checks(&dir, None, None, None);
+        let lag = findings
+            .iter()
+            .find(|f| f.check == "projections" && f.severity == Severity::Warn)
+            .expect("lag finding");
+        assert!(lag.message.contains("counts (acme) is 3 event(s) behind"));
+        assert!(findings.iter().any(|f| f.check == "sqlite" && f.severity == Severity::Ok));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_checks_against_the_event_log() {
+        let dir = temp_data_dir("log");
+        write_projection(&dir, "totals", "acme", 13);
+        write_projection(&dir, "counts", "acme", 9);
+        write_projection(&dir, "ahead", "acme", 20);
+
+        let mut scan = StoreScan::default();
+        for (pos, event_type, tenant) in [(11, "Created", "acme"), (12, "Archived", "acme"), (13, "TenantCreated", "system")] {
+            scan.add(crate::db::StoredEvent {
+                pos,
+                stream: format!("todo-{}", pos),
+                rev: 1,
+                tenant: tenant.to_string(),
+                timestamp_ms: 0,
+                bytes: 0,
+                event_type: Some(event_type.to_string()),
+                data: None,
+            });
+        }
+        let lock: SchemaLockFile = serde_json::from_str(
+            r#"{"version": "1.0", "generatedAt": "", "compilerVersion": "",
+                "aggregates": {"Todo": {"events": {"Created": {"version": 1, "fields": {}, "hash": ""}}}}}"#,
+        )
+        .unwrap();
+
+        let findings = run_checks(&dir, None, Some(&lock), Some(Ok(&scan)));
+        let messages: Vec<&str> = findings.iter().map(|f| f.message.as_str()).collect();
+        assert!(messages.contains(&"counts (acme) is 4 event(s) behind the log head"));
+        assert!(messages.contains(&"ahead (acme) is at position 20, past the log head 13"));
+        assert!(!messages.iter().any(|m| m.starts_with("totals")));
+        assert!(messages.contains(&"Stored events have types missing from the lock file: Archived"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0 GB");
+    }
+}
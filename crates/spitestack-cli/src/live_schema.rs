@@ -0,0 +1,234 @@
+//! `spitestack schema status --live` - stored events checked against the
+//! current event schemas.
+//!
+//! Reading the event store happens in Bun (`src/schema-sample.ts` in the
+//! generated project) because it is only reachable through `@spitestack/db`.
+//! The script prints sampled payloads per tenant and event type; this module
+//! checks each one against the event variants the domain declares now.
+//!
+//! Stored events do not record the schema version they were written with, so
+//! failures are grouped by event type and problem rather than by version.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+use spite_compiler::ir::{DomainIR, DomainType, EventVariant};
+
+/// Stored events of one type, as printed by the sample script.
+#[derive(Debug, Clone)]
+pub struct TypeSample {
+    pub tenant: String,
+    pub event_type: String,
+    /// Number of stored events of this type.
+    pub count: u64,
+    pub samples: Vec<Value>,
+}
+
+/// An event type whose sampled payloads would fail to replay.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayFailure {
+    pub tenant: String,
+    pub event_type: String,
+    pub count: u64,
+    pub sampled: usize,
+    pub failed: usize,
+    /// Distinct problems, in first-seen order.
+    pub problems: Vec<String>,
+}
+
+/// Parse the sample script's output (one JSON object per line).
+pub fn parse_samples(text: &str) -> Result<Vec<TypeSample>, String> {
+    let mut samples = Vec::new();
+
+    for (i, raw) in text.lines().enumerate() {
+        let raw = raw.trim();
+        if raw.is_empty() {
+            continue;
+        }
+
+        let value: Value =
+            serde_json::from_str(raw).map_err(|e| format!("Line {}: invalid JSON ({})", i + 1, e))?;
+        let field = |name: &str| value.get(name).cloned();
+
+        samples.push(TypeSample {
+            tenant: field("tenant").and_then(|v| v.as_str().map(String::from)).unwrap_or_default(),
+            event_type: field("type").and_then(|v| v.as_str().map(String::from)).unwrap_or_default(),
+            count: field("count").and_then(|v| v.as_u64()).unwrap_or(0),
+            samples: match field("samples") {
+                Some(Value::Array(items)) => items,
+                _ => return Err(format!("Line {}: \"samples\" must be an array", i + 1)),
+            },
+        });
+    }
+
+    Ok(samples)
+}
+
+/// Event variants by name. Aggregates may share event names, so a stored
+/// event replays if any variant of its name accepts it.
+pub fn event_variants(domain: &DomainIR) -> HashMap<&str, Vec<&EventVariant>> {
+    let mut variants: HashMap<&str, Vec<&EventVariant>> = HashMap::new();
+    for aggregate in &domain.aggregates {
+        for variant in &aggregate.events.variants {
+            variants.entry(variant.name.as_str()).or_default().push(variant);
+        }
+    }
+    variants
+}
+
+/// Check sampled payloads against the current event variants.
+pub fn check_samples(samples: &[TypeSample], variants: &HashMap<&str, Vec<&EventVariant>>) -> Vec<ReplayFailure> {
+    let mut failures = Vec::new();
+
+    for sample in samples {
+        let mut problems = Vec::new();
+        let mut failed = 0;
+
+        for payload in &sample.samples {
+            let problem = match variants.get(sample.event_type.as_str()) {
+                None => Some(format!("no aggregate declares a '{}' event", sample.event_type)),
+                // Collecting into Option short-circuits once a variant accepts it
+                Some(candidates) => candidates
+                    .iter()
+                    .map(|variant| payload_problem(payload, variant))
+                    .collect::<Option<Vec<String>>>()
+                    .and_then(|problems| problems.into_iter().next()),
+            };
+
+            if let Some(problem) = problem {
+                failed += 1;
+                if !problems.contains(&problem) {
+                    problems.push(problem);
+                }
+            }
+        }
+
+        if failed > 0 {
+            failures.push(ReplayFailure {
+                tenant: sample.tenant.clone(),
+                event_type: sample.event_type.clone(),
+                count: sample.count,
+                sampled: sample.samples.len(),
+                failed,
+                problems,
+            });
+        }
+    }
+
+    failures
+}
+
+/// The first reason the payload does not fit the variant, if any.
+fn payload_problem(payload: &Value, variant: &EventVariant) -> Option<String> {
+    let Some(object) = payload.as_object() else {
+        return Some("payload is not an object".to_string());
+    };
+
+    variant
+        .fields
+        .iter()
+        .find_map(|field| value_problem(object.get(&field.name), &field.typ, &field.name))
+}
+
+fn value_problem(value: Option<&Value>, typ: &DomainType, path: &str) -> Option<String> {
+    let value = match (value, typ) {
+        (None | Some(Value::Null), DomainType::Option(_)) => return None,
+        (None, _) => return Some(format!("missing required field `{}`", path)),
+        (Some(Value::Null), _) => return Some(format!("`{}` is null", path)),
+        (Some(value), _) => value,
+    };
+
+    let expected = match typ {
+        DomainType::String => value.is_string().then_some(()).ok_or("a string"),
+        DomainType::Number => value.is_number().then_some(()).ok_or("a number"),
+        DomainType::Boolean => value.is_boolean().then_some(()).ok_or("a boolean"),
+        DomainType::Option(inner) => return value_problem(Some(value), inner, path),
+        DomainType::Array(inner) => {
+            let Some(items) = value.as_array() else {
+                return Some(format!("`{}` is not an array", path));
+            };
+            return items
+                .iter()
+                .enumerate()
+                .find_map(|(i, item)| value_problem(Some(item), inner, &format!("{}[{}]", path, i)));
+        }
+        DomainType::Object(object) => {
+            if !value.is_object() {
+                return Some(format!("`{}` is not an object", path));
+            }
+            return object.fields.iter().find_map(|field| {
+                let nested = format!("{}.{}", path, field.name);
+                match value.get(&field.name) {
+                    None | Some(Value::Null) if field.optional => None,
+                    child => value_problem(child, &field.typ, &nested),
+                }
+            });
+        }
+        // Named types are not resolved here; replay decides
+        DomainType::Reference(_) => Ok(()),
+    };
+
+    expected.err().map(|kind| format!("`{}` is not {}", path, kind))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spite_compiler::ir::EventField;
+
+    fn created() -> EventVariant {
+        EventVariant {
+            name: "Created".to_string(),
+            span: None,
+            fields: vec![
+                EventField { name: "title".to_string(), typ: DomainType::String },
+                EventField {
+                    name: "tags".to_string(),
+                    typ: DomainType::Option(Box::new(DomainType::Array(Box::new(DomainType::String)))),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_parse_samples() {
+        let text = r#"{"tenant": "acme", "type": "Created", "count": 42, "samples": [{"type": "Created", "title": "a"}]}"#;
+        let samples = parse_samples(text).unwrap();
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].count, 42);
+        assert!(parse_samples(r#"{"type": "Created"}"#).unwrap_err().contains("\"samples\" must be an array"));
+    }
+
+    #[test]
+    fn test_reports_payloads_that_no_longer_fit() {
+        let variant = created();
+        let variants = HashMap::from([("Created", vec![&variant])]);
+        let samples = vec![
+            TypeSample {
+                tenant: "acme".to_string(),
+                event_type: "Created".to_string(),
+                count: 100,
+                samples: vec![
+                    serde_json::json!({ "type": "Created", "title": "ok" }),
+                    serde_json::json!({ "type": "Created", "name": "old shape" }),
+                    serde_json::json!({ "type": "Created", "title": "x", "tags": [1] }),
+                ],
+            },
+            TypeSample {
+                tenant: "acme".to_string(),
+                event_type: "Renamed".to_string(),
+                count: 3,
+                samples: vec![serde_json::json!({ "type": "Renamed" })],
+            },
+        ];
+
+        let failures = check_samples(&samples, &variants);
+        assert_eq!(failures.len(), 2);
+        assert_eq!(failures[0].failed, 2);
+        assert_eq!(
+            failures[0].problems,
+            vec!["missing required field `title`".to_string(), "`tags[0]` is not a string".to_string()]
+        );
+        assert_eq!(failures[1].problems, vec!["no aggregate declares a 'Renamed' event".to_string()]);
+    }
+}
@@ -0,0 +1,452 @@
+//! `spitestack lsp` - a language server for domain code.
+//!
+//! Speaks LSP over stdio (JSON-RPC with `Content-Length` framing) and
+//! supports:
+//!
+//! - Diagnostics for parse errors, structure and purity violations, and
+//!   breaking changes against `events.lock.json`, refreshed when a file is
+//!   opened or saved
+//! - Go to definition from an event name, e.g. `'Created'` in
+//!   `this.emit({ type: 'Created' })`, to the declaration of that event
+//!
+//! The compiler frontend reads the domain from disk, so unsaved edits are
+//! only seen after a save. Aggregates whose IR didn't change since the last
+//! run are not re-validated.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+use spite_compiler::cache::BuildCache;
+use spite_compiler::diagnostic::Span;
+use spite_compiler::ir::DomainIR;
+use spite_compiler::schema::{diff_schemas, SchemaLockFile};
+use spite_compiler::CompilerError;
+
+/// JSON-RPC error code for unknown methods.
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// LSP `DiagnosticSeverity.Error`.
+const SEVERITY_ERROR: u8 = 1;
+
+/// Runs the server on stdin/stdout until the client sends `exit`.
+pub fn run(domain: PathBuf, language: String) -> io::Result<()> {
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let stdout = io::stdout();
+    let mut output = stdout.lock();
+
+    let mut server = Server::new(domain, language);
+    while let Some(message) = read_message(&mut input)? {
+        for reply in server.handle(&message) {
+            write_message(&mut output, &reply)?;
+        }
+        if server.exited {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Reads one `Content-Length` framed message. Returns `None` at end of input.
+pub fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some(value) = line.strip_prefix("Content-Length:") {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+
+    let length = length.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length"))?;
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Writes one message with its `Content-Length` header.
+pub fn write_message(writer: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()
+}
+
+/// Server state between messages.
+pub struct Server {
+    domain: PathBuf,
+    language: String,
+    /// Last successfully parsed domain, used for go to definition.
+    domain_ir: Option<DomainIR>,
+    /// Aggregates that passed validation, by IR fingerprint.
+    cache: BuildCache,
+    /// Files that currently have diagnostics, so they can be cleared.
+    published: BTreeSet<PathBuf>,
+    exited: bool,
+}
+
+impl Server {
+    pub fn new(domain: PathBuf, language: String) -> Self {
+        Self {
+            domain,
+            language,
+            domain_ir: None,
+            cache: BuildCache::default(),
+            published: BTreeSet::new(),
+            exited: false,
+        }
+    }
+
+    /// Handles one incoming message and returns the messages to send back.
+    pub fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let method = message["method"].as_str().unwrap_or_default();
+        let id = message.get("id").cloned();
+        let params = &message["params"];
+
+        match (method, id) {
+            ("initialize", Some(id)) => {
+                self.resolve_domain(params);
+                vec![response(id, json!({
+                    "capabilities": {
+                        // Diagnostics come from the files on disk, so edits are only needed on save
+                        "textDocumentSync": { "openClose": true, "change": 0, "save": true },
+                        "definitionProvider": true,
+                    },
+                    "serverInfo": { "name": "spitestack", "version": env!("CARGO_PKG_VERSION") },
+                }))]
+            }
+            ("shutdown", Some(id)) => vec![response(id, Value::Null)],
+            ("textDocument/definition", Some(id)) => {
+                let locations = self.definition(params);
+                vec![response(id, Value::Array(locations))]
+            }
+            (_, Some(id)) => vec![json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": METHOD_NOT_FOUND, "message": format!("Unknown method: {}", method) },
+            })],
+            ("initialized" | "textDocument/didOpen" | "textDocument/didSave", None) => self.publish_diagnostics(),
+            ("exit", None) => {
+                self.exited = true;
+                Vec::new()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Resolves a relative domain directory against the workspace root.
+    fn resolve_domain(&mut self, params: &Value) {
+        if self.domain.is_absolute() {
+            return;
+        }
+        if let Some(root) = params["rootUri"].as_str().and_then(uri_to_path) {
+            self.domain = root.join(&self.domain);
+        }
+    }
+
+    /// Re-checks the domain and publishes diagnostics for every affected file.
+    fn publish_diagnostics(&mut self) -> Vec<Value> {
+        let by_file = self.collect_diagnostics();
+
+        let files: BTreeSet<PathBuf> = by_file.keys().cloned().chain(self.published.iter().cloned()).collect();
+        let notifications = files
+            .iter()
+            .map(|file| {
+                json!({
+                    "jsonrpc": "2.0",
+                    "method": "textDocument/publishDiagnostics",
+                    "params": {
+                        "uri": path_to_uri(file),
+                        "diagnostics": by_file.get(file).cloned().unwrap_or_default(),
+                    },
+                })
+            })
+            .collect();
+
+        self.published = by_file.into_keys().collect();
+        notifications
+    }
+
+    fn collect_diagnostics(&mut self) -> BTreeMap<PathBuf, Vec<Value>> {
+        let mut by_file: BTreeMap<PathBuf, Vec<Value>> = BTreeMap::new();
+
+        let parsed = spite_compiler::frontend::create_frontend(&self.language)
+            .and_then(|mut frontend| frontend.parse_directory(&self.domain));
+        let domain_ir = match parsed {
+            Ok(domain_ir) => domain_ir,
+            Err(e) => {
+                if let Some((file, diagnostic)) = compiler_diagnostic(&e) {
+                    by_file.entry(file).or_default().push(diagnostic);
+                }
+                return by_file;
+            }
+        };
+
+        for aggregate in &domain_ir.aggregates {
+            if self.cache.aggregate_unchanged(aggregate) {
+                continue;
+            }
+            match spite_compiler::validate::validate_aggregate(aggregate) {
+                Ok(()) => self.cache.record_aggregate(aggregate),
+                Err(e) => {
+                    if let Some((file, diagnostic)) = compiler_diagnostic(&e) {
+                        by_file.entry(file).or_default().push(diagnostic);
+                    }
+                }
+            }
+        }
+
+        let lock_path = self.domain.parent().unwrap_or(&self.domain).join("events.lock.json");
+        if let Ok(Some(lock)) = SchemaLockFile::load(&lock_path) {
+            for diff in diff_schemas(&lock.aggregates, &domain_ir).iter().filter(|d| d.is_breaking()) {
+                let span = domain_ir
+                    .aggregates
+                    .iter()
+                    .find(|a| a.name == diff.aggregate)
+                    .and_then(|a| a.events.variants.iter().find(|v| v.name == diff.event))
+                    .and_then(|v| v.span.as_ref());
+                if let Some(span) = span {
+                    by_file.entry(span.file.clone()).or_default().push(json!({
+                        "range": range(span),
+                        "severity": SEVERITY_ERROR,
+                        "source": "spitestack",
+                        "code": "spitestack::schema::breaking_change",
+                        "message": format!(
+                            "Breaking schema change in {}.{}:\n{}",
+                            diff.aggregate,
+                            diff.event,
+                            diff.format_changes()
+                        ),
+                    }));
+                }
+            }
+        }
+
+        self.domain_ir = Some(domain_ir);
+        by_file
+    }
+
+    /// Finds the declarations of the event named under the cursor.
+    fn definition(&mut self, params: &Value) -> Vec<Value> {
+        let Some(path) = params["textDocument"]["uri"].as_str().and_then(uri_to_path) else {
+            return Vec::new();
+        };
+        let line = params["position"]["line"].as_u64().unwrap_or(0) as usize;
+        let character = params["position"]["character"].as_u64().unwrap_or(0) as usize;
+        let Some(word) = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| word_at(&text, line, character))
+        else {
+            return Vec::new();
+        };
+
+        if self.domain_ir.is_none() {
+            self.collect_diagnostics();
+        }
+        let Some(domain_ir) = &self.domain_ir else {
+            return Vec::new();
+        };
+
+        domain_ir
+            .aggregates
+            .iter()
+            .flat_map(|a| &a.events.variants)
+            .filter(|v| v.name == word)
+            .filter_map(|v| v.span.as_ref())
+            .map(|span| json!({ "uri": path_to_uri(&span.file), "range": range(span) }))
+            .collect()
+    }
+}
+
+fn response(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+/// Converts a compiler error into an LSP diagnostic for its file.
+fn compiler_diagnostic(error: &CompilerError) -> Option<(PathBuf, Value)> {
+    let json = error.to_json();
+    let file = PathBuf::from(json["file"].as_str()?);
+    let mut message = error.to_string();
+    if let Some(help) = json["help"].as_str() {
+        message = format!("{}\n\nhelp: {}", message, help);
+    }
+    let range = match &json["range"] {
+        Value::Null => json!({
+            "start": { "line": 0, "character": 0 },
+            "end": { "line": 0, "character": 0 },
+        }),
+        range => range.clone(),
+    };
+    Some((
+        file,
+        json!({
+            "range": range,
+            "severity": SEVERITY_ERROR,
+            "source": "spitestack",
+            "code": json["code"],
+            "message": message,
+        }),
+    ))
+}
+
+fn range(span: &Span) -> Value {
+    json!({
+        "start": { "line": span.start_line, "character": span.start_col },
+        "end": { "line": span.end_line, "character": span.end_col },
+    })
+}
+
+/// The identifier-like word at a zero-based line and byte column.
+fn word_at(text: &str, line: usize, character: usize) -> Option<String> {
+    let line = text.lines().nth(line)?;
+    let is_word = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let character = character.min(line.len());
+    let start = line[..character].rfind(|c: char| !is_word(c)).map(|i| i + 1).unwrap_or(0);
+    let end = line[character..].find(|c: char| !is_word(c)).map(|i| i + character).unwrap_or(line.len());
+    (start < end).then(|| line[start..end].to_string())
+}
+
+fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+    Some(PathBuf::from(percent_decode(path)))
+}
+
+fn path_to_uri(path: &Path) -> String {
+    let absolute = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    format!("file://{}", absolute.display().to_string().replace(' ', "%20"))
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = (bytes[i] == b'%')
+            .then(|| text.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match hex {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TODO: &str = r#"export type TodoState = { title: string };
+
+export type TodoEvent =
+  | { type: 'Created'; title: string }
+  | { type: 'Completed' };
+
+export class TodoAggregate {
+  static initialState: TodoState = { title: '' };
+  readonly events: TodoEvent[] = [];
+  state: TodoState = { ...TodoAggregate.initialState };
+
+  emit(event: TodoEvent) { this.apply(event); this.events.push(event); }
+  apply(event: TodoEvent) {}
+
+  create(title: string) {
+    this.emit({ type: 'Created', title });
+  }
+}
+"#;
+
+    fn temp_domain(name: &str, source: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("spitestack-lsp-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("Todo")).unwrap();
+        std::fs::write(dir.join("Todo").join("aggregate.ts"), source).unwrap();
+        dir
+    }
+
+    #[test]
+    fn frames_messages() {
+        let mut buffer = Vec::new();
+        write_message(&mut buffer, &json!({ "jsonrpc": "2.0", "method": "exit" })).unwrap();
+        let message = read_message(&mut buffer.as_slice()).unwrap().unwrap();
+        assert_eq!(message["method"], "exit");
+        assert!(read_message(&mut &b""[..]).unwrap().is_none());
+    }
+
+    #[test]
+    fn does_not_ask_for_unsaved_changes() {
+        let mut server = Server::new(PathBuf::from("/domain"), "typescript".to_string());
+        let replies = server.handle(&json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }));
+        let sync = &replies[0]["result"]["capabilities"]["textDocumentSync"];
+        assert_eq!(sync["change"], 0);
+        assert_eq!(sync["save"], true);
+    }
+
+    #[test]
+    fn finds_word_under_cursor() {
+        let text = "x\n    this.emit({ type: 'Created', title });";
+        assert_eq!(word_at(text, 1, 28).as_deref(), Some("Created"));
+        assert_eq!(word_at(text, 1, 4).as_deref(), Some("this"));
+        assert_eq!(word_at(text, 5, 0), None);
+    }
+
+    #[test]
+    fn goes_to_event_declaration() {
+        let dir = temp_domain("definition", TODO);
+        let file = dir.join("Todo").join("aggregate.ts");
+        let mut server = Server::new(dir.clone(), "typescript".to_string());
+
+        let emit_line = TODO.lines().position(|l| l.contains("this.emit({ type: 'Created'")).unwrap();
+        let column = TODO.lines().nth(emit_line).unwrap().find("Created").unwrap();
+        let replies = server.handle(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "textDocument/definition",
+            "params": {
+                "textDocument": { "uri": path_to_uri(&file) },
+                "position": { "line": emit_line, "character": column },
+            },
+        }));
+
+        let locations = replies[0]["result"].as_array().unwrap();
+        assert_eq!(locations.len(), 1);
+        let declaration_line = TODO.lines().position(|l| l.contains("{ type: 'Created'; title")).unwrap();
+        assert_eq!(locations[0]["range"]["start"]["line"], declaration_line);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn publishes_and_clears_diagnostics() {
+        let dir = temp_domain("diagnostics", &TODO.replace("export type TodoState", "export type OtherState"));
+        let mut server = Server::new(dir.clone(), "typescript".to_string());
+
+        let replies = server.handle(&json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }));
+        assert_eq!(replies.len(), 1);
+        let diagnostics = &replies[0]["params"]["diagnostics"];
+        assert_eq!(diagnostics[0]["code"], "spitestack::structure::missing_member");
+
+        std::fs::write(dir.join("Todo").join("aggregate.ts"), TODO).unwrap();
+        let replies = server.handle(&json!({ "jsonrpc": "2.0", "method": "textDocument/didSave", "params": {} }));
+        assert_eq!(replies.len(), 1);
+        assert_eq!(replies[0]["params"]["diagnostics"], json!([]));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
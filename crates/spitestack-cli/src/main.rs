@@ -10,8 +10,15 @@ use clap::{Parser, Subcommand};
 use notify_debouncer_mini::{new_debouncer, notify::RecursiveMode, DebounceEventResult};
 use tokio::process::{Child, Command};
 
-use spite_compiler::{Compiler, CompilerConfig};
-
+use spite_compiler::{ApiStyle, CompileResult, Compiler, CompilerConfig};
+
+mod bench;
+mod db;
+mod doctor;
+mod live_schema;
+mod lsp;
+mod projections;
+mod seed;
 mod tui;
 mod ui;
 
@@ -35,7 +42,7 @@ enum Commands {
         #[arg(short, long, default_value = "src/domain")]
         domain: PathBuf,
 
-        /// Source language
+        /// Source language (typescript or python)
         #[arg(short, long, default_value = "typescript")]
         language: String,
 
@@ -54,7 +61,7 @@ enum Commands {
         #[arg(short, long, default_value = ".spitestack")]
         output: PathBuf,
 
-        /// Source language
+        /// Source language (typescript or python)
         #[arg(short, long, default_value = "typescript")]
         language: String,
 
@@ -65,6 +72,18 @@ enum Commands {
         /// Port for the generated server
         #[arg(short, long, default_value_t = 3000)]
         port: u16,
+
+        /// API style for the generated server (rest or graphql)
+        #[arg(long, default_value = "rest")]
+        api_style: String,
+
+        /// Also generate a typed TypeScript client package in <output>/client
+        #[arg(long)]
+        emit_client: bool,
+
+        /// Also generate a Dockerfile and production start script in <output>
+        #[arg(long)]
+        emit_docker: bool,
     },
 
     /// Check domain logic without generating code
@@ -73,9 +92,13 @@ enum Commands {
         #[arg(short, long, default_value = "src/domain")]
         domain: PathBuf,
 
-        /// Source language
+        /// Source language (typescript or python)
         #[arg(short, long, default_value = "typescript")]
         language: String,
+
+        /// Print diagnostics as JSON on stdout (for editor integration)
+        #[arg(long)]
+        json_diagnostics: bool,
     },
 
     /// Start dev server with hot reload
@@ -88,7 +111,7 @@ enum Commands {
         #[arg(short, long, default_value = ".spitestack")]
         output: PathBuf,
 
-        /// Source language
+        /// Source language (typescript or python)
         #[arg(short, long, default_value = "typescript")]
         language: String,
 
@@ -99,6 +122,10 @@ enum Commands {
         /// Skip purity checks
         #[arg(long)]
         skip_purity_check: bool,
+
+        /// API style for the generated server (rest or graphql)
+        #[arg(long, default_value = "rest")]
+        api_style: String,
     },
 
     /// Watch for changes and recompile (without running)
@@ -111,9 +138,62 @@ enum Commands {
         #[arg(short, long, default_value = ".spitestack")]
         output: PathBuf,
 
-        /// Source language
+        /// Source language (typescript or python)
+        #[arg(short, long, default_value = "typescript")]
+        language: String,
+
+        /// API style for the generated server (rest or graphql)
+        #[arg(long, default_value = "rest")]
+        api_style: String,
+    },
+
+    /// Check the project's data directory for problems
+    Doctor {
+        /// Domain source directory
+        #[arg(short, long, default_value = "src/domain")]
+        domain: PathBuf,
+
+        /// Output directory of the generated project (contains data/)
+        #[arg(short, long, default_value = ".spitestack")]
+        output: PathBuf,
+
+        /// Source language (typescript or python)
+        #[arg(short, long, default_value = "typescript")]
+        language: String,
+    },
+
+    /// Run a language server for domain code on stdio
+    Lsp {
+        /// Domain source directory (relative to the workspace root)
+        #[arg(short, long, default_value = "src/domain")]
+        domain: PathBuf,
+
+        /// Source language (typescript or python)
+        #[arg(short, long, default_value = "typescript")]
+        language: String,
+    },
+
+    /// Append fixture events from an NDJSON file to the local store
+    Seed {
+        /// Fixture file (one {"stream", "type", "payload"} object per line)
+        #[arg(short, long)]
+        file: PathBuf,
+
+        /// Domain source directory
+        #[arg(short, long, default_value = "src/domain")]
+        domain: PathBuf,
+
+        /// Output directory of the generated project
+        #[arg(short, long, default_value = ".spitestack")]
+        output: PathBuf,
+
+        /// Source language (typescript or python)
         #[arg(short, long, default_value = "typescript")]
         language: String,
+
+        /// Tenant for lines without a "tenant" field
+        #[arg(short, long, default_value = "system")]
+        tenant: String,
     },
 
     /// Schema management commands for event evolution
@@ -121,6 +201,123 @@ enum Commands {
         #[command(subcommand)]
         action: SchemaAction,
     },
+
+    /// Event store tooling
+    Db {
+        #[command(subcommand)]
+        action: DbAction,
+    },
+}
+
+/// Event store subcommands.
+#[derive(Subcommand)]
+enum DbAction {
+    /// Show event, stream and tenant counts for the local store
+    Stats {
+        /// Output directory of the generated project
+        #[arg(short, long, default_value = ".spitestack")]
+        output: PathBuf,
+    },
+
+    /// List streams with their revision and event count
+    ListStreams {
+        /// Only streams whose id starts with this prefix
+        #[arg(long)]
+        prefix: Option<String>,
+
+        /// Only streams of this tenant
+        #[arg(short, long)]
+        tenant: Option<String>,
+
+        /// Maximum number of streams listed
+        #[arg(long, default_value_t = 100)]
+        limit: usize,
+
+        /// Output directory of the generated project
+        #[arg(short, long, default_value = ".spitestack")]
+        output: PathBuf,
+    },
+
+    /// Print the events of one stream
+    ReadStream {
+        /// Stream id
+        stream: String,
+
+        /// Tenant that owns the stream
+        #[arg(short, long, default_value = "default")]
+        tenant: String,
+
+        /// First revision to read
+        #[arg(long, default_value_t = 0)]
+        from_rev: u64,
+
+        /// Maximum number of events
+        #[arg(long, default_value_t = 100)]
+        limit: usize,
+
+        /// Output directory of the generated project
+        #[arg(short, long, default_value = ".spitestack")]
+        output: PathBuf,
+    },
+
+    /// Follow new events as they are appended (Ctrl-C to stop)
+    Tail {
+        /// Start at this global position instead of the head
+        #[arg(long)]
+        from: Option<u64>,
+
+        /// Only events whose stream id starts with this prefix
+        #[arg(long)]
+        prefix: Option<String>,
+
+        /// Only events of this tenant
+        #[arg(short, long)]
+        tenant: Option<String>,
+
+        /// Output directory of the generated project
+        #[arg(short, long, default_value = ".spitestack")]
+        output: PathBuf,
+    },
+
+    /// Check global positions, stream revisions and payloads for consistency
+    Verify {
+        /// Output directory of the generated project
+        #[arg(short, long, default_value = ".spitestack")]
+        output: PathBuf,
+    },
+
+    /// Snapshot the event store into a directory (safe while the server runs)
+    Backup {
+        /// Destination directory (created; must be empty if it exists)
+        dest: PathBuf,
+
+        /// Output directory of the generated project
+        #[arg(short, long, default_value = ".spitestack")]
+        output: PathBuf,
+    },
+
+    /// Append synthetic events to a throwaway store and report latencies
+    Bench {
+        /// Concurrent writers, each appending to its own stream
+        #[arg(long, default_value_t = 4)]
+        writers: usize,
+
+        /// Target append rate across all writers
+        #[arg(long, default_value_t = 1000)]
+        events_per_sec: u64,
+
+        /// Size of each event payload in bytes
+        #[arg(long, default_value_t = 256)]
+        payload_bytes: usize,
+
+        /// How long to run, in seconds
+        #[arg(long, default_value_t = 10)]
+        duration: u64,
+
+        /// Output directory of the generated project
+        #[arg(short, long, default_value = ".spitestack")]
+        output: PathBuf,
+    },
 }
 
 /// Schema management subcommands.
@@ -131,6 +328,22 @@ enum SchemaAction {
         /// Domain source directory
         #[arg(short, long, default_value = "src/domain")]
         domain: PathBuf,
+
+        /// Also check stored events in the event store against the current schemas
+        #[arg(long)]
+        live: bool,
+
+        /// Output directory of the generated project (with --live)
+        #[arg(short, long, default_value = ".spitestack")]
+        output: PathBuf,
+
+        /// Tenants whose events are checked (with --live)
+        #[arg(short, long, default_value = "default")]
+        tenant: Vec<String>,
+
+        /// Stored events checked per event type (with --live)
+        #[arg(long, default_value = "20")]
+        sample: usize,
     },
 
     /// Generate or update the schema lock file
@@ -151,6 +364,21 @@ enum SchemaAction {
         domain: PathBuf,
     },
 
+    /// Export event payload schemas from the lock file
+    Export {
+        /// Domain source directory
+        #[arg(short, long, default_value = "src/domain")]
+        domain: PathBuf,
+
+        /// Output format (json-schema or typebox)
+        #[arg(short, long, default_value = "json-schema")]
+        format: String,
+
+        /// Output file (prints to stdout if omitted)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
     /// Reset lock file (requires explicit confirmation)
     Reset {
         /// Domain source directory
@@ -195,11 +423,27 @@ async fn main() -> miette::Result<()> {
             language,
             skip_purity_check,
             port,
+            api_style,
+            emit_client,
+            emit_docker,
         }) => {
-            compile_project(&domain, &output, &language, skip_purity_check, port).await?;
+            let config = CompilerConfig {
+                domain_dir: domain,
+                out_dir: output,
+                skip_purity_check,
+                language,
+                api_style: parse_api_style(&api_style)?,
+                emit_client,
+                emit_docker,
+            };
+            compile_project(config, port).await?;
+        }
+
+        Some(Commands::Check { domain, language, json_diagnostics: true }) => {
+            check_json(domain, language).await;
         }
 
-        Some(Commands::Check { domain, language }) => {
+        Some(Commands::Check { domain, language, json_diagnostics: false }) => {
             let spinner = ui::spinner("Checking domain logic...");
 
             let config = CompilerConfig {
@@ -207,15 +451,23 @@ async fn main() -> miette::Result<()> {
                 out_dir: PathBuf::new(),
                 skip_purity_check: false,
                 language: language.clone(),
+                api_style: ApiStyle::default(),
+                emit_client: false,
+                emit_docker: false,
             };
 
             let compiler = Compiler::new(config);
 
             // Get stats by parsing
-            let mut frontend = spite_compiler::frontend::create_frontend(&language)
-                .map_err(|e| miette::miette!("{}", e))?;
-            let domain_ir = frontend.parse_directory(&domain)
-                .map_err(|e| miette::miette!("{}", e))?;
+            let mut frontend = spite_compiler::frontend::create_frontend(&language)?;
+            let domain_ir = match frontend.parse_directory(&domain) {
+                Ok(domain_ir) => domain_ir,
+                Err(e) => {
+                    spinner.finish_and_clear();
+                    ui::nope_header();
+                    return Err(e.into());
+                }
+            };
 
             match compiler.check().await {
                 Ok(_) => {
@@ -235,7 +487,7 @@ async fn main() -> miette::Result<()> {
                 Err(e) => {
                     spinner.finish_and_clear();
                     ui::nope_header();
-                    return Err(miette::miette!("{}", e));
+                    return Err(e.into());
                 }
             }
         }
@@ -246,21 +498,51 @@ async fn main() -> miette::Result<()> {
             language,
             port,
             skip_purity_check,
+            api_style,
         }) => {
-            run_dev_mode(&domain, &output, &language, port, skip_purity_check).await?;
+            let api_style = parse_api_style(&api_style)?;
+            run_dev_mode(&domain, &output, &language, port, skip_purity_check, api_style).await?;
         }
 
         Some(Commands::Watch {
             domain,
             output,
             language,
+            api_style,
         }) => {
-            run_watch_mode(&domain, &output, &language).await?;
+            let api_style = parse_api_style(&api_style)?;
+            run_watch_mode(&domain, &output, &language, api_style).await?;
+        }
+
+        Some(Commands::Doctor {
+            domain,
+            output,
+            language,
+        }) => {
+            run_doctor(&domain, &output, &language).await?;
+        }
+
+        Some(Commands::Lsp { domain, language }) => {
+            lsp::run(domain, language).map_err(|e| miette::miette!("Language server failed: {}", e))?;
+        }
+
+        Some(Commands::Seed {
+            file,
+            domain,
+            output,
+            language,
+            tenant,
+        }) => {
+            run_seed(&file, &domain, &output, &language, &tenant).await?;
         }
 
         Some(Commands::Schema { action }) => {
             handle_schema_command(action).await?;
         }
+
+        Some(Commands::Db { action }) => {
+            handle_db_command(action).await?;
+        }
     }
 
     Ok(())
@@ -403,15 +685,18 @@ export class TodoAggregate {
     Ok(())
 }
 
+/// Parse the `--api-style` flag.
+fn parse_api_style(value: &str) -> miette::Result<ApiStyle> {
+    ApiStyle::parse(value)
+        .ok_or_else(|| miette::miette!("Unknown API style '{}' (expected rest or graphql)", value))
+}
+
 /// Compile domain logic to a TypeScript project.
-async fn compile_project(
-    domain: &std::path::Path,
-    output: &std::path::Path,
-    language: &str,
-    skip_purity_check: bool,
-    port: u16,
-) -> miette::Result<()> {
+async fn compile_project(config: CompilerConfig, port: u16) -> miette::Result<()> {
     let start = Instant::now();
+    let domain = config.domain_dir.clone();
+    let output = config.out_dir.clone();
+    let language = config.language.clone();
 
     // Derive project name from domain directory
     let project_name = domain
@@ -421,17 +706,13 @@ async fn compile_project(
 
     let spinner = ui::spinner("Compiling domain logic...");
 
-    let config = CompilerConfig {
-        domain_dir: domain.to_path_buf(),
-        out_dir: output.to_path_buf(),
-        skip_purity_check,
-        language: language.to_string(),
-    };
-
     let compiler = Compiler::new(config);
-    compiler.compile_project(&project_name, port).await?;
+    let result = compiler.compile_project(&project_name, port).await?;
 
     spinner.finish_and_clear();
+    if let Some(note) = rebuild_changed_projections(&compiler, &result) {
+        ui::info(&note);
+    }
 
     // Success celebration
     ui::success_banner();
@@ -441,9 +722,9 @@ async fn compile_project(
     ui::box_line("");
 
     // Get aggregate details for the display
-    let mut frontend = spite_compiler::frontend::create_frontend(language)
+    let mut frontend = spite_compiler::frontend::create_frontend(&language)
         .map_err(|e| miette::miette!("{}", e))?;
-    let domain_ir = frontend.parse_directory(domain)
+    let domain_ir = frontend.parse_directory(&domain)
         .map_err(|e| miette::miette!("{}", e))?;
 
     let max_events = domain_ir
@@ -491,6 +772,7 @@ async fn run_dev_mode(
     language: &str,
     port: u16,
     skip_purity_check: bool,
+    api_style: ApiStyle,
 ) -> miette::Result<()> {
     // Print dev server banner
     println!();
@@ -529,6 +811,9 @@ async fn run_dev_mode(
         out_dir: output.to_path_buf(),
         skip_purity_check,
         language: language.to_string(),
+        api_style,
+        emit_client: false,
+        emit_docker: false,
     };
 
     let compiler = Compiler::new(config);
@@ -605,6 +890,9 @@ async fn run_dev_mode(
                     out_dir: output_clone.clone(),
                     skip_purity_check,
                     language: language_clone.clone(),
+                    api_style,
+                    emit_client: false,
+                    emit_docker: false,
                 };
 
                 let compiler = Compiler::new(config);
@@ -613,11 +901,15 @@ async fn run_dev_mode(
                         let duration = start.elapsed().as_millis();
                         ui::box_line("");
                         ui::box_line(&format!(
-                            "   {} Compiled {} aggregate(s) in {}ms",
+                            "   {} Compiled {} aggregate(s) in {}ms{}",
                             ui::symbols::TARGET_FILLED,
                             result.aggregates,
-                            duration
+                            duration,
+                            unchanged_note(&result)
                         ));
+                        if let Some(note) = rebuild_changed_projections(&compiler, &result) {
+                            ui::box_line(&format!("   {} {}", ui::symbols::TARGET_FILLED, note));
+                        }
                         ui::box_line(&format!(
                             "   {} Server hot-reloaded",
                             ui::symbols::TARGET_FILLED
@@ -662,6 +954,7 @@ async fn run_watch_mode(
     domain: &std::path::Path,
     output: &std::path::Path,
     language: &str,
+    api_style: ApiStyle,
 ) -> miette::Result<()> {
     ui::info(&format!("Watching for changes in {}", domain.display()));
     println!();
@@ -720,6 +1013,9 @@ async fn run_watch_mode(
                     out_dir: output_clone.clone(),
                     skip_purity_check: false,
                     language: language_clone.clone(),
+                    api_style,
+                    emit_client: false,
+                    emit_docker: false,
                 };
 
                 let compiler = Compiler::new(config);
@@ -728,9 +1024,10 @@ async fn run_watch_mode(
                         spinner.finish_and_clear();
                         let duration = start.elapsed().as_millis();
                         ui::success(&format!(
-                            "Compiled {} aggregate(s) in {}ms",
+                            "Compiled {} aggregate(s) in {}ms{}",
                             result.aggregates,
-                            duration
+                            duration,
+                            unchanged_note(&result)
                         ));
                     }
                     Err(e) => {
@@ -752,6 +1049,54 @@ async fn run_watch_mode(
     Ok(())
 }
 
+/// `spitestack check --json-diagnostics`: prints `{"diagnostics": [...]}` and
+/// exits non-zero if there are any.
+async fn check_json(domain: PathBuf, language: String) {
+    let config = CompilerConfig {
+        domain_dir: domain,
+        out_dir: PathBuf::new(),
+        skip_purity_check: false,
+        language,
+        api_style: ApiStyle::default(),
+        emit_client: false,
+        emit_docker: false,
+    };
+
+    let diagnostics: Vec<_> = match Compiler::new(config).check().await {
+        Ok(()) => Vec::new(),
+        Err(e) => vec![e.to_json()],
+    };
+    println!("{}", serde_json::json!({ "diagnostics": diagnostics }));
+    if !diagnostics.is_empty() {
+        std::process::exit(1);
+    }
+}
+
+/// Drops the stored read models of projections that changed, so the
+/// restarted server replays them from the event log. Must run while the
+/// server is stopped.
+fn rebuild_changed_projections(compiler: &Compiler, result: &CompileResult) -> Option<String> {
+    if result.changed_projections.is_empty() {
+        return None;
+    }
+    let names = result.changed_projections.join(", ");
+    Some(match compiler.reset_projections(&result.changed_projections) {
+        Ok(_) => format!("Rebuilding {} from the event log", names),
+        Err(e) => format!("Could not reset {}: {}", names, e),
+    })
+}
+
+/// Describes what an incremental recompile skipped, e.g. " (2 unchanged, 5 files up to date)".
+fn unchanged_note(result: &CompileResult) -> String {
+    if result.skipped_aggregates == 0 && result.skipped_files == 0 {
+        return String::new();
+    }
+    format!(
+        " ({} unchanged, {} files up to date)",
+        result.skipped_aggregates, result.skipped_files
+    )
+}
+
 /// Start bun dev as a background process.
 async fn start_bun_dev(project_dir: &PathBuf) -> miette::Result<Child> {
     // First run bun install to ensure dependencies are installed
@@ -780,11 +1125,448 @@ async fn start_bun_dev(project_dir: &PathBuf) -> miette::Result<Child> {
     Ok(child)
 }
 
+/// Validate a fixture file and import it with the generated seed script.
+async fn run_seed(
+    file: &std::path::Path,
+    domain: &std::path::Path,
+    output: &std::path::Path,
+    language: &str,
+    tenant: &str,
+) -> miette::Result<()> {
+    let text = std::fs::read_to_string(file)
+        .map_err(|e| miette::miette!("Failed to read {}: {}", file.display(), e))?;
+    let fixtures = seed::parse_fixtures(&text).map_err(|e| miette::miette!("{}: {}", file.display(), e))?;
+
+    let mut frontend = spite_compiler::frontend::create_frontend(language)
+        .map_err(|e| miette::miette!("{}", e))?;
+    let domain_ir = frontend.parse_directory(domain)
+        .map_err(|e| miette::miette!("{}", e))?;
+
+    let unknown = seed::unknown_event_types(&fixtures, &domain_ir);
+    if !unknown.is_empty() {
+        ui::nope_header();
+        for fixture in &unknown {
+            ui::error(&format!("Line {}: no aggregate declares a '{}' event", fixture.line, fixture.event_type));
+        }
+        return Err(miette::miette!("Fixture file references unknown event types"));
+    }
+
+    let seed_script = output.join("src").join("seed.ts");
+    if !seed_script.exists() {
+        return Err(miette::miette!(
+            "{} not found. Run `spitestack compile` first",
+            seed_script.display()
+        ));
+    }
+
+    let fixtures_path = file
+        .canonicalize()
+        .map_err(|e| miette::miette!("Failed to resolve {}: {}", file.display(), e))?;
+
+    ui::info(&format!("Seeding {} event(s) from {}", fixtures.len(), file.display()));
+    let status = Command::new("bun")
+        .arg("run")
+        .arg("src/seed.ts")
+        .arg(&fixtures_path)
+        .arg(tenant)
+        .current_dir(output)
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .status()
+        .await
+        .map_err(|e| miette::miette!("Failed to run bun: {}", e))?;
+
+    if !status.success() {
+        return Err(miette::miette!("Seeding failed"));
+    }
+
+    ui::looking_good();
+    Ok(())
+}
+
+/// Handle event store subcommands.
+async fn handle_db_command(action: DbAction) -> miette::Result<()> {
+    match action {
+        DbAction::Stats { output } => db_stats(&output).await,
+        DbAction::ListStreams { prefix, tenant, limit, output } => {
+            db_list_streams(&output, prefix.as_deref(), tenant.as_deref(), limit).await
+        }
+        DbAction::ReadStream { stream, tenant, from_rev, limit, output } => {
+            db_read_stream(&output, &stream, &tenant, from_rev, limit).await
+        }
+        DbAction::Tail { from, prefix, tenant, output } => {
+            db_tail(&output, from, prefix.as_deref(), tenant.as_deref()).await
+        }
+        DbAction::Verify { output } => db_verify(&output).await,
+        DbAction::Backup { dest, output } => db_backup(&output, &dest),
+        DbAction::Bench {
+            writers,
+            events_per_sec,
+            payload_bytes,
+            duration,
+            output,
+        } => run_bench(&output, writers, events_per_sec, payload_bytes, duration).await,
+    }
+}
+
+/// Run the store inspection script, calling `on_event` for each event it
+/// prints. Ctrl-C stops the script and ends the command normally.
+async fn run_db_tool(
+    output: &std::path::Path,
+    args: &[String],
+    mut on_event: impl FnMut(db::StoredEvent),
+) -> miette::Result<()> {
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    let mut run = db::spawn_tool(output, args, Stdio::inherit()).map_err(|e| miette::miette!("{}", e))?;
+    let mut lines = BufReader::new(run.child.stdout.take().expect("stdout is piped")).lines();
+
+    loop {
+        tokio::select! {
+            line = lines.next_line() => match line {
+                Ok(Some(line)) if line.trim().is_empty() => {}
+                Ok(Some(line)) => on_event(
+                    db::parse_event(&line).map_err(|e| miette::miette!("Unexpected store output: {}", e))?,
+                ),
+                Ok(None) => break,
+                Err(e) => return Err(miette::miette!("Failed to read store output: {}", e)),
+            },
+            _ = tokio::signal::ctrl_c() => {
+                let _ = run.child.kill().await;
+                return Ok(());
+            }
+        }
+    }
+
+    let status = run.child.wait().await.map_err(|e| miette::miette!("Failed to run bun: {}", e))?;
+    if !status.success() {
+        return Err(miette::miette!("Reading the event store failed"));
+    }
+    Ok(())
+}
+
+/// Scan the whole log.
+async fn scan_store(output: &std::path::Path) -> miette::Result<db::StoreScan> {
+    let spinner = ui::spinner("Scanning the event log...");
+    let mut scan = db::StoreScan::default();
+    let result = run_db_tool(output, &["scan".to_string()], |event| scan.add(event)).await;
+    spinner.finish_and_clear();
+    result.map(|_| scan)
+}
+
+/// Print store totals.
+async fn db_stats(output: &std::path::Path) -> miette::Result<()> {
+    let scan = scan_store(output).await?;
+    let size: u64 = db::store_files(&output.join("data").join("events"))
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|path| std::fs::metadata(path).ok())
+        .map(|meta| meta.len())
+        .sum();
+
+    ui::box_header(&format!("{} Event Store", ui::symbols::DIAMOND));
+    ui::box_line("");
+    ui::box_line(&format!("Events: {} (head position {})", scan.events, scan.head));
+    ui::box_line(&format!("Streams: {}  Tenants: {}", scan.stream_count(), scan.tenants.len()));
+    ui::box_line(&format!(
+        "Payloads: {}  On disk: {}",
+        doctor::format_bytes(scan.bytes),
+        doctor::format_bytes(size)
+    ));
+    if let (Some(first), Some(last)) = (scan.first_timestamp_ms, scan.last_timestamp_ms) {
+        ui::box_line(&format!("Written between {} and {} (ms since epoch)", first, last));
+    }
+    ui::box_line("");
+    let mut types: Vec<_> = scan.types.iter().collect();
+    types.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    for (event_type, count) in types.iter().take(10) {
+        ui::box_line(&format!("{:>8}  {}", count, event_type));
+    }
+    if !scan.problems.is_empty() {
+        ui::box_line("");
+        ui::box_line(&format!("{} consistency problem(s); run `spitestack db verify`", scan.problems.len()));
+    }
+    ui::box_footer();
+    println!();
+    Ok(())
+}
+
+/// Print the streams of the store.
+async fn db_list_streams(
+    output: &std::path::Path,
+    prefix: Option<&str>,
+    tenant: Option<&str>,
+    limit: usize,
+) -> miette::Result<()> {
+    let scan = scan_store(output).await?;
+    let streams = scan.streams(prefix, tenant);
+
+    for stream in streams.iter().take(limit) {
+        println!(
+            "{:<20} {:<40} rev {:<8} {} event(s)",
+            stream.tenant, stream.stream, stream.revision, stream.events
+        );
+    }
+    if streams.len() > limit {
+        ui::dim(&format!("... {} more (raise --limit)", streams.len() - limit));
+    }
+    if streams.is_empty() {
+        ui::info("No matching streams");
+    }
+    Ok(())
+}
+
+/// Print one event with its payload.
+fn print_event(event: &db::StoredEvent) {
+    println!(
+        "{} {} {}  rev {}  pos {}  {}",
+        ui::symbols::TRIANGLE,
+        event.event_type.as_deref().unwrap_or("(untyped)"),
+        event.stream,
+        event.rev,
+        event.pos,
+        event.tenant
+    );
+    if let Some(data) = &event.data {
+        let pretty = serde_json::to_string_pretty(data).unwrap_or_default();
+        for line in pretty.lines() {
+            println!("    {}", line);
+        }
+    }
+}
+
+/// Print the events of one stream.
+async fn db_read_stream(
+    output: &std::path::Path,
+    stream: &str,
+    tenant: &str,
+    from_rev: u64,
+    limit: usize,
+) -> miette::Result<()> {
+    let args = ["read-stream", stream, tenant, &from_rev.to_string(), &limit.to_string()].map(String::from);
+    let mut count = 0;
+    run_db_tool(output, &args, |event| {
+        count += 1;
+        print_event(&event);
+    })
+    .await?;
+    if count == 0 {
+        ui::info(&format!("No events in {} ({}) from revision {}", stream, tenant, from_rev));
+    }
+    Ok(())
+}
+
+/// Follow the log and print new events.
+async fn db_tail(
+    output: &std::path::Path,
+    from: Option<u64>,
+    prefix: Option<&str>,
+    tenant: Option<&str>,
+) -> miette::Result<()> {
+    let mut args = vec!["tail".to_string()];
+    // The script resumes after a position
+    if let Some(from) = from {
+        args.push(from.saturating_sub(1).to_string());
+    }
+
+    ui::info("Following the event log (Ctrl-C to stop)");
+    run_db_tool(output, &args, |event| {
+        let wanted = prefix.is_none_or(|prefix| event.stream.starts_with(prefix))
+            && tenant.is_none_or(|tenant| event.tenant == tenant);
+        if wanted {
+            print_event(&event);
+        }
+    })
+    .await
+}
+
+/// Check the log for consistency problems.
+async fn db_verify(output: &std::path::Path) -> miette::Result<()> {
+    let scan = scan_store(output).await?;
+
+    if scan.problems.is_empty() {
+        ui::success(&format!(
+            "{} event(s) in {} stream(s) verified",
+            scan.events,
+            scan.stream_count()
+        ));
+        return Ok(());
+    }
+
+    ui::nope_header();
+    for problem in &scan.problems {
+        ui::error(problem);
+    }
+    Err(miette::miette!("{} consistency problem(s) in the event store", scan.problems.len()))
+}
+
+/// Snapshot the store databases into `dest`.
+fn db_backup(output: &std::path::Path, dest: &std::path::Path) -> miette::Result<()> {
+    let events_dir = output.join("data").join("events");
+    let files = db::store_files(&events_dir)
+        .map_err(|e| miette::miette!("Failed to read {}: {}", events_dir.display(), e))?;
+    if files.is_empty() {
+        return Err(miette::miette!("No event store in {}", events_dir.display()));
+    }
+    if dest.read_dir().is_ok_and(|mut entries| entries.next().is_some()) {
+        return Err(miette::miette!("{} is not empty", dest.display()));
+    }
+    std::fs::create_dir_all(dest).map_err(|e| miette::miette!("Failed to create {}: {}", dest.display(), e))?;
+
+    let (databases, bytes) = db::backup_store(&events_dir, dest).map_err(|e| miette::miette!("{}", e))?;
+
+    ui::success(&format!(
+        "Backed up {} database(s), {} to {}",
+        databases,
+        doctor::format_bytes(bytes),
+        dest.display()
+    ));
+    Ok(())
+}
+
+/// Drive the event store with the generated bench script and print a report.
+async fn run_bench(
+    output: &std::path::Path,
+    writers: usize,
+    events_per_sec: u64,
+    payload_bytes: usize,
+    duration: u64,
+) -> miette::Result<()> {
+    if writers == 0 || events_per_sec == 0 || duration == 0 {
+        return Err(miette::miette!("--writers, --events-per-sec and --duration must be greater than zero"));
+    }
+
+    if !output.join("package.json").exists() {
+        return Err(miette::miette!(
+            "No generated project in {}. Run `spitestack compile` first",
+            output.display()
+        ));
+    }
+
+    // The script only exists for the duration of the run
+    let bench_script = output.join("src").join("bench.ts");
+    std::fs::write(&bench_script, spite_compiler::project::generate_bench_ts())
+        .map_err(|e| miette::miette!("Failed to write {}: {}", bench_script.display(), e))?;
+
+    let spinner = ui::spinner(&format!(
+        "Appending {} events/s from {} writer(s) for {}s...",
+        events_per_sec, writers, duration
+    ));
+    let result = Command::new("bun")
+        .arg("run")
+        .arg("src/bench.ts")
+        .arg(writers.to_string())
+        .arg(events_per_sec.to_string())
+        .arg(payload_bytes.to_string())
+        .arg(duration.to_string())
+        .current_dir(output)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .output()
+        .await
+        .map_err(|e| miette::miette!("Failed to run bun: {}", e));
+    spinner.finish_and_clear();
+    let _ = std::fs::remove_file(&bench_script);
+
+    let result = result?;
+    if !result.status.success() {
+        return Err(miette::miette!("Benchmark failed"));
+    }
+
+    let results = bench::parse_results(&String::from_utf8_lossy(&result.stdout))
+        .map_err(|e| miette::miette!("Unexpected bench output: {}", e))?;
+    let summary = bench::summarize(&results, duration);
+
+    ui::box_header(&format!("{} Event Store Bench", ui::symbols::DIAMOND));
+    ui::box_line("");
+    ui::box_line(&format!(
+        "Writers: {}  Target: {} events/s  Payload: {} bytes",
+        writers, events_per_sec, payload_bytes
+    ));
+    ui::box_line(&format!(
+        "Appended: {} ({:.0} events/s)  Failed: {}  Dropped: {}",
+        summary.appended, summary.throughput, summary.failed, summary.dropped
+    ));
+    ui::box_line("");
+    for (label, micros) in &summary.percentiles {
+        ui::box_line(&format!("{:>6}: {:.2} ms", label, *micros as f64 / 1000.0));
+    }
+    ui::box_footer();
+    println!();
+
+    Ok(())
+}
+
+/// Run data directory health checks and print a report.
+async fn run_doctor(domain: &std::path::Path, output: &std::path::Path, language: &str) -> miette::Result<()> {
+    use doctor::Severity;
+    use spite_compiler::schema::SchemaLockFile;
+
+    let data_dir = output.join("data");
+
+    // The event log is only read when there is a store to read
+    let has_store = db::store_files(&data_dir.join("events")).is_ok_and(|files| !files.is_empty());
+    let scan = if has_store {
+        Some(scan_store(output).await.map_err(|e| e.to_string()))
+    } else {
+        None
+    };
+
+    let spinner = ui::spinner("Examining the data directory...");
+
+    // Domain problems are reported, not fatal - the data checks still run
+    let domain_ir = spite_compiler::frontend::create_frontend(language)
+        .and_then(|mut frontend| frontend.parse_directory(domain));
+    let lock_path = domain.parent().unwrap_or(domain).join("events.lock.json");
+    let lock = SchemaLockFile::load(&lock_path).ok().flatten();
+
+    let log = scan.as_ref().map(|scan| scan.as_ref().map_err(Clone::clone));
+    let findings = doctor::run_checks(&data_dir, domain_ir.as_ref().ok(), lock.as_ref(), log);
+
+    spinner.finish_and_clear();
+
+    println!();
+    ui::box_header(&format!("{} Doctor", ui::symbols::DIAMOND));
+    ui::box_line("");
+    ui::box_line(&format!("Data: {}", data_dir.display()));
+    ui::box_line("");
+    for finding in &findings {
+        let marker = match finding.severity {
+            Severity::Ok => "OK",
+            Severity::Warn => "WARN",
+            Severity::Error => "FAIL",
+        };
+        ui::box_line(&format!("[{}] {}: {}", marker, finding.check, finding.message));
+        if let Some(fix) = &finding.fix {
+            ui::box_line(&format!("  {} {}", ui::symbols::ARROW, fix));
+        }
+    }
+    ui::box_line("");
+    ui::box_footer();
+
+    if let Err(e) = &domain_ir {
+        println!();
+        ui::error(&format!("Domain did not parse, schema check skipped: {}", e));
+    }
+
+    let errors = findings.iter().filter(|f| f.severity == Severity::Error).count();
+    if errors > 0 {
+        return Err(miette::miette!("Doctor found {} problem(s)", errors));
+    }
+
+    println!();
+    ui::looking_good();
+    Ok(())
+}
+
 /// Handle schema management commands.
 async fn handle_schema_command(action: SchemaAction) -> miette::Result<()> {
     match action {
-        SchemaAction::Status { domain } => {
+        SchemaAction::Status { domain, live, output, tenant, sample } => {
             schema_status(&domain).await?;
+            if live {
+                schema_status_live(&domain, &output, &tenant, sample).await?;
+            }
         }
         SchemaAction::Sync { domain, force } => {
             schema_sync(&domain, force).await?;
@@ -792,6 +1574,9 @@ async fn handle_schema_command(action: SchemaAction) -> miette::Result<()> {
         SchemaAction::Diff { domain } => {
             schema_diff(&domain).await?;
         }
+        SchemaAction::Export { domain, format, output } => {
+            schema_export(&domain, &format, output.as_ref()).await?;
+        }
         SchemaAction::Reset { domain, i_know_what_im_doing } => {
             schema_reset(&domain, i_know_what_im_doing).await?;
         }
@@ -878,6 +1663,78 @@ async fn schema_status(domain: &PathBuf) -> miette::Result<()> {
     Ok(())
 }
 
+/// Check sampled stored events against the current event schemas.
+async fn schema_status_live(
+    domain: &std::path::Path,
+    output: &std::path::Path,
+    tenants: &[String],
+    sample: usize,
+) -> miette::Result<()> {
+    let mut frontend = spite_compiler::frontend::create_frontend("typescript")
+        .map_err(|e| miette::miette!("{}", e))?;
+    let domain_ir = frontend.parse_directory(domain)
+        .map_err(|e| miette::miette!("{}", e))?;
+
+    let sample_script = output.join("src").join("schema-sample.ts");
+    if !sample_script.exists() {
+        return Err(miette::miette!(
+            "{} not found. Run `spitestack compile` first",
+            sample_script.display()
+        ));
+    }
+
+    let spinner = ui::spinner("Sampling stored events...");
+    let result = Command::new("bun")
+        .arg("run")
+        .arg("src/schema-sample.ts")
+        .arg(sample.to_string())
+        .args(tenants)
+        .current_dir(output)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .output()
+        .await
+        .map_err(|e| miette::miette!("Failed to run bun: {}", e))?;
+    spinner.finish_and_clear();
+
+    if !result.status.success() {
+        return Err(miette::miette!("Reading the event store failed"));
+    }
+
+    let samples = live_schema::parse_samples(&String::from_utf8_lossy(&result.stdout))
+        .map_err(|e| miette::miette!("Unexpected sample output: {}", e))?;
+    let failures = live_schema::check_samples(&samples, &live_schema::event_variants(&domain_ir));
+
+    ui::box_header(&format!("{} Stored Events", ui::symbols::DIAMOND));
+    ui::box_line("");
+    ui::box_line(&format!("Tenants: {}", tenants.join(", ")));
+    ui::box_line(&format!("Event types: {} (up to {} sampled each)", samples.len(), sample));
+    ui::box_line("");
+    if failures.is_empty() {
+        ui::box_line("Status: All sampled events fit the current schemas");
+    } else {
+        for failure in &failures {
+            ui::box_line(&format!(
+                "{} ({}): {} of {} sampled would fail to replay ({} stored)",
+                failure.event_type, failure.tenant, failure.failed, failure.sampled, failure.count
+            ));
+            for problem in &failure.problems {
+                ui::box_line(&format!("  - {}", problem));
+            }
+        }
+    }
+    ui::box_footer();
+    println!();
+
+    if !failures.is_empty() {
+        return Err(miette::miette!(
+            "{} event type(s) have stored events that no longer match their schema",
+            failures.len()
+        ));
+    }
+    Ok(())
+}
+
 /// Generate or update the schema lock file.
 async fn schema_sync(domain: &PathBuf, force: bool) -> miette::Result<()> {
     use spite_compiler::schema::SchemaLockFile;
@@ -986,6 +1843,43 @@ async fn schema_diff(domain: &PathBuf) -> miette::Result<()> {
     Ok(())
 }
 
+/// Export event payload schemas from the lock file.
+async fn schema_export(domain: &std::path::Path, format: &str, output: Option<&PathBuf>) -> miette::Result<()> {
+    use spite_compiler::schema::{export_schemas, ExportFormat, SchemaLockFile};
+
+    let format = ExportFormat::parse(format).ok_or_else(|| {
+        miette::miette!("Unknown export format '{}' (expected json-schema or typebox)", format)
+    })?;
+
+    let lock_path = domain.parent().unwrap_or(domain).join("events.lock.json");
+    let lock = SchemaLockFile::load(&lock_path)
+        .map_err(|e| miette::miette!("{}", e))?
+        .ok_or_else(|| miette::miette!(
+            "No lock file found at {}. Run `spitestack schema sync` first",
+            lock_path.display()
+        ))?;
+
+    let content = export_schemas(&lock, format);
+
+    match output {
+        None => print!("{}", content),
+        Some(path) => {
+            let path = if path.is_dir() { path.join(format.file_name()) } else { path.clone() };
+            std::fs::write(&path, content)
+                .map_err(|e| miette::miette!("Failed to write {}: {}", path.display(), e))?;
+
+            ui::looking_good();
+            println!();
+            println!("    Event schemas exported: {}", path.display());
+            println!("    {} events",
+                lock.aggregates.values().map(|a| a.events.len()).sum::<usize>()
+            );
+        }
+    }
+
+    Ok(())
+}
+
 /// Reset the schema lock file.
 async fn schema_reset(domain: &PathBuf, confirmed: bool) -> miette::Result<()> {
     let lock_path = domain.parent().unwrap_or(domain).join("events.lock.json");
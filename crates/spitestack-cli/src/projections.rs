@@ -0,0 +1,199 @@
+//! Projection databases of a generated project.
+//!
+//! Each projection worker keeps one SQLite file per tenant at
+//! `data/projections/{projection}_{tenant}.db`, holding the projection's rows,
+//! its checkpoint (`{projection}_position`) and its last batch error
+//! (`{projection}_last_error`). Projections with unique columns also record
+//! skipped events in `{projection}_poison`.
+
+use std::path::{Path, PathBuf};
+
+use rusqlite::{Connection, OpenFlags, OptionalExtension};
+
+/// Status of one projection for one tenant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectionStatus {
+    pub projection: String,
+    pub tenant: String,
+    pub path: PathBuf,
+    /// Global position the worker has applied up to; negative while a
+    /// rebuild is pending.
+    pub checkpoint: i64,
+    pub last_error: Option<String>,
+    pub poisoned: u64,
+}
+
+/// Snake-case names of the projections registered in the generated project
+/// at `output`, from its worker files.
+pub fn registered(output: &Path) -> Vec<String> {
+    let dir = output.join("src").join("generated").join("projections");
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| entry.file_name().to_str()?.strip_suffix(".worker.ts").map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// Split `{projection}_{tenant}` using the longest matching projection name.
+/// Without known names, the first underscore-separated segment is used.
+pub fn split_projection_file(stem: &str, known: &[String]) -> Option<(String, String)> {
+    if known.is_empty() {
+        let (projection, tenant) = stem.split_once('_')?;
+        return Some((projection.to_string(), tenant.to_string()));
+    }
+
+    known
+        .iter()
+        .filter(|name| stem.len() > name.len() + 1 && stem.starts_with(&format!("{}_", name)))
+        .max_by_key(|name| name.len())
+        .map(|name| (name.clone(), stem[name.len() + 1..].to_string()))
+}
+
+/// Projection database files in `projections_dir`, with the projection and
+/// tenant they belong to (None if no known projection matches).
+pub fn projection_files(projections_dir: &Path, known: &[String]) -> Vec<(PathBuf, Option<(String, String)>)> {
+    let mut files: Vec<(PathBuf, Option<(String, String)>)> = std::fs::read_dir(projections_dir)
+        .map(|entries| entries.filter_map(|entry| entry.ok().map(|e| e.path())).collect::<Vec<_>>())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("db"))
+        .filter_map(|path| {
+            let stem = path.file_stem()?.to_str()?.to_string();
+            // Session table of the auth runtime, checkpointed on demand
+            if stem == "sessions" {
+                return None;
+            }
+            let owner = split_projection_file(&stem, known);
+            Some((path, owner))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Quote an SQLite identifier.
+pub fn quote_ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn table_exists(conn: &Connection, table: &str) -> rusqlite::Result<bool> {
+    conn.query_row("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1", [table], |_| Ok(()))
+        .optional()
+        .map(|row| row.is_some())
+}
+
+/// Read a projection's checkpoint, last error and poison count.
+pub fn read_status(path: &Path, projection: &str, tenant: &str) -> Result<ProjectionStatus, String> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY).map_err(|e| e.to_string())?;
+
+    let checkpoint = conn
+        .query_row(
+            &format!("SELECT last_event_id FROM {} WHERE tenant_id = ?1", quote_ident(&format!("{}_position", projection))),
+            [tenant],
+            |row| row.get::<_, i64>(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?
+        .unwrap_or(0);
+
+    let errors = format!("{}_last_error", projection);
+    let last_error = if table_exists(&conn, &errors).map_err(|e| e.to_string())? {
+        conn.query_row(
+            &format!("SELECT error FROM {} WHERE tenant_id = ?1", quote_ident(&errors)),
+            [tenant],
+            |row| row.get::<_, String>(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?
+    } else {
+        None
+    };
+
+    let poison = format!("{}_poison", projection);
+    let poisoned = if table_exists(&conn, &poison).map_err(|e| e.to_string())? {
+        conn.query_row(
+            &format!("SELECT COUNT(*) FROM {} WHERE tenant_id = ?1", quote_ident(&poison)),
+            [tenant],
+            |row| row.get::<_, u64>(0),
+        )
+        .map_err(|e| e.to_string())?
+    } else {
+        0
+    };
+
+    Ok(ProjectionStatus {
+        projection: projection.to_string(),
+        tenant: tenant.to_string(),
+        path: path.to_path_buf(),
+        checkpoint,
+        last_error,
+        poisoned,
+    })
+}
+
+/// Ask the running worker to rebuild: a negative checkpoint makes it drop
+/// the tenant's rows and replay the log from the start.
+pub fn request_rebuild(status: &ProjectionStatus) -> Result<(), String> {
+    let conn = Connection::open(&status.path).map_err(|e| e.to_string())?;
+    conn.execute(
+        &format!(
+            "INSERT INTO {} (tenant_id, last_event_id, updated_at) VALUES (?1, -1, datetime('now'))
+             ON CONFLICT(tenant_id) DO UPDATE SET last_event_id = -1, updated_at = excluded.updated_at",
+            quote_ident(&format!("{}_position", status.projection))
+        ),
+        [&status.tenant],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_projection(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("spitestack-projections-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("todo_stats_acme.db");
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE todo_stats_position (tenant_id TEXT PRIMARY KEY, last_event_id INTEGER NOT NULL DEFAULT 0, updated_at TEXT);
+             CREATE TABLE todo_stats_last_error (tenant_id TEXT PRIMARY KEY, error TEXT NOT NULL, recorded_at TEXT);
+             INSERT INTO todo_stats_position VALUES ('acme', 42, NULL);
+             INSERT INTO todo_stats_last_error VALUES ('acme', 'boom', NULL);",
+        )
+        .unwrap();
+        path
+    }
+
+    #[test]
+    fn test_split_projection_file() {
+        let known = vec!["todo".to_string(), "todo_stats".to_string()];
+        assert_eq!(
+            split_projection_file("todo_stats_acme", &known),
+            Some(("todo_stats".to_string(), "acme".to_string()))
+        );
+        assert_eq!(split_projection_file("other_acme", &known), None);
+        assert_eq!(split_projection_file("todo_acme", &[]), Some(("todo".to_string(), "acme".to_string())));
+    }
+
+    #[test]
+    fn test_read_status_and_request_rebuild() {
+        let path = temp_projection("status");
+        let status = read_status(&path, "todo_stats", "acme").unwrap();
+        assert_eq!((status.checkpoint, status.last_error.as_deref(), status.poisoned), (42, Some("boom"), 0));
+        assert_eq!(read_status(&path, "todo_stats", "globex").unwrap().checkpoint, 0);
+
+        request_rebuild(&status).unwrap();
+        assert_eq!(read_status(&path, "todo_stats", "acme").unwrap().checkpoint, -1);
+
+        assert_eq!(quote_ident("a\"b"), "\"a\"\"b\"");
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
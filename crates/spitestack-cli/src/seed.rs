@@ -0,0 +1,107 @@
+//! `spitestack seed` - fixture validation before handing off to the generated seed script.
+//!
+//! The append itself happens in Bun (`src/seed.ts` in the generated project)
+//! because the event store is only reachable through `@spitestack/db`. This
+//! module catches malformed lines and unknown event types up front, with line
+//! numbers, so a bad fixture file never partially seeds a store.
+
+use std::collections::HashSet;
+
+use spite_compiler::ir::DomainIR;
+
+/// A parsed fixture line (payload is validated but not kept).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixtureLine {
+    pub line: usize,
+    pub stream: String,
+    pub event_type: String,
+}
+
+/// Parse NDJSON fixtures using the same rules as `runtime/fixtures.ts`.
+pub fn parse_fixtures(text: &str) -> Result<Vec<FixtureLine>, String> {
+    let mut fixtures = Vec::new();
+
+    for (i, raw) in text.lines().enumerate() {
+        let line = i + 1;
+        let raw = raw.trim();
+        if raw.is_empty() || raw.starts_with("//") {
+            continue;
+        }
+
+        let value: serde_json::Value =
+            serde_json::from_str(raw).map_err(|e| format!("Line {}: invalid JSON ({})", line, e))?;
+        let obj = value
+            .as_object()
+            .ok_or_else(|| format!("Line {}: expected an object", line))?;
+
+        let stream = non_empty_str(obj.get("stream"))
+            .ok_or_else(|| format!("Line {}: \"stream\" must be a non-empty string", line))?;
+        let event_type = non_empty_str(obj.get("type"))
+            .ok_or_else(|| format!("Line {}: \"type\" must be a non-empty string", line))?;
+
+        match obj.get("payload") {
+            None | Some(serde_json::Value::Object(_)) => {}
+            Some(_) => return Err(format!("Line {}: \"payload\" must be an object", line)),
+        }
+        match obj.get("tenant") {
+            None | Some(serde_json::Value::String(_)) => {}
+            Some(_) => return Err(format!("Line {}: \"tenant\" must be a string", line)),
+        }
+
+        fixtures.push(FixtureLine {
+            line,
+            stream: stream.to_string(),
+            event_type: event_type.to_string(),
+        });
+    }
+
+    Ok(fixtures)
+}
+
+/// Fixture lines whose event type is not declared by any aggregate.
+pub fn unknown_event_types<'a>(fixtures: &'a [FixtureLine], domain: &DomainIR) -> Vec<&'a FixtureLine> {
+    let known: HashSet<&str> = domain
+        .aggregates
+        .iter()
+        .flat_map(|a| a.events.variants.iter().map(|v| v.name.as_str()))
+        .collect();
+
+    fixtures
+        .iter()
+        .filter(|f| !known.contains(f.event_type.as_str()))
+        .collect()
+}
+
+fn non_empty_str(value: Option<&serde_json::Value>) -> Option<&str> {
+    value.and_then(|v| v.as_str()).filter(|s| !s.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fixtures() {
+        let text = r#"
+// demo data
+{"stream": "todo-1", "type": "Created", "payload": {"title": "Buy milk"}}
+{"stream": "todo-1", "type": "Completed", "tenant": "acme"}
+"#;
+        let fixtures = parse_fixtures(text).unwrap();
+        assert_eq!(fixtures.len(), 2);
+        assert_eq!(fixtures[1].line, 4);
+        assert_eq!(fixtures[1].event_type, "Completed");
+    }
+
+    #[test]
+    fn test_parse_fixtures_reports_line() {
+        let text = "{\"stream\": \"todo-1\", \"type\": \"Created\"}\n{\"stream\": \"\", \"type\": \"Created\"}\n";
+        assert_eq!(
+            parse_fixtures(text).unwrap_err(),
+            "Line 2: \"stream\" must be a non-empty string"
+        );
+        assert!(parse_fixtures("{\"stream\": \"a\", \"type\": \"B\", \"payload\": []}")
+            .unwrap_err()
+            .contains("\"payload\" must be an object"));
+    }
+}
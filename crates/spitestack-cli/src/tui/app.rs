@@ -6,9 +6,13 @@ use std::collections::VecDeque;
 use std::path::PathBuf;
 use std::time::Instant;
 
+use spite_compiler::ApiStyle;
 use tokio::sync::mpsc;
 
+use crate::db::StoredEvent;
 use crate::tui::audio::AudioPlayer;
+use crate::tui::browser::EventBrowser;
+use crate::tui::projections::{ProjectionPanel, ProjectionSnapshot};
 
 /// The main application state.
 pub struct App {
@@ -36,6 +40,12 @@ pub struct App {
     /// File watcher state
     pub watcher: WatcherState,
 
+    /// Event browser (while `/events` is open)
+    pub browser: Option<EventBrowser>,
+
+    /// Projection status panel (while `/projections` is open)
+    pub projections: Option<ProjectionPanel>,
+
     /// Channel for async task results
     pub task_tx: mpsc::Sender<TaskResult>,
     pub task_rx: mpsc::Receiver<TaskResult>,
@@ -74,6 +84,8 @@ impl App {
             errors: Vec::new(),
             fix_context: None,
             watcher: WatcherState::default(),
+            browser: None,
+            projections: None,
             task_tx,
             task_rx,
             should_quit: false,
@@ -132,6 +144,10 @@ pub enum AppMode {
     ErrorDetail,
     /// Full-screen music mode (SpiteStack Records)
     MusicMode,
+    /// Browsing the local event store
+    EventBrowser,
+    /// Projection checkpoints and errors
+    Projections,
 }
 
 /// Project state.
@@ -141,6 +157,8 @@ pub struct ProjectState {
     pub domain_dir: PathBuf,
     pub output_dir: PathBuf,
     pub name: Option<String>,
+    /// API style from the project's `package.json` (`spitestack.apiStyle`)
+    pub api_style: ApiStyle,
     pub last_compile: Option<CompileSnapshot>,
 }
 
@@ -408,6 +426,12 @@ pub enum TaskResult {
     DevServerStarted { port: u16 },
     DevServerStopped,
     FixApplied { file: PathBuf, success: bool },
+    /// An event read by the event browser
+    StoreEvent(StoredEvent),
+    /// The event browser's store reader stopped
+    StoreStopped(String),
+    /// A refresh of the projection panel
+    ProjectionStatus(ProjectionSnapshot),
 }
 
 // ============================================================================
@@ -0,0 +1,264 @@
+//! Event browser state.
+//!
+//! SpiteStack - Code Angry.
+//!
+//! `/events` follows the local event store from position 1 through the
+//! db-tool script (see `crate::db`), so the stream list covers the whole log
+//! and the tail keeps growing while the dev server writes.
+
+use std::collections::VecDeque;
+use std::path::Path;
+use std::process::Stdio;
+
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::db::{self, StoreScan, StoredEvent, StreamSummary};
+use crate::tui::app::TaskResult;
+
+/// Events kept for the tail pane.
+const MAX_RECENT: usize = 1000;
+
+/// Which pane has focus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BrowserPane {
+    Streams,
+    #[default]
+    Tail,
+}
+
+/// State of the event browser.
+#[derive(Debug, Default)]
+pub struct EventBrowser {
+    pub scan: StoreScan,
+    /// Newest last.
+    pub recent: VecDeque<StoredEvent>,
+    pub pane: BrowserPane,
+    pub selected_stream: usize,
+    pub selected_event: usize,
+    /// Stream id prefix filter.
+    pub prefix: String,
+    /// Whether keys go to the prefix filter.
+    pub editing_prefix: bool,
+    /// Tenant filter.
+    pub tenant: Option<String>,
+    /// Set once the script has stopped.
+    pub stopped: Option<String>,
+    task: Option<JoinHandle<()>>,
+}
+
+impl EventBrowser {
+    /// Start following the store of the generated project at `output`.
+    pub fn open(output: &Path, tx: mpsc::Sender<TaskResult>) -> Result<Self, String> {
+        let mut run = db::spawn_tool(output, &["tail".to_string(), "0".to_string()], Stdio::piped())?;
+
+        let task = tokio::spawn(async move {
+            let stdout = run.child.stdout.take().expect("stdout is piped");
+            let mut lines = BufReader::new(stdout).lines();
+
+            while let Ok(Some(line)) = lines.next_line().await {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let result = match db::parse_event(&line) {
+                    Ok(event) => TaskResult::StoreEvent(event),
+                    Err(e) => {
+                        let _ = tx.send(TaskResult::StoreStopped(format!("unexpected store output: {}", e))).await;
+                        return;
+                    }
+                };
+                if tx.send(result).await.is_err() {
+                    return;
+                }
+            }
+
+            let mut stderr = String::new();
+            if let Some(mut pipe) = run.child.stderr.take() {
+                let _ = pipe.read_to_string(&mut stderr).await;
+            }
+            let _ = run.child.wait().await;
+            let reason = stderr.lines().last().unwrap_or("store reader exited").to_string();
+            let _ = tx.send(TaskResult::StoreStopped(reason)).await;
+        });
+
+        let mut browser = Self::default();
+        browser.task = Some(task);
+        Ok(browser)
+    }
+
+    /// Stop following the store. Dropping the task kills the script.
+    pub fn close(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+    }
+
+    /// Record an event from the script.
+    pub fn push(&mut self, event: StoredEvent) {
+        // Keep the selection on the same event while following
+        if self.pane == BrowserPane::Tail && self.selected_event > 0 && self.matches(&event) {
+            self.selected_event += 1;
+        }
+        self.recent.push_back(event.clone());
+        if self.recent.len() > MAX_RECENT {
+            self.recent.pop_front();
+        }
+        self.scan.add(event);
+    }
+
+    fn matches(&self, event: &StoredEvent) -> bool {
+        event.stream.starts_with(&self.prefix) && self.tenant.as_ref().is_none_or(|tenant| &event.tenant == tenant)
+    }
+
+    /// Streams passing the filters.
+    pub fn streams(&self) -> Vec<&StreamSummary> {
+        self.scan.streams(Some(&self.prefix), self.tenant.as_deref())
+    }
+
+    /// Recent events passing the filters, newest first. With the stream pane
+    /// focused, only the selected stream's events.
+    pub fn events(&self) -> Vec<&StoredEvent> {
+        let streams = self.streams();
+        let stream = match self.pane {
+            BrowserPane::Streams => streams.get(self.selected_stream).copied(),
+            BrowserPane::Tail => None,
+        };
+        self.recent
+            .iter()
+            .rev()
+            .filter(|event| self.matches(event))
+            .filter(|event| stream.is_none_or(|s| s.stream == event.stream && s.tenant == event.tenant))
+            .collect()
+    }
+
+    /// The event whose payload is shown.
+    pub fn current_event(&self) -> Option<&StoredEvent> {
+        let index = match self.pane {
+            BrowserPane::Streams => 0,
+            BrowserPane::Tail => self.selected_event,
+        };
+        self.events().get(index).copied()
+    }
+
+    /// Move the selection of the focused pane.
+    pub fn move_selection(&mut self, down: bool) {
+        let len = match self.pane {
+            BrowserPane::Streams => self.streams().len(),
+            BrowserPane::Tail => self.events().len(),
+        };
+        let selected = match self.pane {
+            BrowserPane::Streams => &mut self.selected_stream,
+            BrowserPane::Tail => &mut self.selected_event,
+        };
+        if down {
+            *selected = (*selected + 1).min(len.saturating_sub(1));
+        } else {
+            *selected = selected.saturating_sub(1);
+        }
+    }
+
+    /// Switch focus between the stream list and the tail.
+    pub fn toggle_pane(&mut self) {
+        self.pane = match self.pane {
+            BrowserPane::Streams => BrowserPane::Tail,
+            BrowserPane::Tail => BrowserPane::Streams,
+        };
+        self.selected_event = 0;
+    }
+
+    /// Cycle the tenant filter through every tenant seen, then back to all.
+    pub fn cycle_tenant(&mut self) {
+        let tenants: Vec<&String> = self.scan.tenants.iter().collect();
+        self.tenant = match &self.tenant {
+            None => tenants.first().map(|t| t.to_string()),
+            Some(current) => tenants
+                .iter()
+                .position(|t| *t == current)
+                .and_then(|i| tenants.get(i + 1))
+                .map(|t| t.to_string()),
+        };
+        self.reset_selection();
+    }
+
+    /// Set the stream prefix filter.
+    pub fn set_prefix(&mut self, prefix: String) {
+        self.prefix = prefix;
+        self.reset_selection();
+    }
+
+    fn reset_selection(&mut self) {
+        self.selected_stream = 0;
+        self.selected_event = 0;
+    }
+}
+
+impl Drop for EventBrowser {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(pos: u64, stream: &str, tenant: &str) -> StoredEvent {
+        StoredEvent {
+            pos,
+            stream: stream.to_string(),
+            rev: pos,
+            tenant: tenant.to_string(),
+            timestamp_ms: 0,
+            bytes: 10,
+            event_type: Some("Created".to_string()),
+            data: None,
+        }
+    }
+
+    fn browser() -> EventBrowser {
+        let mut browser = EventBrowser::default();
+        browser.push(event(1, "todo-1", "acme"));
+        browser.push(event(2, "user-1", "acme"));
+        browser.push(event(3, "todo-2", "globex"));
+        browser
+    }
+
+    #[test]
+    fn test_filters_apply_to_streams_and_tail() {
+        let mut browser = browser();
+        let positions = |b: &EventBrowser| b.events().iter().map(|e| e.pos).collect::<Vec<_>>();
+        assert_eq!(positions(&browser), vec![3, 2, 1]);
+
+        browser.set_prefix("todo".to_string());
+        assert_eq!(browser.streams().len(), 2);
+        assert_eq!(positions(&browser), vec![3, 1]);
+
+        browser.cycle_tenant();
+        assert_eq!(browser.tenant.as_deref(), Some("acme"));
+        assert_eq!(positions(&browser), vec![1]);
+
+        browser.cycle_tenant();
+        browser.cycle_tenant();
+        assert_eq!(browser.tenant, None);
+    }
+
+    #[test]
+    fn test_stream_pane_shows_selected_stream() {
+        let mut browser = browser();
+        browser.toggle_pane();
+        browser.move_selection(true);
+        browser.move_selection(true);
+        assert_eq!(browser.streams()[browser.selected_stream].stream, "todo-2");
+        assert_eq!(browser.current_event().map(|e| e.pos), Some(3));
+    }
+
+    #[test]
+    fn test_selection_follows_new_events() {
+        let mut browser = browser();
+        browser.move_selection(true);
+        assert_eq!(browser.current_event().map(|e| e.pos), Some(2));
+        browser.push(event(4, "todo-3", "acme"));
+        assert_eq!(browser.current_event().map(|e| e.pos), Some(2));
+    }
+}
@@ -51,6 +51,18 @@ pub const COMMANDS: &[CommandDef] = &[
         description: "production build",
         category: "recording",
     },
+    CommandDef {
+        name: "events",
+        aliases: &["ev"],
+        description: "browse the event store",
+        category: "recording",
+    },
+    CommandDef {
+        name: "projections",
+        aliases: &["proj"],
+        description: "projection status",
+        category: "recording",
+    },
     CommandDef {
         name: "clear",
         aliases: &[],
@@ -8,7 +8,9 @@ use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
 use tokio::sync::mpsc;
 
 use crate::tui::app::{App, AppMode, CompileSnapshot, CompilerStatus, DiagnosticEntry, OutputLevel, TaskResult};
+use crate::tui::browser::EventBrowser;
 use crate::tui::commands::get_suggestions;
+use crate::tui::projections::ProjectionPanel;
 use spite_compiler::{Compiler, CompilerConfig};
 
 /// Application events.
@@ -101,6 +103,13 @@ pub async fn handle_event(app: &mut App, event: AppEvent) -> EventResult {
             // Update VU meters based on compiler status
             app.vu_meters.update_for_status(&app.compiler.status);
 
+            // Keep the projection panel live
+            if let Some(panel) = app.projections.as_mut() {
+                if panel.due() {
+                    panel.refresh(&app.project.output_dir, app.task_tx.clone());
+                }
+            }
+
             EventResult::Continue
         }
         AppEvent::Resize(_, _) => EventResult::Continue,
@@ -134,6 +143,8 @@ async fn handle_key(app: &mut App, key: KeyEvent) -> EventResult {
         AppMode::FixSelection => handle_fix_key(app, key).await,
         AppMode::ErrorDetail => handle_error_detail_key(app, key).await,
         AppMode::MusicMode => handle_music_mode_key(app, key).await,
+        AppMode::EventBrowser => handle_browser_key(app, key).await,
+        AppMode::Projections => handle_projections_key(app, key).await,
         _ => EventResult::Continue,
     }
 }
@@ -430,6 +441,77 @@ async fn handle_music_mode_key(app: &mut App, key: KeyEvent) -> EventResult {
     }
 }
 
+/// Handle key in the event browser.
+async fn handle_browser_key(app: &mut App, key: KeyEvent) -> EventResult {
+    let Some(browser) = app.browser.as_mut() else {
+        app.mode = AppMode::Dashboard;
+        return EventResult::Continue;
+    };
+
+    // Typing a stream prefix
+    if browser.editing_prefix {
+        match key.code {
+            KeyCode::Esc | KeyCode::Enter => browser.editing_prefix = false,
+            KeyCode::Backspace => {
+                let mut prefix = browser.prefix.clone();
+                prefix.pop();
+                browser.set_prefix(prefix);
+            }
+            KeyCode::Char(c) => {
+                let prefix = format!("{}{}", browser.prefix, c);
+                browser.set_prefix(prefix);
+            }
+            _ => {}
+        }
+        return EventResult::Continue;
+    }
+
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => {
+            app.browser = None;
+            app.mode = AppMode::Dashboard;
+        }
+        KeyCode::Tab => browser.toggle_pane(),
+        KeyCode::Up | KeyCode::Char('k') => browser.move_selection(false),
+        KeyCode::Down | KeyCode::Char('j') => browser.move_selection(true),
+        KeyCode::Char('/') => browser.editing_prefix = true,
+        KeyCode::Char('t') => browser.cycle_tenant(),
+        _ => {}
+    }
+    EventResult::Continue
+}
+
+/// Handle key in the projection panel.
+async fn handle_projections_key(app: &mut App, key: KeyEvent) -> EventResult {
+    let Some(panel) = app.projections.as_mut() else {
+        app.mode = AppMode::Dashboard;
+        return EventResult::Continue;
+    };
+
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => {
+            app.projections = None;
+            app.mode = AppMode::Dashboard;
+        }
+        KeyCode::Up | KeyCode::Char('k') => panel.move_selection(false),
+        KeyCode::Down | KeyCode::Char('j') => panel.move_selection(true),
+        KeyCode::Char('r') => {
+            let Some(status) = panel.current().cloned() else {
+                return EventResult::Continue;
+            };
+            match crate::projections::request_rebuild(&status) {
+                Ok(()) => {
+                    panel.last_refresh = None;
+                    app.log_info(format!("rebuilding {} ({})", status.projection, status.tenant));
+                }
+                Err(e) => app.log_error(format!("failed to rebuild {}: {}", status.projection, e)),
+            }
+        }
+        _ => {}
+    }
+    EventResult::Continue
+}
+
 /// Execute a slash command.
 async fn execute_command(app: &mut App, command: &str) {
     // Strip leading / in case user typed /mix instead of mix
@@ -483,6 +565,19 @@ async fn execute_command(app: &mut App, command: &str) {
         "master" | "prod" => {
             app.log_info("mastering not yet implemented");
         }
+        // /events - Browse the local event store
+        "events" | "ev" => {
+            execute_events(app);
+        }
+        // /projections - Projection checkpoints and errors
+        "projections" | "proj" => {
+            if app.project.root.is_none() {
+                app.log_error("no project. use /init first.");
+            } else {
+                app.projections = Some(ProjectionPanel::default());
+                app.mode = AppMode::Projections;
+            }
+        }
         "clear" => {
             app.output.clear();
         }
@@ -500,6 +595,8 @@ async fn execute_command(app: &mut App, command: &str) {
             app.log_info("  /remix    - fix errors");
             app.log_info("  /record   - start new session (init)");
             app.log_info("  /master   - production build");
+            app.log_info("  /events   - browse the event store");
+            app.log_info("  /projections - projection status");
             app.log_info("");
             app.log_info("session:");
             app.log_info("  /clear    - clear output");
@@ -517,6 +614,22 @@ async fn execute_command(app: &mut App, command: &str) {
     }
 }
 
+/// Execute the /events command.
+fn execute_events(app: &mut App) {
+    if app.project.root.is_none() {
+        app.log_error("no project. use /init first.");
+        return;
+    }
+
+    match EventBrowser::open(&app.project.output_dir, app.task_tx.clone()) {
+        Ok(browser) => {
+            app.browser = Some(browser);
+            app.mode = AppMode::EventBrowser;
+        }
+        Err(e) => app.log_error(e),
+    }
+}
+
 /// Execute the /dev command.
 async fn execute_dev(app: &mut App) {
     // Check if we have a project
@@ -564,6 +677,9 @@ async fn execute_compile(app: &mut App) {
         out_dir: output_dir.clone(),
         skip_purity_check: false,
         language: "typescript".to_string(),
+        api_style: app.project.api_style,
+        emit_client: false,
+        emit_docker: false,
     };
 
     let compiler = Compiler::new(config);
@@ -679,5 +795,20 @@ pub async fn handle_task_result(app: &mut App, result: TaskResult) {
                 app.log_error(format!("failed to fix: {}", file.display()));
             }
         }
+        TaskResult::StoreEvent(event) => {
+            if let Some(browser) = app.browser.as_mut() {
+                browser.push(event);
+            }
+        }
+        TaskResult::ProjectionStatus(snapshot) => {
+            if let Some(panel) = app.projections.as_mut() {
+                panel.update(snapshot);
+            }
+        }
+        TaskResult::StoreStopped(reason) => {
+            if let Some(browser) = app.browser.as_mut() {
+                browser.stopped = Some(reason);
+            }
+        }
     }
 }
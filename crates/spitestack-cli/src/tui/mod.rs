@@ -6,9 +6,11 @@
 
 pub mod app;
 pub mod audio;
+pub mod browser;
 pub mod capabilities;
 pub mod commands;
 pub mod event;
+pub mod projections;
 pub mod render;
 pub mod terminal;
 pub mod theme;
@@ -23,6 +25,7 @@ use crate::tui::event::{handle_event, handle_task_result, EventHandler, EventRes
 use crate::tui::terminal::TuiContext;
 use crate::tui::theme::Theme;
 use crate::tui::widgets::{draw_dashboard, draw_splash};
+use spite_compiler::ApiStyle;
 
 /// Run the TUI application.
 pub async fn run() -> miette::Result<()> {
@@ -83,6 +86,11 @@ fn detect_project(app: &mut App) {
                 .file_name()
                 .map(|s| s.to_string_lossy().to_string());
 
+            match read_api_style(&cwd) {
+                Ok(style) => app.project.api_style = style,
+                Err(e) => app.log_error(e),
+            }
+
             app.log_info(format!("project: {}", app.project.name.as_deref().unwrap_or("unknown")));
             return;
         }
@@ -92,6 +100,24 @@ fn detect_project(app: &mut App) {
     app.log_info("no project detected. use /init to create one.");
 }
 
+/// API style set in the project's `package.json`:
+/// `"spitestack": { "apiStyle": "graphql" }`. REST when unset.
+fn read_api_style(root: &std::path::Path) -> Result<ApiStyle, String> {
+    let Ok(content) = std::fs::read_to_string(root.join("package.json")) else {
+        return Ok(ApiStyle::default());
+    };
+    let package: serde_json::Value =
+        serde_json::from_str(&content).map_err(|e| format!("invalid package.json: {}", e))?;
+
+    match package.pointer("/spitestack/apiStyle") {
+        None => Ok(ApiStyle::default()),
+        Some(value) => value
+            .as_str()
+            .and_then(ApiStyle::parse)
+            .ok_or_else(|| format!("unknown spitestack.apiStyle {} (expected \"rest\" or \"graphql\")", value)),
+    }
+}
+
 /// Main application loop.
 async fn run_app(app: &mut App, ctx: &mut TuiContext, theme: &Theme) -> miette::Result<()> {
     let tier = ctx.tier();
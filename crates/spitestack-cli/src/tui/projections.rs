@@ -0,0 +1,170 @@
+//! Projection status panel state.
+//!
+//! SpiteStack - Code Angry.
+//!
+//! `/projections` lists the registered projections with each tenant's
+//! checkpoint, its lag behind the event log head and the worker's last
+//! error. Checkpoints come from the projection databases; the head comes
+//! from the db-tool script (see `crate::db`).
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::mpsc;
+
+use crate::db;
+use crate::projections::{self, ProjectionStatus};
+use crate::tui::app::TaskResult;
+
+/// How often the panel re-reads checkpoints and the head.
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// One refresh of the panel.
+#[derive(Debug, Clone, Default)]
+pub struct ProjectionSnapshot {
+    /// Registered projections (snake case).
+    pub registered: Vec<String>,
+    pub statuses: Vec<ProjectionStatus>,
+    /// Global position of the newest event.
+    pub head: Option<u64>,
+    /// Files or reads that failed.
+    pub problems: Vec<String>,
+}
+
+impl ProjectionSnapshot {
+    /// Events the status still has to apply, if the head is known.
+    pub fn lag(&self, status: &ProjectionStatus) -> Option<u64> {
+        self.head.map(|head| head.saturating_sub(status.checkpoint.max(0) as u64))
+    }
+
+    /// Registered projections without a database yet.
+    pub fn idle(&self) -> Vec<&String> {
+        self.registered
+            .iter()
+            .filter(|name| !self.statuses.iter().any(|s| &&s.projection == name))
+            .collect()
+    }
+}
+
+/// State of the projection panel.
+#[derive(Debug, Default)]
+pub struct ProjectionPanel {
+    pub snapshot: ProjectionSnapshot,
+    pub selected: usize,
+    pub refreshing: bool,
+    pub last_refresh: Option<Instant>,
+}
+
+impl ProjectionPanel {
+    /// Whether a refresh is due.
+    pub fn due(&self) -> bool {
+        !self.refreshing && self.last_refresh.is_none_or(|at| at.elapsed() >= REFRESH_INTERVAL)
+    }
+
+    /// Start a refresh for the generated project at `output`; the result
+    /// arrives as `TaskResult::ProjectionStatus`.
+    pub fn refresh(&mut self, output: &Path, tx: mpsc::Sender<TaskResult>) {
+        self.refreshing = true;
+        let output = output.to_path_buf();
+        tokio::spawn(async move {
+            let snapshot = read_snapshot(output).await;
+            let _ = tx.send(TaskResult::ProjectionStatus(snapshot)).await;
+        });
+    }
+
+    /// Apply a finished refresh.
+    pub fn update(&mut self, snapshot: ProjectionSnapshot) {
+        self.refreshing = false;
+        self.last_refresh = Some(Instant::now());
+        self.selected = self.selected.min(snapshot.statuses.len().saturating_sub(1));
+        self.snapshot = snapshot;
+    }
+
+    pub fn move_selection(&mut self, down: bool) {
+        if down {
+            self.selected = (self.selected + 1).min(self.snapshot.statuses.len().saturating_sub(1));
+        } else {
+            self.selected = self.selected.saturating_sub(1);
+        }
+    }
+
+    /// The selected projection and tenant.
+    pub fn current(&self) -> Option<&ProjectionStatus> {
+        self.snapshot.statuses.get(self.selected)
+    }
+}
+
+async fn read_snapshot(output: PathBuf) -> ProjectionSnapshot {
+    let mut snapshot = ProjectionSnapshot {
+        registered: projections::registered(&output),
+        ..ProjectionSnapshot::default()
+    };
+
+    let projections_dir = output.join("data").join("projections");
+    for (path, owner) in projections::projection_files(&projections_dir, &snapshot.registered) {
+        let Some((projection, tenant)) = owner else {
+            continue;
+        };
+        match projections::read_status(&path, &projection, &tenant) {
+            Ok(status) => snapshot.statuses.push(status),
+            Err(e) => snapshot.problems.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    match read_head(&output).await {
+        Ok(head) => snapshot.head = Some(head),
+        Err(e) => snapshot.problems.push(format!("event log head: {}", e)),
+    }
+    snapshot
+}
+
+/// Global position of the newest event, via the db-tool script.
+async fn read_head(output: &Path) -> Result<u64, String> {
+    let mut run = db::spawn_tool(output, &["head".to_string()], Stdio::null())?;
+    let stdout = run.child.stdout.take().expect("stdout is piped");
+    let mut lines = BufReader::new(stdout).lines();
+
+    let mut head = 0;
+    while let Some(line) = lines.next_line().await.map_err(|e| e.to_string())? {
+        if !line.trim().is_empty() {
+            head = db::parse_event(&line)?.pos;
+        }
+    }
+    let status = run.child.wait().await.map_err(|e| e.to_string())?;
+    if !status.success() {
+        return Err("reading the event store failed".to_string());
+    }
+    Ok(head)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(projection: &str, checkpoint: i64) -> ProjectionStatus {
+        ProjectionStatus {
+            projection: projection.to_string(),
+            tenant: "acme".to_string(),
+            path: PathBuf::new(),
+            checkpoint,
+            last_error: None,
+            poisoned: 0,
+        }
+    }
+
+    #[test]
+    fn test_snapshot_lag_and_idle_projections() {
+        let snapshot = ProjectionSnapshot {
+            registered: vec!["todo_stats".to_string(), "user_directory".to_string()],
+            statuses: vec![status("todo_stats", 40), status("todo_stats", -1)],
+            head: Some(42),
+            problems: Vec::new(),
+        };
+        assert_eq!(snapshot.lag(&snapshot.statuses[0]), Some(2));
+        assert_eq!(snapshot.lag(&snapshot.statuses[1]), Some(42));
+        assert_eq!(snapshot.idle(), vec!["user_directory"]);
+        assert_eq!(ProjectionSnapshot::default().lag(&snapshot.statuses[0]), None);
+    }
+}
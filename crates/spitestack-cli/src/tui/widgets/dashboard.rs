@@ -25,7 +25,8 @@ use crate::tui::app::{App, AppMode};
 use crate::tui::capabilities::CapabilityTier;
 use crate::tui::theme::Theme;
 use crate::tui::widgets::{
-    draw_errors, draw_input, draw_music_mode, draw_output, draw_status, draw_vu_meters_tiered,
+    draw_errors, draw_event_browser, draw_input, draw_music_mode, draw_output, draw_projections,
+    draw_status, draw_vu_meters_tiered,
 };
 use crate::tui::widgets::errors::draw_error_detail;
 
@@ -56,6 +57,12 @@ pub fn draw_dashboard(f: &mut Frame, app: &App, theme: &Theme, tier: CapabilityT
             // Full screen error detail
             draw_error_detail(f, app, theme, tier, chunks[1]);
         }
+        AppMode::EventBrowser => {
+            draw_event_browser(f, app, theme, tier, chunks[1]);
+        }
+        AppMode::Projections => {
+            draw_projections(f, app, theme, tier, chunks[1]);
+        }
         AppMode::Compiling => {
             // Show VU meters during compilation (mixing desk view)
             let main_chunks = Layout::default()
@@ -0,0 +1,195 @@
+//! Event browser widget.
+//!
+//! ┌ STREAMS ─────────────┬ TAIL  prefix: todo  tenant: all ─────────┐
+//! │ › todo-1  acme  r3   │ › 42  TodoCompleted  todo-1  acme  r3    │
+//! │ · todo-2  acme  r1   │ · 41  TodoCreated    todo-2  acme  r1    │
+//! │                      ├ PAYLOAD ─────────────────────────────────┤
+//! │                      │ {                                        │
+//! │                      │   "type": "TodoCompleted"                │
+//! └──────────────────────┴──────────────────────────────────────────┘
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Style,
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::tui::app::App;
+use crate::tui::browser::{BrowserPane, EventBrowser};
+use crate::tui::capabilities::CapabilityTier;
+use crate::tui::theme::{SymbolSet, Theme};
+
+/// Draw the event browser with tier-appropriate rendering.
+pub fn draw_event_browser(f: &mut Frame, app: &App, theme: &Theme, tier: CapabilityTier, area: Rect) {
+    let Some(browser) = &app.browser else {
+        return;
+    };
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(35),  // Streams
+            Constraint::Percentage(65),  // Tail + payload
+        ])
+        .split(area);
+    let right = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage(50),  // Tail
+            Constraint::Percentage(50),  // Payload
+        ])
+        .split(columns[1]);
+
+    draw_streams(f, browser, theme, tier, columns[0]);
+    draw_tail(f, browser, theme, tier, right[0]);
+    draw_payload(f, browser, theme, tier, right[1]);
+}
+
+/// Panel block, highlighted when focused.
+fn panel<'a>(title: String, focused: bool, theme: &Theme, tier: CapabilityTier) -> Block<'a> {
+    // Use rounded borders for Premium tier
+    let border_set = match tier {
+        CapabilityTier::Premium => border::ROUNDED,
+        _ => border::PLAIN,
+    };
+    let border_style = if focused { theme.accent() } else { theme.border() };
+
+    Block::default()
+        .title(Span::styled(title, theme.header()))
+        .borders(Borders::ALL)
+        .border_set(border_set)
+        .border_style(border_style)
+}
+
+/// Rows around `selected` that fit in `height`.
+fn visible(selected: usize, height: usize) -> std::ops::Range<usize> {
+    let start = selected.saturating_sub(height.saturating_sub(1));
+    start..start + height
+}
+
+fn draw_streams(f: &mut Frame, browser: &EventBrowser, theme: &Theme, tier: CapabilityTier, area: Rect) {
+    let syms = SymbolSet::for_tier(tier);
+    let streams = browser.streams();
+    let focused = browser.pane == BrowserPane::Streams;
+
+    let block = panel(format!("STREAMS ({})", streams.len()), focused, theme, tier);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let range = visible(browser.selected_stream, inner.height as usize);
+    let lines: Vec<Line> = streams
+        .iter()
+        .enumerate()
+        .skip(range.start)
+        .take(range.len())
+        .map(|(i, stream)| {
+            let selected = focused && i == browser.selected_stream;
+            let style = if selected { theme.selected() } else { theme.text() };
+            Line::from(vec![
+                Span::styled(if selected { syms.arrow } else { syms.dot }, style),
+                Span::styled(format!(" {}", stream.stream), style),
+                Span::styled(format!("  {}  r{}", stream.tenant, stream.revision), theme.muted()),
+            ])
+        })
+        .collect();
+
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+fn draw_tail(f: &mut Frame, browser: &EventBrowser, theme: &Theme, tier: CapabilityTier, area: Rect) {
+    let syms = SymbolSet::for_tier(tier);
+    let events = browser.events();
+    let focused = browser.pane == BrowserPane::Tail;
+
+    let prefix = if browser.editing_prefix {
+        format!("{}_", browser.prefix)
+    } else if browser.prefix.is_empty() {
+        "*".to_string()
+    } else {
+        browser.prefix.clone()
+    };
+    let title = format!(
+        "TAIL  head {}  prefix: {}  tenant: {}",
+        browser.scan.head,
+        prefix,
+        browser.tenant.as_deref().unwrap_or("all")
+    );
+
+    let block = panel(title, focused, theme, tier);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    if let Some(reason) = &browser.stopped {
+        let msg = Paragraph::new(Line::from(vec![
+            Span::styled(syms.cross, theme.error()),
+            Span::styled(format!(" {}", reason), theme.error()),
+        ]));
+        f.render_widget(msg, inner);
+        return;
+    }
+
+    if events.is_empty() {
+        let msg = Paragraph::new(Line::from(vec![
+            Span::styled(syms.dot, theme.muted()),
+            Span::styled(" waiting for events", theme.muted()),
+        ]));
+        f.render_widget(msg, inner);
+        return;
+    }
+
+    let range = visible(browser.selected_event, inner.height as usize);
+    let lines: Vec<Line> = events
+        .iter()
+        .enumerate()
+        .skip(range.start)
+        .take(range.len())
+        .map(|(i, event)| {
+            let selected = focused && i == browser.selected_event;
+            let style = if selected { theme.selected() } else { theme.text() };
+            Line::from(vec![
+                Span::styled(if selected { syms.arrow } else { syms.dot }, style),
+                Span::styled(format!(" {:>6}  ", event.pos), theme.muted()),
+                Span::styled(event.event_type.as_deref().unwrap_or("(untyped)"), style),
+                Span::styled(
+                    format!("  {}  {}  r{}", event.stream, event.tenant, event.rev),
+                    theme.muted(),
+                ),
+            ])
+        })
+        .collect();
+
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+fn draw_payload(f: &mut Frame, browser: &EventBrowser, theme: &Theme, tier: CapabilityTier, area: Rect) {
+    let block = panel("PAYLOAD".to_string(), false, theme, tier);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let Some(data) = browser.current_event().and_then(|event| event.data.as_ref()) else {
+        return;
+    };
+
+    let pretty = serde_json::to_string_pretty(data).unwrap_or_default();
+    let lines: Vec<Line> = pretty.lines().map(|line| json_line(line, theme)).collect();
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+}
+
+/// Color one line of pretty-printed JSON: keys bright, values muted.
+fn json_line<'a>(line: &'a str, theme: &Theme) -> Line<'a> {
+    let value_style: Style = theme.muted();
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+
+    match trimmed.strip_prefix('"').and_then(|rest| rest.find("\": ").map(|end| end + 1)) {
+        Some(key_end) => Line::from(vec![
+            Span::raw(indent),
+            Span::styled(&trimmed[..=key_end], theme.accent()),
+            Span::styled(&trimmed[key_end + 1..], value_style),
+        ]),
+        None => Line::from(vec![Span::raw(indent), Span::styled(trimmed, value_style)]),
+    }
+}
@@ -100,7 +100,17 @@ pub fn draw_input(f: &mut Frame, app: &App, theme: &Theme, tier: CapabilityTier,
         }
     } else {
         // Inactive - show helpful hint with highlighted key
-        if app.input.buffer.is_empty() {
+        if matches!(app.mode, AppMode::EventBrowser) {
+            for (key, action) in [("[tab]", " pane  "), ("[/]", " prefix  "), ("[t]", " tenant  "), ("[q]", " close")] {
+                spans.push(Span::styled(key, theme.accent()));
+                spans.push(Span::styled(action, theme.muted()));
+            }
+        } else if matches!(app.mode, AppMode::Projections) {
+            for (key, action) in [("[j/k]", " select  "), ("[r]", " rebuild  "), ("[q]", " close")] {
+                spans.push(Span::styled(key, theme.accent()));
+                spans.push(Span::styled(action, theme.muted()));
+            }
+        } else if app.input.buffer.is_empty() {
             spans.push(Span::styled("press ", theme.muted()));
             spans.push(Span::styled("/", theme.accent())); // Highlight the key
             spans.push(Span::styled(" to enter command", theme.muted()));
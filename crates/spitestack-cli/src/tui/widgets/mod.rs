@@ -4,9 +4,11 @@
 
 mod dashboard;
 mod errors;
+mod events;
 mod input;
 mod music;
 mod output;
+mod projections;
 mod splash;
 mod status;
 mod vinyl;
@@ -14,9 +16,11 @@ mod vu_meter;
 
 pub use dashboard::draw_dashboard;
 pub use errors::draw_errors;
+pub use events::draw_event_browser;
 pub use input::draw_input;
 pub use music::draw_music_mode;
 pub use output::draw_output;
+pub use projections::draw_projections;
 pub use splash::draw_splash;
 pub use status::draw_status;
 pub use vinyl::{
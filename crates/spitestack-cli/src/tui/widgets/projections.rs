@@ -0,0 +1,109 @@
+//! Projection status widget.
+//!
+//! ┌ PROJECTIONS  head 1042 ──────────────────────────────────────────┐
+//! │ › todo_stats       acme     1042   lag 0                         │
+//! │ · todo_stats       globex    998   lag 44                        │
+//! │ x user_directory   acme      310   lag 732  unique violation...  │
+//! │ · audit_log        no data yet                                   │
+//! └──────────────────────────────────────────────────────────────────┘
+
+use ratatui::{
+    layout::Rect,
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::tui::app::App;
+use crate::tui::capabilities::CapabilityTier;
+use crate::tui::theme::{SymbolSet, Theme};
+
+/// Draw the projection panel with tier-appropriate rendering.
+pub fn draw_projections(f: &mut Frame, app: &App, theme: &Theme, tier: CapabilityTier, area: Rect) {
+    let Some(panel) = &app.projections else {
+        return;
+    };
+    let syms = SymbolSet::for_tier(tier);
+    let snapshot = &panel.snapshot;
+
+    // Use rounded borders for Premium tier
+    let border_set = match tier {
+        CapabilityTier::Premium => border::ROUNDED,
+        _ => border::PLAIN,
+    };
+    let head = snapshot.head.map(|h| h.to_string()).unwrap_or_else(|| "?".to_string());
+    let block = Block::default()
+        .title(Span::styled(format!("PROJECTIONS  head {}", head), theme.header()))
+        .borders(Borders::ALL)
+        .border_set(border_set)
+        .border_style(theme.border());
+
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    if panel.last_refresh.is_none() {
+        let msg = Paragraph::new(Line::from(vec![
+            Span::styled(syms.dot, theme.muted()),
+            Span::styled(" reading checkpoints...", theme.muted()),
+        ]));
+        f.render_widget(msg, inner);
+        return;
+    }
+
+    let name_width = snapshot.registered.iter().map(|n| n.len()).max().unwrap_or(0);
+    let mut lines: Vec<Line> = Vec::new();
+
+    for (i, status) in snapshot.statuses.iter().enumerate() {
+        let selected = i == panel.selected;
+        let (symbol, style) = if status.last_error.is_some() {
+            (syms.cross, theme.error())
+        } else if selected {
+            (syms.arrow, theme.selected())
+        } else {
+            (syms.dot, theme.text())
+        };
+
+        let progress = if status.checkpoint < 0 {
+            "rebuilding".to_string()
+        } else {
+            match snapshot.lag(status) {
+                Some(lag) => format!("{:>8}   lag {}", status.checkpoint, lag),
+                None => format!("{:>8}", status.checkpoint),
+            }
+        };
+
+        let row_style = if selected { theme.selected() } else { theme.text() };
+        let mut spans = vec![
+            Span::styled(symbol, style),
+            Span::styled(
+                format!(" {:<width$}  {:<12}", status.projection, status.tenant, width = name_width),
+                row_style,
+            ),
+            Span::styled(progress, theme.muted()),
+        ];
+        if status.poisoned > 0 {
+            spans.push(Span::styled(format!("  {} poisoned", status.poisoned), theme.warning()));
+        }
+        if let Some(error) = &status.last_error {
+            spans.push(Span::styled(format!("  {}", error), theme.error()));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    for name in snapshot.idle() {
+        lines.push(Line::from(vec![
+            Span::styled(syms.dot, theme.muted()),
+            Span::styled(format!(" {:<width$}  no data yet", name, width = name_width), theme.muted()),
+        ]));
+    }
+
+    for problem in &snapshot.problems {
+        lines.push(Line::from(vec![
+            Span::styled(syms.dagger, theme.warning()),
+            Span::styled(format!(" {}", problem), theme.warning()),
+        ]));
+    }
+
+    f.render_widget(Paragraph::new(lines), inner);
+}
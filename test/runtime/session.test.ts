@@ -0,0 +1,169 @@
+/**
+ * Behavior tests for the cookie session runtime module.
+ *
+ * Sessions are driven through the same entry points the generated router
+ * uses, against an in-memory event log, so cookies, CSRF and origin checks,
+ * logout and replays across instances are exercised end to end.
+ */
+
+import { describe, expect, it, beforeEach, afterEach } from "bun:test";
+import { Database } from "bun:sqlite";
+import { mkdtemp, rm } from "fs/promises";
+import { tmpdir } from "os";
+import { join } from "path";
+import type { LogEvent } from "../../crates/spite-compiler/runtime/event-log";
+import {
+  createSessionAuth,
+  handleSessionLogin,
+  handleSessionLogout,
+  type SessionAuth,
+  type SessionLog,
+} from "../../crates/spite-compiler/runtime/session";
+
+const ORIGIN = "http://localhost:3000";
+
+/** Event log shared by every instance of a test, like one SpiteDB store. */
+class MemoryLog implements SessionLog {
+  private events: (LogEvent & { tenant: string })[] = [];
+
+  async append(streamId: string, _commandId: string, expectedRev: number, events: Buffer[], tenant: string) {
+    const rev = await this.getStreamRevision(streamId, tenant);
+    if (rev !== expectedRev) throw new Error(`expected revision ${expectedRev}, stream is at ${rev}`);
+    events.forEach((data, i) => {
+      this.events.push({
+        globalPos: this.events.length + 1,
+        streamId,
+        streamRev: rev + i + 1,
+        tenantHash: tenant.length,
+        timestampMs: Date.now(),
+        data,
+        tenant,
+      });
+    });
+    return { lastPos: this.events.length };
+  }
+
+  async getStreamRevision(streamId: string, tenant: string) {
+    return this.events.filter((e) => e.streamId === streamId && e.tenant === tenant).length;
+  }
+
+  async readGlobal(fromPos: number, limit: number) {
+    return this.events.filter((e) => Number(e.globalPos) >= fromPos).slice(0, limit);
+  }
+
+  async readStream(streamId: string, fromRev: number, limit: number, tenant: string) {
+    return this.events
+      .filter((e) => e.streamId === streamId && e.tenant === tenant && Number(e.streamRev) >= fromRev)
+      .slice(0, limit);
+  }
+}
+
+function loginResponse(sub: string): Response {
+  return new Response(JSON.stringify({ status: "success", user: { sub, orgs: {} } }), { status: 200 });
+}
+
+/** Log in through `sessions`, returning the cookie header and CSRF token. */
+async function login(sessions: SessionAuth, sub = "user-1") {
+  const response = await handleSessionLogin(sessions, loginResponse(sub));
+  const cookies = response.headers.getSetCookie().map((c) => c.split(";")[0]);
+  const { csrfToken } = (await response.json()) as { csrfToken: string };
+  return { cookie: cookies.join("; "), csrfToken };
+}
+
+function request(method: string, cookie: string, headers: Record<string, string> = {}): Request {
+  return new Request(`${ORIGIN}/auth/logout`, { method, headers: { Cookie: cookie, ...headers } });
+}
+
+describe("Runtime: cookie sessions", () => {
+  let log: MemoryLog;
+  let dirs: string[];
+
+  async function instance(replayIntervalMs = 1000): Promise<SessionAuth> {
+    const dataDir = await mkdtemp(join(tmpdir(), "spite-sessions-"));
+    dirs.push(dataDir);
+    return createSessionAuth(log, { dataDir, replayIntervalMs });
+  }
+
+  beforeEach(() => {
+    log = new MemoryLog();
+    dirs = [];
+  });
+
+  afterEach(async () => {
+    for (const dir of dirs) await rm(dir, { recursive: true, force: true });
+  });
+
+  it("authenticates requests with the session cookie", async () => {
+    const sessions = await instance();
+    const { cookie } = await login(sessions);
+
+    const result = await sessions.verifyRequest(request("GET", cookie));
+    expect(result.ok).toBe(true);
+    expect(result.ok && result.user.sub).toBe("user-1");
+
+    const anonymous = await sessions.verifyRequest(request("GET", ""));
+    expect(anonymous.ok).toBe(false);
+  });
+
+  it("passes responses that are not logins through", async () => {
+    const sessions = await instance();
+    const mfaRequired = new Response(JSON.stringify({ status: "mfa_required", mfaToken: "t" }), { status: 200 });
+
+    const response = await handleSessionLogin(sessions, mfaRequired);
+    expect(response.headers.getSetCookie()).toEqual([]);
+    expect(((await response.json()) as { status: string }).status).toBe("mfa_required");
+  });
+
+  it("requires the CSRF token and a same-origin request to change state", async () => {
+    const sessions = await instance();
+    const { cookie, csrfToken } = await login(sessions);
+
+    expect((await sessions.verifyRequest(request("POST", cookie))).ok).toBe(false);
+    expect((await sessions.verifyRequest(request("POST", cookie, { "X-CSRF-Token": "forged" }))).ok).toBe(false);
+    const crossOrigin = request("POST", cookie, { "X-CSRF-Token": csrfToken, Origin: "https://evil.example" });
+    expect((await sessions.verifyRequest(crossOrigin)).ok).toBe(false);
+
+    const sameOrigin = request("POST", cookie, { "X-CSRF-Token": csrfToken, Origin: ORIGIN });
+    expect((await sessions.verifyRequest(sameOrigin)).ok).toBe(true);
+  });
+
+  it("only logs out with the CSRF token", async () => {
+    const sessions = await instance();
+    const { cookie, csrfToken } = await login(sessions);
+
+    const forged = await handleSessionLogout(sessions, request("POST", cookie, { Origin: "https://evil.example" }));
+    expect(forged.status).toBe(403);
+    expect((await sessions.verifyRequest(request("GET", cookie))).ok).toBe(true);
+
+    const logout = await handleSessionLogout(sessions, request("POST", cookie, { "X-CSRF-Token": csrfToken }));
+    expect(logout.status).toBe(200);
+    expect(logout.headers.getSetCookie().every((c) => c.includes("Max-Age=0"))).toBe(true);
+    expect((await sessions.verifyRequest(request("GET", cookie))).ok).toBe(false);
+  });
+
+  it("sees sessions started and revoked by another instance", async () => {
+    const first = await instance(0);
+    const second = await instance(0);
+    const { cookie, csrfToken } = await login(first);
+
+    expect((await second.verifyRequest(request("GET", cookie))).ok).toBe(true);
+
+    await handleSessionLogout(first, request("POST", cookie, { "X-CSRF-Token": csrfToken }));
+    expect((await second.verifyRequest(request("GET", cookie))).ok).toBe(false);
+  });
+
+  it("rebuilds the table from the event log", async () => {
+    const sessions = await instance(0);
+    const kept = await login(sessions, "user-1");
+    const revoked = await login(sessions, "user-2");
+    await handleSessionLogout(sessions, request("POST", revoked.cookie, { "X-CSRF-Token": revoked.csrfToken }));
+
+    // A negative checkpoint asks for a rebuild, as `spite` does for projections
+    const table = new Database(join(dirs[0], "sessions.db"));
+    table.run("UPDATE sessions_position SET last_event_id = -1");
+    table.close();
+
+    expect((await sessions.verifyRequest(request("GET", kept.cookie))).ok).toBe(true);
+    expect((await sessions.verifyRequest(request("GET", revoked.cookie))).ok).toBe(false);
+  });
+});